@@ -1,12 +1,13 @@
-use superdsp::objects::object::DSPObject;
+use superdsp::objects::flowgraph::Flowgraph;
 use superdsp::radios;
 
 fn main() {
-    let mut gen = superdsp::objects::wave_gen_complex::WaveStepGenComplex::new(250_000.0, 1.0, 0.0, 1_000_000.0);
-    let mut sink = radios::bladerf::sink::BladeRfSink::new(915000000, 1_000_000, 1_000_000, 1_000_000, 1024);
+    let mut flowgraph = Flowgraph::new();
+
+    let gen = flowgraph.add(superdsp::objects::wave_gen_complex::WaveStepGenComplex::new(250_000.0, 1.0, 0.0, 1_000_000.0, 1024));
+    let sink = flowgraph.add(radios::bladerf::sink::BladeRfSink::new(915000000, 1_000_000, 1_000_000, 1_000_000, 1024));
     
-    let s = gen.get_bus();
-    sink.set_bus(s);
+    flowgraph.connect(gen.output(0), sink.input(0));
 
-    gen.start();
-}
\ No newline at end of file
+    flowgraph.run().unwrap();
+}
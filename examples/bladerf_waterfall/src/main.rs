@@ -1,17 +1,23 @@
 use superdsp::gui::time_chart_complex::TimeChartComplex;
 use superdsp::gui::waterfall::Waterfall;
-use superdsp::objects::object::DSPObject;
+use superdsp::math::window::Window;
+use superdsp::objects::flowgraph::Flowgraph;
+use superdsp::objects::scheduler::Scheduler;
 use superdsp::radios;
 
 fn main() {
-    let mut src = radios::bladerf::src::BladeRfSrc::new(915000000, 1_000_000, 1_000_000, 1_000_000, 1024);
-    let mut chart = TimeChartComplex::new();
-    let mut waterfall = Waterfall::new(1024);
-    
-    let s = src.get_bus();
+    let chart = TimeChartComplex::new();
+    let waterfall = Waterfall::new(1024, Window::Hann);
 
-    waterfall.set_bus(s);
-    chart.set_bus(s);
+    // The GUI draws clones of the charts, which share their sample buffers with the flowgraph
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(radios::bladerf::src::BladeRfSrc::new(915000000, 1_000_000, 1_000_000, 1_000_000, 1024));
+    let chart_block = flowgraph.add(chart.clone());
+    let waterfall_block = flowgraph.add(waterfall.clone());
 
-    superdsp::objects::GUIExecutor::run(vec![Box::new(waterfall), Box::new(chart)], Box::new(src));
-}
\ No newline at end of file
+    flowgraph.connect(src.output(0), waterfall_block.input(0));
+    flowgraph.connect(src.output(0), chart_block.input(0));
+
+    // Each block gets its own thread so drawing the waterfall never holds up the radio
+    superdsp::objects::GUIExecutor::run_threaded(vec![Box::new(waterfall), Box::new(chart)], flowgraph, Scheduler::new(3, 16));
+}
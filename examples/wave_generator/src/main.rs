@@ -1,16 +1,16 @@
 use superdsp::gui::time_chart::TimeChart;
 use superdsp::objects::GUIExecutor;
-use superdsp::objects::object::DSPObject;
+use superdsp::objects::flowgraph::Flowgraph;
 use superdsp::objects::wave_gen_time::WaveStepGenTime;
 
 fn main() {
-    let mut wave_step_gen = WaveStepGenTime::new(440.0, 1.0, 0.0, 44100.0);
+    let chart = TimeChart::new();
     
-    let mut chart = TimeChart::new();
+    let mut flowgraph = Flowgraph::new();
+    let wave_step_gen = flowgraph.add(WaveStepGenTime::new(440.0, 1.0, 0.0, 44100.0, 441));
+    let chart_block = flowgraph.add(chart.clone());
     
+    flowgraph.connect(wave_step_gen.output(0), chart_block.input(0));
     
-    let s = wave_step_gen.get_bus();
-    chart.set_bus(s);
-    
-    GUIExecutor::run(vec![Box::new(chart)], Box::new(wave_step_gen));
-}
\ No newline at end of file
+    GUIExecutor::run(vec![Box::new(chart)], flowgraph);
+}
@@ -1,14 +1,17 @@
 use superdsp::gui::waterfall::Waterfall;
+use superdsp::math::window::Window;
 use superdsp::objects::GUIExecutor;
-use superdsp::objects::object::DSPObject;
+use superdsp::objects::flowgraph::Flowgraph;
 use superdsp::objects::wave_gen_time_complex::WaveStepGenTimeComplex;
 
 fn main() {
-    let mut waterfall = Waterfall::new(1024);
-    let mut gen = WaveStepGenTimeComplex::new(8.0, 1.0, 0.0, 16.0);
+    let waterfall = Waterfall::new(1024, Window::Hann);
     
-    waterfall.set_bus(gen.get_bus());
+    let mut flowgraph = Flowgraph::new();
+    let gen = flowgraph.add(WaveStepGenTimeComplex::new(8.0, 1.0, 0.0, 16.0, 1));
+    let waterfall_block = flowgraph.add(waterfall.clone());
+    flowgraph.connect(gen.output(0), waterfall_block.input(0));
     
-    GUIExecutor::run(vec![Box::new(waterfall)], Box::new(gen))
+    GUIExecutor::run(vec![Box::new(waterfall)], flowgraph)
     
-}
\ No newline at end of file
+}
@@ -8,17 +8,16 @@ use plotters_iced::{Chart, ChartBuilder, ChartWidget, DrawingBackend};
 use spin::RwLock;
 
 use crate::gui::{DSPChart, Message};
-use crate::objects::object::{Bus, DSPObject, Type};
+use crate::objects::object::{DSPObject, Samples, Type};
 
 #[derive(Clone)]
 pub struct TimeChart {
     buffer: Arc<RwLock<Vec<f64>>>,
-    bus: Bus<'static>,
 }
 
 impl TimeChart {
     pub fn new() -> TimeChart {
-        TimeChart { buffer: Arc::new(RwLock::new(vec![0.0; 50])), bus: Bus::new_f64() }
+        TimeChart { buffer: Arc::new(RwLock::new(vec![0.0; 50])) }
     }
 }
 
@@ -61,36 +60,26 @@ impl Chart<Message> for TimeChart {
 }
 
 impl DSPObject for TimeChart {
-    fn return_type(&self) -> Type {
-        Type::F64
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::F64]
     }
 
-    fn input_type(&self) -> Type {
-        Type::F64
+    fn output_types(&self) -> Vec<Type> {
+        vec![]
     }
 
-    fn get_bus(&mut self) -> &mut Bus<'static> {
-        &mut self.bus
-    }
-
-    fn set_bus(&mut self, bus: &mut Bus<'static>) {
-        bus.subscribe(self);
-        self.bus = *bus;
-    }
+    fn work(&mut self, inputs: &[Samples], _outputs: &mut [Samples]) {
+        let mut buffer = self.buffer.write();
 
-    fn process(&mut self) {
-        // Put input buffer into buffer
-        self.buffer.write().push(*self.bus.buffer_f64.unwrap().read());
+        // Put input block into buffer
+        buffer.extend_from_slice(inputs[0].as_f64());
 
-        // Remove the first element if buffer is too long
-        if self.buffer.read().len() > 50 {
-            self.buffer.write().remove(0);
+        // Remove the oldest elements if buffer is too long
+        if buffer.len() > 50 {
+            let excess = buffer.len() - 50;
+            buffer.drain(..excess);
         }
     }
-
-    fn start(&mut self) {
-        panic!("Charts can not be root object");
-    }
 }
 
 impl DSPChart for TimeChart {
@@ -10,19 +10,17 @@ use plotters_iced::{Chart, ChartBuilder, ChartWidget, DrawingBackend};
 use spin::Mutex;
 
 use crate::gui::{DSPChart, Message};
-use crate::objects::object::{Bus, DSPObject, Type};
+use crate::objects::object::{DSPObject, Samples, Type};
 
 #[derive(Clone)]
 pub struct TimeChartComplex {
     buffer: Arc<Mutex<Vec<Complex<f64>>>>,
-    bus: Bus<'static>,
 }
 
 impl TimeChartComplex {
     pub fn new() -> TimeChartComplex {
         TimeChartComplex { 
-            buffer: Arc::new(Mutex::new(vec![Complex::new(0.0,0.0); 50])),
-            bus: Bus::new_complex()
+            buffer: Arc::new(Mutex::new(vec![Complex::new(0.0,0.0); 50]))
         }
     }
 }
@@ -72,34 +70,24 @@ impl Chart<Message> for TimeChartComplex {
 }
 
 impl DSPObject for TimeChartComplex {
-    fn return_type(&self) -> Type {
-        Type::Complex
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
     }
 
-    fn input_type(&self) -> Type {
-        Type::Complex
+    fn output_types(&self) -> Vec<Type> {
+        vec![]
     }
 
-    fn get_bus(&mut self) -> &mut Bus<'static> {
-        &mut self.bus
-    }
-
-    fn set_bus(&mut self, bus: &mut Bus<'static>) {
-        self.bus = *bus;
-        bus.subscribe(self);
-    }
-
-    fn start(&mut self) {
-        panic!("Charts can not be root object");
-    }
+    fn work(&mut self, inputs: &[Samples], _outputs: &mut [Samples]) {
+        let mut buffer = self.buffer.lock();
 
-    fn process(&mut self) {
-        // Put input buffer into buffer
-        self.buffer.lock().push(*self.bus.buffer_complex.unwrap().read());
+        // Put input block into buffer
+        buffer.extend_from_slice(inputs[0].as_complex());
 
-        // Remove the first element if buffer is too long
-        if self.buffer.lock().len() > 50 {
-            self.buffer.lock().remove(0);
+        // Remove the oldest elements if buffer is too long
+        if buffer.len() > 50 {
+            let excess = buffer.len() - 50;
+            buffer.drain(..excess);
         }
     }
 }
@@ -7,31 +7,35 @@ use std::vec;
 use iced::{Command, Length};
 use iced::widget::Image;
 use iced::widget::image::Handle;
-use ndarray::{Array1, Axis};
-use ndarray::linalg::Dot;
+use ndarray::Array1;
 use num::Complex;
 use plotters_iced::{Chart, ChartBuilder, DrawingBackend};
 use spin::RwLock;
 
 use crate::gui::{DSPChart, Message};
 use crate::math;
-use crate::math::fourier::fft_shift;
-use crate::objects::object::{Bus, DSPObject, Type};
+use crate::math::fourier::{fftshift, FftPlan};
+use crate::math::window::Window;
+use crate::objects::object::{DSPObject, Samples, Type};
 
 #[derive(Clone)]
 pub struct Waterfall {
     buffer: Arc<RwLock<Array1<Complex<f64>>>>,
 
-    dft_matrix: ndarray::Array2<Complex<f64>>,
-    
-    bus: Bus<'static>,
+    plan: FftPlan,
+    window: Arc<Vec<f64>>,
 
     pixels: Arc<RwLock<VecDeque<u8>>>,
     width_and_width: usize,
 }
 
 impl Waterfall {
-    pub fn new(buff_size: usize) -> Waterfall {
+    /// Create a new waterfall
+    ///
+    /// - buff_size: usize - number of samples in each FFT, which is also the width and height of the
+    ///   image in pixels
+    /// - window: Window - window applied to each block before the FFT
+    pub fn new(buff_size: usize, window: Window) -> Waterfall {
         let mut pixels = vec![0; buff_size * buff_size * 4];
         
         for i in 0..buff_size * buff_size {
@@ -41,20 +45,19 @@ impl Waterfall {
             pixels[4 * i + 3] = 255;
         }
 
-        let dft_matrix = fft_shift(buff_size).dot(&math::fourier::make_basis(buff_size));
-        
         let mut w = Waterfall {
             buffer: Arc::new(RwLock::new(<Array1<Complex<f64>>>::from(vec![Complex::new(0.0, 0.0); buff_size]))),
-            dft_matrix,
+            plan: FftPlan::new(buff_size),
+            window: Arc::new(scaled_window(window, buff_size)),
             pixels: Arc::new(RwLock::new(VecDeque::from(pixels))),
             width_and_width: buff_size,
-            
-            bus: Bus::new_complex(),
         };
         
         let w_clone = w.clone();
         
         spawn(move || {
+            let mut spectrum = vec![Complex::new(0.0, 0.0); w_clone.width_and_width];
+
             loop {
                 // lock
                 let mut locked_pixels = w_clone.pixels.write();
@@ -65,13 +68,18 @@ impl Waterfall {
                     continue;
                 }
 
-                // Preform dft on buffer
-                let dfted = w_clone.dft_matrix.dot(&locked_buffer.view());
-                let slice = dfted.as_slice().unwrap();
+                // Preform fft on buffer
+                for ((x, sample), w) in spectrum.iter_mut().zip(locked_buffer.iter()).zip(w_clone.window.iter()) {
+                    *x = sample * w;
+                }
+                w_clone.plan.fft(&mut spectrum);
+
+                // Put the lowest frequency on the left
+                fftshift(&mut spectrum);
 
                 // Add new data
                 for i in 0..w_clone.width_and_width {
-                    let val = (slice[i].norm_sqr() * 255.0) as u8;
+                    let val = (spectrum[i].norm_sqr() * 255.0) as u8;
 
                     locked_pixels[4 * i] = val;
                     locked_pixels[4 * i + 1] = val;
@@ -88,9 +96,18 @@ impl Waterfall {
     }
 }
 
+/// Window coefficients divided by their coherent gain, so a tone shows up just as bright whichever
+/// window is used
+fn scaled_window(window: Window, n: usize) -> Vec<f64> {
+    let coefficients = window.periodic(n);
+    let gain = math::window::coherent_gain(&coefficients);
+
+    coefficients.iter().map(|w| w / gain).collect()
+}
+
 impl Default for Waterfall {
     fn default() -> Self {
-        Self::new(1024)
+        Self::new(1024, Window::Hann)
     }
 }
 
@@ -102,31 +119,27 @@ impl Chart<Message> for Waterfall {
 }
 
 impl DSPObject for Waterfall {
-    fn return_type(&self) -> Type {
-        Type::Complex
-    }
-
-    fn input_type(&self) -> Type {
-        Type::Complex
-    }
-    
-    fn get_bus(&mut self) -> &mut Bus<'static> {
-        &mut self.bus
-    }
-    
-    fn set_bus(&mut self, bus: &mut Bus<'static>) {
-        self.bus = *bus;
-        bus.subscribe(self as *mut dyn DSPObject);
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
     }
 
-    fn process(&mut self) {
-        // Put input buffer into buffer
-        self.buffer.write().remove_index(Axis(0),0);
-        self.buffer.write().push(Axis(0), ndarray::arr0(*self.bus.buffer_complex.unwrap().read()).view()).unwrap();
+    fn output_types(&self) -> Vec<Type> {
+        vec![]
     }
 
-    fn start(&mut self) {
-        panic!("Charts can not be root object");
+    fn work(&mut self, inputs: &[Samples], _outputs: &mut [Samples]) {
+        let input = inputs[0].as_complex();
+        let mut buffer = self.buffer.write();
+        let buffer = buffer.as_slice_mut().unwrap();
+        let n = buffer.len();
+
+        // Shift the newest samples into the end of the buffer
+        if input.len() >= n {
+            buffer.copy_from_slice(&input[input.len() - n..]);
+        } else {
+            buffer.copy_within(input.len().., 0);
+            buffer[n - input.len()..].copy_from_slice(input);
+        }
     }
 }
 
@@ -0,0 +1,116 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+
+use crate::math::filter::{check, kaiser_parameters, FilterError};
+use crate::math::window::Window;
+
+/// Magnitude response of a CIC filter scaled to unity gain at DC. `frequency` is a fraction of the
+/// high sample rate, the input of a decimator or the output of an interpolator.
+pub fn response(order: usize, delay: usize, rate: usize, frequency: f64) -> f64 {
+    let length = (rate * delay) as f64;
+    let denominator = length * libm::sin(PI * frequency);
+
+    if denominator == 0.0 {
+        return 1.0;
+    }
+
+    libm::pow((libm::sin(PI * length * frequency) / denominator).abs(), order as f64)
+}
+
+/// Number of bits the integer registers of a CIC filter grow by, which is log2((R·M)^N) rounded
+/// up. Together with the input bits this has to fit in 64 bits.
+pub fn bit_growth(order: usize, delay: usize, rate: usize) -> u32 {
+    libm::ceil(order as f64 * libm::log2((rate * delay) as f64)) as u32
+}
+
+/// Windowed FIR filter that undoes the passband droop of a CIC filter, to run at its low sample
+/// rate
+///
+/// - sample_rate: f64 - low sample rate of the CIC filter, the output of a decimator or the input
+///   of an interpolator
+/// - cutoff: f64 - frequency half way through the transition band, where the response is -6 dB
+/// - width: f64 - width of the transition band
+/// - attenuation: f64 - minimum stopband attenuation in dB
+/// - order: usize - number of integrator and comb stages of the CIC filter
+/// - delay: usize - differential delay of the comb stages
+/// - rate: usize - rate change factor of the CIC filter
+pub fn compensator(sample_rate: f64, cutoff: f64, width: f64, attenuation: f64, order: usize, delay: usize, rate: usize) -> Result<Vec<f64>, FilterError> {
+    check(sample_rate, &[cutoff], width, attenuation)?;
+    if order == 0 || delay == 0 || rate == 0 {
+        return Err(FilterError::InvalidCic { order, delay, rate });
+    }
+
+    let (taps, beta) = kaiser_parameters(sample_rate, width, attenuation);
+    let window = Window::Kaiser(beta).symmetric(taps);
+    let middle = (taps - 1) as f64 / 2.0;
+
+    // Integrate the inverse of the CIC response up to the cutoff with the midpoint rule
+    let points = 64 * taps;
+    let step = cutoff / sample_rate / points as f64;
+    let desired: Vec<(f64, f64)> = (0..points).map(|i| {
+        let f = (i as f64 + 0.5) * step;
+        (f, 1.0 / response(order, delay, rate, f / rate as f64))
+    }).collect();
+
+    let mut coefficients = vec![0.0; taps];
+    for (k, coefficient) in coefficients.iter_mut().enumerate() {
+        let t = k as f64 - middle;
+        let sum: f64 = desired.iter().map(|(f, gain)| gain * libm::cos(2.0 * PI * f * t)).sum();
+        *coefficient = 2.0 * sum * step * window[k];
+    }
+
+    let gain: f64 = coefficients.iter().sum();
+    Ok(coefficients.iter().map(|c| c / gain).collect())
+}
+
+/// Round a sample to a signed integer with `bits` bits, where ±1.0 is full scale
+pub(crate) fn quantize(x: f64, bits: u32) -> i64 {
+    let full_scale = (1i64 << (bits - 1)) as f64;
+    libm::round(x * full_scale).clamp(-full_scale, full_scale - 1.0) as i64
+}
+
+/// A cascade of integrators. The sums are allowed to wrap around, which the combs undo as long as
+/// the final result fits in 64 bits.
+#[derive(Clone)]
+pub(crate) struct Integrators {
+    sums: Vec<i64>,
+}
+
+impl Integrators {
+    pub(crate) fn new(order: usize) -> Integrators {
+        Integrators { sums: vec![0; order] }
+    }
+
+    pub(crate) fn push(&mut self, x: i64) -> i64 {
+        self.sums.iter_mut().fold(x, |x, sum| {
+            *sum = sum.wrapping_add(x);
+            *sum
+        })
+    }
+}
+
+/// A cascade of combs y[n] = x[n] - x[n - delay]
+#[derive(Clone)]
+pub(crate) struct Combs {
+    lines: Vec<Vec<i64>>,
+    next: usize,
+}
+
+impl Combs {
+    pub(crate) fn new(order: usize, delay: usize) -> Combs {
+        Combs { lines: vec![vec![0; delay]; order], next: 0 }
+    }
+
+    pub(crate) fn push(&mut self, x: i64) -> i64 {
+        let next = self.next;
+        let y = self.lines.iter_mut().fold(x, |x, line| {
+            let y = x.wrapping_sub(line[next]);
+            line[next] = x;
+            y
+        });
+
+        self.next = (next + 1) % self.lines.first().map_or(1, |line| line.len());
+        y
+    }
+}
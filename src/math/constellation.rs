@@ -0,0 +1,223 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::f64::consts::PI;
+use core::fmt;
+
+use num::Complex;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConstellationError {
+    /// The number of points is not a power of two of at least 2, or for QAM not an even power
+    InvalidOrder(usize),
+    /// There is not exactly one label for every point
+    LabelCount { points: usize, labels: usize },
+    /// A label is out of range or used twice
+    InvalidLabel(usize),
+    /// Every point is at the origin, so there is no power to normalize
+    ZeroPower,
+    /// An APSK ring has no points or a radius that is not a positive number
+    InvalidRing { points: usize, radius: f64 },
+}
+
+impl fmt::Display for ConstellationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstellationError::InvalidOrder(order) => write!(f, "can not build a constellation with {} points", order),
+            ConstellationError::LabelCount { points, labels } => write!(f, "{} points need as many labels, got {}", points, labels),
+            ConstellationError::InvalidLabel(label) => write!(f, "label {} is out of range or used more than once", label),
+            ConstellationError::ZeroPower => write!(f, "a constellation needs at least one point away from the origin"),
+            ConstellationError::InvalidRing { points, radius } => write!(f, "an APSK ring needs at least one point and a positive radius, got {} points at {}", points, radius),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ConstellationError {}
+
+/// Gray code of `value`, so neighbouring points differ in a single bit
+pub fn gray(value: usize) -> usize {
+    value ^ (value >> 1)
+}
+
+/// The value whose Gray code is `code`
+pub fn gray_inverse(code: usize) -> usize {
+    let mut value = code;
+    let mut shift = code >> 1;
+    while shift != 0 {
+        value ^= shift;
+        shift >>= 1;
+    }
+
+    value
+}
+
+/// Points evenly spread around each ring in turn, counterclockwise from the first point of the
+/// ring
+fn ring_points(rings: &[(usize, f64, f64)]) -> Result<Vec<Complex<f64>>, ConstellationError> {
+    let mut points = Vec::new();
+    for (count, radius, phase) in rings {
+        if *count == 0 || radius.partial_cmp(&0.0) != Some(Ordering::Greater) {
+            return Err(ConstellationError::InvalidRing { points: *count, radius: *radius });
+        }
+
+        points.extend((0..*count).map(|k| Complex::from_polar(*radius, phase + 2.0 * PI * k as f64 / *count as f64)));
+    }
+
+    Ok(points)
+}
+
+/// A set of points in the complex plane and the bits each of them stands for, scaled to unit
+/// average power. Symbols carry log2(order) bits, most significant first, and the label of a
+/// point is the value of its bits.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Constellation {
+    // Indexed by label
+    points: Vec<Complex<f64>>,
+}
+
+impl Constellation {
+    /// Create a constellation from its points and their labels
+    /// - points: Vec<Complex<f64>> - The points, scaled to unit average power here
+    /// - labels: Vec<usize> - The label of each point, using every value below the number of
+    ///   points once
+    pub fn new(points: Vec<Complex<f64>>, labels: Vec<usize>) -> Result<Constellation, ConstellationError> {
+        let order = points.len();
+        if order < 2 || !order.is_power_of_two() {
+            return Err(ConstellationError::InvalidOrder(order));
+        }
+        if labels.len() != order {
+            return Err(ConstellationError::LabelCount { points: order, labels: labels.len() });
+        }
+
+        let mut labelled = vec![None; order];
+        for (point, label) in points.iter().zip(labels.iter()) {
+            match labelled.get_mut(*label) {
+                Some(slot @ None) => *slot = Some(*point),
+                _ => return Err(ConstellationError::InvalidLabel(*label)),
+            }
+        }
+
+        let power = points.iter().map(|point| point.norm_sqr()).sum::<f64>() / order as f64;
+        if power.partial_cmp(&0.0) != Some(Ordering::Greater) {
+            return Err(ConstellationError::ZeroPower);
+        }
+
+        let scale = 1.0 / libm::sqrt(power);
+        Ok(Constellation {
+            points: labelled.iter().map(|point| point.unwrap() * scale).collect(),
+        })
+    }
+
+    /// Square QAM, e.g. 16, 64 or 256 points, Gray mapped along each axis so every point differs
+    /// from its nearest neighbours in a single bit. The first half of the bits picks the column
+    /// and the second half the row.
+    /// - order: usize - The number of points, an even power of two
+    pub fn qam(order: usize) -> Result<Constellation, ConstellationError> {
+        let bits = order.trailing_zeros() as usize;
+        if order < 4 || !order.is_power_of_two() || !bits.is_multiple_of(2) {
+            return Err(ConstellationError::InvalidOrder(order));
+        }
+
+        let half = bits / 2;
+        let side = 1 << half;
+        let level = |i: usize| (2 * i) as f64 - (side - 1) as f64;
+
+        let mut points = Vec::with_capacity(order);
+        let mut labels = Vec::with_capacity(order);
+        for column in 0..side {
+            for row in 0..side {
+                points.push(Complex::new(level(column), level(row)));
+                labels.push((gray(column) << half) | gray(row));
+            }
+        }
+
+        Constellation::new(points, labels)
+    }
+
+    /// Amplitude and phase shift keying, with points evenly spread around concentric rings.
+    /// Labels count up around each ring, counterclockwise from its first point, from the
+    /// innermost ring outwards. Use [`Constellation::new`] for other labellings.
+    /// - rings: &[(usize, f64, f64)] - The number of points, the radius and the angle of the first
+    ///   point (in radians) of each ring
+    pub fn apsk(rings: &[(usize, f64, f64)]) -> Result<Constellation, ConstellationError> {
+        let points = ring_points(rings)?;
+        let labels = (0..points.len()).collect();
+        Constellation::new(points, labels)
+    }
+
+    /// 16APSK with 4 inner and 12 outer points and the bit mapping of DVB-S2, where neighbours on
+    /// either ring differ in a single bit
+    /// - ratio: f64 - The outer radius over the inner radius, from 2.57 to 3.15 depending on the
+    ///   code rate
+    pub fn apsk16(ratio: f64) -> Result<Constellation, ConstellationError> {
+        let points = ring_points(&[(4, 1.0, PI / 4.0), (12, ratio, PI / 12.0)])?;
+        let labels = vec![12, 14, 15, 13, 4, 0, 8, 10, 2, 6, 7, 3, 11, 9, 1, 5];
+        Constellation::new(points, labels)
+    }
+
+    /// 32APSK with 4, 12 and 16 points on three rings as in DVB-S2. The outer ring carries a one
+    /// in the first bit and the others a zero, and the rest of the bits follow a Gray code around
+    /// each ring, so neighbours on any ring differ in a single bit. The inner and middle rings
+    /// split one 16 entry Gray code between them: the inner ring takes its middle four entries.
+    /// - middle: f64 - The middle radius over the inner radius
+    /// - outer: f64 - The outer radius over the inner radius
+    pub fn apsk32(middle: f64, outer: f64) -> Result<Constellation, ConstellationError> {
+        let points = ring_points(&[(4, 1.0, PI / 4.0), (12, middle, PI / 12.0), (16, outer, 0.0)])?;
+
+        let inner = (6..10).map(gray);
+        let middle = (0..6).chain(10..16).map(gray);
+        let outer = (0..16).map(|k| 16 + gray(k));
+        Constellation::new(points, inner.chain(middle).chain(outer).collect())
+    }
+
+    /// Number of points
+    pub fn order(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Number of bits each symbol carries
+    pub fn bits_per_symbol(&self) -> usize {
+        self.order().trailing_zeros() as usize
+    }
+
+    /// Every point, indexed by label
+    pub fn points(&self) -> &[Complex<f64>] {
+        &self.points
+    }
+
+    /// The point with the given label
+    pub fn point(&self, label: usize) -> Complex<f64> {
+        self.points[label]
+    }
+
+    /// Label of the point nearest to `y`
+    pub fn nearest(&self, y: Complex<f64>) -> usize {
+        let distance = |label: &usize| (y - self.points[*label]).norm_sqr();
+        (0..self.order()).min_by(|a, b| distance(a).total_cmp(&distance(b))).unwrap()
+    }
+
+    /// Log likelihood ratio of each bit of the symbol `y`, most significant first, using the
+    /// max-log approximation: the squared distance to the nearest point where the bit is zero
+    /// minus that to the nearest point where it is one, over the noise variance. Positive values
+    /// favour a one.
+    /// - y: Complex<f64> - The received symbol
+    /// - noise_variance: f64 - The noise power per symbol, E|n|²
+    /// - output: &mut Vec<f64> - Where to add the log likelihood ratios
+    pub fn llrs(&self, y: Complex<f64>, noise_variance: f64, output: &mut Vec<f64>) {
+        let distances: Vec<f64> = self.points.iter().map(|point| (y - point).norm_sqr()).collect();
+
+        for bit in (0..self.bits_per_symbol()).rev() {
+            let (mut one, mut zero) = (f64::INFINITY, f64::INFINITY);
+            for (label, distance) in distances.iter().enumerate() {
+                if (label >> bit) & 1 == 1 {
+                    one = one.min(*distance);
+                } else {
+                    zero = zero.min(*distance);
+                }
+            }
+
+            output.push((zero - one) / noise_variance);
+        }
+    }
+}
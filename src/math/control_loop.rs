@@ -0,0 +1,94 @@
+use core::f64::consts::PI;
+
+/// Second order loop filter that turns a phase error into a phase and frequency estimate, the
+/// core of phase locked loops, Costas loops and timing loops. The gains follow from the loop
+/// bandwidth and damping factor of the equivalent analog loop.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ControlLoop {
+    /// Loop bandwidth, in radians per sample
+    pub bandwidth: f64,
+    pub damping: f64,
+    /// Largest frequency the loop may settle on either side of zero, in radians per sample
+    pub max_frequency: f64,
+
+    alpha: f64,
+    beta: f64,
+    phase: f64,
+    frequency: f64,
+}
+
+impl ControlLoop {
+    /// Create a new loop starting at zero phase and frequency
+    /// - bandwidth: f64 - The loop bandwidth, in radians per sample. Around 2π/100 is typical, smaller
+    ///   values reject more noise but lock more slowly.
+    /// - damping: f64 - The damping factor, 1/√2 for a critically damped loop
+    /// - max_frequency: f64 - The frequency limit, in radians per sample
+    pub fn new(bandwidth: f64, damping: f64, max_frequency: f64) -> ControlLoop {
+        let mut control_loop = ControlLoop {
+            bandwidth,
+            damping,
+            max_frequency,
+            alpha: 0.0,
+            beta: 0.0,
+            phase: 0.0,
+            frequency: 0.0,
+        };
+        control_loop.set_bandwidth(bandwidth);
+
+        control_loop
+    }
+
+    /// Change the loop bandwidth, in radians per sample
+    pub fn set_bandwidth(&mut self, bandwidth: f64) {
+        self.bandwidth = bandwidth;
+        self.update_gains();
+    }
+
+    /// Change the damping factor
+    pub fn set_damping(&mut self, damping: f64) {
+        self.damping = damping;
+        self.update_gains();
+    }
+
+    fn update_gains(&mut self) {
+        let (b, d) = (self.bandwidth, self.damping);
+        let denominator = 1.0 + 2.0 * d * b + b * b;
+
+        self.alpha = 4.0 * d * b / denominator;
+        self.beta = 4.0 * b * b / denominator;
+    }
+
+    /// The proportional and integral gains (α, β) that follow from the bandwidth and damping
+    pub fn gains(&self) -> (f64, f64) {
+        (self.alpha, self.beta)
+    }
+
+    /// Feed in the phase error of the current sample and step the estimates on to the next one
+    pub fn advance(&mut self, error: f64) {
+        self.frequency = (self.frequency + self.beta * error).clamp(-self.max_frequency, self.max_frequency);
+        self.phase = wrap(self.phase + self.frequency + self.alpha * error);
+    }
+
+    /// Phase estimate, in radians between -π and π
+    pub fn phase(&self) -> f64 {
+        self.phase
+    }
+
+    /// Frequency estimate, in radians per sample
+    pub fn frequency(&self) -> f64 {
+        self.frequency
+    }
+
+    pub fn set_phase(&mut self, phase: f64) {
+        self.phase = wrap(phase);
+    }
+
+    pub fn set_frequency(&mut self, frequency: f64) {
+        self.frequency = frequency.clamp(-self.max_frequency, self.max_frequency);
+    }
+}
+
+/// Wrap an angle to between -π and π
+pub fn wrap(phase: f64) -> f64 {
+    phase - 2.0 * PI * libm::floor((phase + PI) / (2.0 * PI))
+}
@@ -0,0 +1,369 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::f64::consts::{PI, SQRT_2};
+use core::fmt;
+
+use num::Complex;
+
+use crate::math::fourier::RealFftPlan;
+use crate::math::window::Window;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FilterError {
+    /// The sample rate is not a positive number
+    InvalidSampleRate(f64),
+    /// A cutoff frequency, or the edge of the transition band around it, is not between 0 and half
+    /// the sample rate
+    InvalidFrequency { frequency: f64, nyquist: f64 },
+    /// The lower edge of a band is not below its upper edge, or too close to it to fit the
+    /// transition bands between them
+    InvalidBand { low: f64, high: f64 },
+    /// The transition width is not a positive number
+    InvalidTransitionWidth(f64),
+    /// The stopband attenuation is not a positive number of dB
+    InvalidAttenuation(f64),
+    /// The filter can not be designed with this many taps
+    InvalidTaps(usize),
+    /// No bands were given to design against
+    NoBands,
+    /// A band weight is not a positive number
+    InvalidWeight(f64),
+    /// The filter order must be at least one
+    InvalidOrder(usize),
+    /// The passband ripple is not a positive number of dB
+    InvalidRipple(f64),
+    /// An iterative design did not settle within its iteration limit
+    DidNotConverge { iterations: usize },
+    /// The order, differential delay or rate of a CIC filter is zero
+    InvalidCic { order: usize, delay: usize, rate: usize },
+    /// The rolloff factor is not between 0 and 1
+    InvalidRolloff(f64),
+    /// The bandwidth time product is not a positive number
+    InvalidBandwidthTime(f64),
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterError::InvalidSampleRate(rate) => write!(f, "sample rate must be positive, got {}", rate),
+            FilterError::InvalidFrequency { frequency, nyquist } => write!(f, "frequency {} must be between 0 and the nyquist frequency {}", frequency, nyquist),
+            FilterError::InvalidBand { low, high } => write!(f, "lower band edge {} must be below the upper band edge {}", low, high),
+            FilterError::InvalidTransitionWidth(width) => write!(f, "transition width must be positive, got {}", width),
+            FilterError::InvalidAttenuation(attenuation) => write!(f, "attenuation must be a positive number of dB, got {}", attenuation),
+            FilterError::InvalidTaps(taps) => write!(f, "can not design a filter with {} taps", taps),
+            FilterError::NoBands => write!(f, "at least one band is needed"),
+            FilterError::InvalidWeight(weight) => write!(f, "band weight must be positive, got {}", weight),
+            FilterError::InvalidOrder(order) => write!(f, "can not design a filter of order {}", order),
+            FilterError::InvalidRipple(ripple) => write!(f, "ripple must be a positive number of dB, got {}", ripple),
+            FilterError::DidNotConverge { iterations } => write!(f, "design did not converge after {} iterations", iterations),
+            FilterError::InvalidRolloff(rolloff) => write!(f, "rolloff must be above 0 and at most 1, got {}", rolloff),
+            FilterError::InvalidBandwidthTime(bt) => write!(f, "bandwidth time product must be positive, got {}", bt),
+            FilterError::InvalidCic { order, delay, rate } => write!(f, "a CIC filter needs an order, differential delay and rate of at least 1, got {}, {} and {}", order, delay, rate),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FilterError {}
+
+/// Response of a filter with the given taps at `frequency`
+pub fn frequency_response(taps: &[f64], frequency: f64, sample_rate: f64) -> Complex<f64> {
+    let omega = -2.0 * PI * frequency / sample_rate;
+
+    taps.iter().enumerate().map(|(k, tap)| Complex::new(libm::cos(omega * k as f64), libm::sin(omega * k as f64)) * tap).sum()
+}
+
+/// Magnitude of the response of a filter with the given taps at `frequency`, in dB
+pub fn magnitude_db(taps: &[f64], frequency: f64, sample_rate: f64) -> f64 {
+    10.0 * libm::log10(frequency_response(taps, frequency, sample_rate).norm_sqr())
+}
+
+/// Kaiser's estimate of the number of taps and β needed to reach `attenuation` dB with a
+/// transition `width` wide. The number of taps is always odd so the filter has a whole sample
+/// of delay and can be turned into a high-pass or band-stop.
+pub fn kaiser_parameters(sample_rate: f64, width: f64, attenuation: f64) -> (usize, f64) {
+    let beta = if attenuation > 50.0 {
+        0.1102 * (attenuation - 8.7)
+    } else if attenuation >= 21.0 {
+        0.5842 * libm::pow(attenuation - 21.0, 0.4) + 0.07886 * (attenuation - 21.0)
+    } else {
+        0.0
+    };
+
+    let delta_omega = 2.0 * PI * width / sample_rate;
+    let taps = libm::ceil((attenuation - 7.95) / (2.285 * delta_omega)).max(1.0) as usize + 1;
+
+    (taps | 1, beta)
+}
+
+/// Windowed sinc low-pass filter
+///
+/// - sample_rate: f64 - sample rate of the signal being filtered
+/// - cutoff: f64 - frequency half way through the transition band, where the response is -6 dB
+/// - width: f64 - width of the transition band
+/// - attenuation: f64 - minimum stopband attenuation in dB
+pub fn lowpass(sample_rate: f64, cutoff: f64, width: f64, attenuation: f64) -> Result<Vec<f64>, FilterError> {
+    check(sample_rate, &[cutoff], width, attenuation)?;
+
+    let nyquist = sample_rate / 2.0;
+    let bands = [(0.0, cutoff - width / 2.0, 1.0), (cutoff + width / 2.0, nyquist, 0.0)];
+
+    meet_spec(sample_rate, width, attenuation, &bands, |taps, beta| {
+        windowed_sinc(taps, cutoff / sample_rate, beta)
+    })
+}
+
+/// Windowed sinc high-pass filter. See [`lowpass`] for the parameters.
+pub fn highpass(sample_rate: f64, cutoff: f64, width: f64, attenuation: f64) -> Result<Vec<f64>, FilterError> {
+    check(sample_rate, &[cutoff], width, attenuation)?;
+
+    let nyquist = sample_rate / 2.0;
+    let bands = [(0.0, cutoff - width / 2.0, 0.0), (cutoff + width / 2.0, nyquist, 1.0)];
+
+    meet_spec(sample_rate, width, attenuation, &bands, |taps, beta| {
+        spectral_inverse(windowed_sinc(taps, cutoff / sample_rate, beta))
+    })
+}
+
+/// Windowed sinc band-pass filter passing everything between `low` and `high`. See [`lowpass`]
+/// for the other parameters.
+pub fn bandpass(sample_rate: f64, low: f64, high: f64, width: f64, attenuation: f64) -> Result<Vec<f64>, FilterError> {
+    check(sample_rate, &[low, high], width, attenuation)?;
+    // Leave room for a band between the two transition bands
+    if high - low <= width {
+        return Err(FilterError::InvalidBand { low, high });
+    }
+
+    let nyquist = sample_rate / 2.0;
+    let bands = [
+        (0.0, low - width / 2.0, 0.0),
+        (low + width / 2.0, high - width / 2.0, 1.0),
+        (high + width / 2.0, nyquist, 0.0),
+    ];
+
+    meet_spec(sample_rate, width, attenuation, &bands, |taps, beta| {
+        band(taps, low / sample_rate, high / sample_rate, beta)
+    })
+}
+
+/// Windowed sinc band-stop filter rejecting everything between `low` and `high`. See [`lowpass`]
+/// for the other parameters.
+pub fn bandstop(sample_rate: f64, low: f64, high: f64, width: f64, attenuation: f64) -> Result<Vec<f64>, FilterError> {
+    check(sample_rate, &[low, high], width, attenuation)?;
+    // Leave room for a band between the two transition bands
+    if high - low <= width {
+        return Err(FilterError::InvalidBand { low, high });
+    }
+
+    let nyquist = sample_rate / 2.0;
+    let bands = [
+        (0.0, low - width / 2.0, 1.0),
+        (low + width / 2.0, high - width / 2.0, 0.0),
+        (high + width / 2.0, nyquist, 1.0),
+    ];
+
+    meet_spec(sample_rate, width, attenuation, &bands, |taps, beta| {
+        spectral_inverse(band(taps, low / sample_rate, high / sample_rate, beta))
+    })
+}
+
+/// Root raised cosine pulse for `span` symbols, with `span * samples_per_symbol + 1` taps. It is
+/// scaled to unit energy, so using it as both the transmit and the matched filter gives unit
+/// gain at the symbol instants and no intersymbol interference.
+///
+/// - samples_per_symbol: usize - samples per symbol of the shaped signal
+/// - rolloff: f64 - excess bandwidth, between 0 and 1
+/// - span: usize - length of the pulse in symbols
+pub fn root_raised_cosine(samples_per_symbol: usize, rolloff: f64, span: usize) -> Result<Vec<f64>, FilterError> {
+    if rolloff.partial_cmp(&0.0) != Some(Ordering::Greater) || rolloff > 1.0 {
+        return Err(FilterError::InvalidRolloff(rolloff));
+    }
+
+    let num_taps = span * samples_per_symbol + 1;
+    if samples_per_symbol == 0 || span == 0 {
+        return Err(FilterError::InvalidTaps(num_taps));
+    }
+
+    let middle = (span * samples_per_symbol) as f64 / 2.0;
+    let taps: Vec<f64> = (0..num_taps).map(|k| {
+        let t = (k as f64 - middle) / samples_per_symbol as f64;
+        let edge = 1.0 / (4.0 * rolloff);
+
+        if t == 0.0 {
+            1.0 - rolloff + 4.0 * rolloff / PI
+        } else if (t.abs() - edge).abs() < 1e-9 {
+            rolloff / SQRT_2 * ((1.0 + 2.0 / PI) * libm::sin(PI * edge) + (1.0 - 2.0 / PI) * libm::cos(PI * edge))
+        } else {
+            let numerator = libm::sin(PI * t * (1.0 - rolloff)) + 4.0 * rolloff * t * libm::cos(PI * t * (1.0 + rolloff));
+            numerator / (PI * t * (1.0 - (4.0 * rolloff * t) * (4.0 * rolloff * t)))
+        }
+    }).collect();
+
+    let energy = libm::sqrt(taps.iter().map(|tap| tap * tap).sum::<f64>());
+    Ok(taps.iter().map(|tap| tap / energy).collect())
+}
+
+/// Gaussian pulse for `span` symbols, with `span * samples_per_symbol + 1` taps, as used to
+/// smooth the frequency steps of GFSK and GMSK. It is scaled to unit DC gain, so a step of one
+/// symbol keeps its height.
+///
+/// - samples_per_symbol: usize - samples per symbol of the shaped signal
+/// - bt: f64 - the 3 dB bandwidth times the symbol period, e.g. 0.5 for Bluetooth or 0.3 for GSM
+/// - span: usize - length of the pulse in symbols
+pub fn gaussian(samples_per_symbol: usize, bt: f64, span: usize) -> Result<Vec<f64>, FilterError> {
+    if bt.partial_cmp(&0.0) != Some(Ordering::Greater) || bt.is_infinite() {
+        return Err(FilterError::InvalidBandwidthTime(bt));
+    }
+
+    let num_taps = span * samples_per_symbol + 1;
+    if samples_per_symbol == 0 || span == 0 {
+        return Err(FilterError::InvalidTaps(num_taps));
+    }
+
+    // Standard deviation in symbols for a 3 dB bandwidth of bt over the symbol period
+    let sigma = libm::sqrt(libm::log(2.0)) / (2.0 * PI * bt);
+    let middle = (span * samples_per_symbol) as f64 / 2.0;
+    let taps: Vec<f64> = (0..num_taps).map(|k| {
+        let t = (k as f64 - middle) / samples_per_symbol as f64;
+        libm::exp(-t * t / (2.0 * sigma * sigma))
+    }).collect();
+
+    let gain: f64 = taps.iter().sum();
+    Ok(taps.iter().map(|tap| tap / gain).collect())
+}
+
+/// Hilbert transformer, which shifts every frequency by -90° and so turns a real signal into
+/// the imaginary part of its analytic signal. The output lags by `(num_taps - 1) / 2` samples,
+/// which the real part has to be delayed by to match. Only frequencies well away from 0 Hz and
+/// the Nyquist frequency are shifted accurately, and a longer filter gets closer to both.
+///
+/// - num_taps: usize - length of the filter, an odd number of at least 3
+/// - window: Window - window on the ideal response, e.g. [`Window::Blackman`]
+pub fn hilbert(num_taps: usize, window: Window) -> Result<Vec<f64>, FilterError> {
+    if num_taps < 3 || num_taps.is_multiple_of(2) {
+        return Err(FilterError::InvalidTaps(num_taps));
+    }
+
+    let middle = (num_taps / 2) as i64;
+    let taps = window.symmetric(num_taps).iter().enumerate().map(|(n, w)| {
+        let k = n as i64 - middle;
+        if k % 2 == 0 { 0.0 } else { 2.0 / (PI * k as f64) * w }
+    }).collect();
+
+    Ok(taps)
+}
+
+/// Kaiser's formula is only an estimate, so keep adding taps until the response is within the
+/// ripple the attenuation allows in every band, giving up once the length has about doubled.
+/// Bands are (low, high, gain) triples.
+fn meet_spec(sample_rate: f64, width: f64, attenuation: f64, bands: &[(f64, f64, f64)], design: impl Fn(usize, f64) -> Vec<f64>) -> Result<Vec<f64>, FilterError> {
+    let (mut taps, beta) = kaiser_parameters(sample_rate, width, attenuation);
+    let limit = 2 * taps + 16;
+
+    // A little margin since the response is only checked on a grid
+    let deviation = 0.99 * libm::pow(10.0, -attenuation / 20.0);
+
+    let mut iterations = 0;
+    loop {
+        iterations += 1;
+        let coefficients = design(taps, beta);
+
+        if max_deviation(&coefficients, sample_rate, width, bands) <= deviation {
+            return Ok(coefficients);
+        }
+        if taps >= limit {
+            return Err(FilterError::DidNotConverge { iterations });
+        }
+
+        // Grow long filters by about 1% at a time, keeping the length odd
+        taps += 2 * (taps / 200).max(1);
+    }
+}
+
+/// Largest distance between the magnitude response and the desired gain over every band, checked
+/// on a grid at least 16 points per transition width
+fn max_deviation(taps: &[f64], sample_rate: f64, width: f64, bands: &[(f64, f64, f64)]) -> f64 {
+    let size = (16 * taps.len()).max(libm::ceil(16.0 * sample_rate / width) as usize).next_power_of_two();
+    let plan = RealFftPlan::new(size);
+
+    let mut padded = vec![0.0; size];
+    padded[..taps.len()].copy_from_slice(taps);
+    let mut spectrum = vec![Complex::new(0.0, 0.0); plan.output_len()];
+    plan.fft(&padded, &mut spectrum);
+
+    // Undo the 1/sqrt(n) scaling of the FFT
+    let scale = libm::sqrt(size as f64);
+
+    spectrum.iter().enumerate().filter_map(|(k, x)| {
+        let frequency = k as f64 * sample_rate / size as f64;
+        let (_, _, gain) = bands.iter().find(|(low, high, _)| frequency >= *low && frequency <= *high)?;
+
+        Some((libm::hypot(x.re, x.im) * scale - gain).abs())
+    }).fold(0.0, f64::max)
+}
+
+pub(crate) fn check(sample_rate: f64, cutoffs: &[f64], width: f64, attenuation: f64) -> Result<(), FilterError> {
+    if sample_rate.partial_cmp(&0.0) != Some(Ordering::Greater) {
+        return Err(FilterError::InvalidSampleRate(sample_rate));
+    }
+
+    let nyquist = sample_rate / 2.0;
+    for frequency in cutoffs {
+        if *frequency <= 0.0 || *frequency >= nyquist || frequency.is_nan() {
+            return Err(FilterError::InvalidFrequency { frequency: *frequency, nyquist });
+        }
+    }
+
+    if width.partial_cmp(&0.0) != Some(Ordering::Greater) {
+        return Err(FilterError::InvalidTransitionWidth(width));
+    }
+
+    // The transition band around each cutoff has to fit as well
+    for edge in cutoffs.iter().flat_map(|frequency| [frequency - width / 2.0, frequency + width / 2.0]) {
+        if edge < 0.0 || edge > nyquist {
+            return Err(FilterError::InvalidFrequency { frequency: edge, nyquist });
+        }
+    }
+
+    if attenuation.partial_cmp(&0.0) != Some(Ordering::Greater) {
+        return Err(FilterError::InvalidAttenuation(attenuation));
+    }
+
+    Ok(())
+}
+
+/// Kaiser windowed ideal low-pass with a cutoff given as a fraction of the sample rate, scaled to
+/// unity gain at DC
+fn windowed_sinc(taps: usize, cutoff: f64, beta: f64) -> Vec<f64> {
+    let window = Window::Kaiser(beta).symmetric(taps);
+    let middle = (taps - 1) as f64 / 2.0;
+
+    let coefficients: Vec<f64> = window.iter().enumerate().map(|(k, w)| {
+        let t = k as f64 - middle;
+        let sinc = if t == 0.0 { 2.0 * cutoff } else { libm::sin(2.0 * PI * cutoff * t) / (PI * t) };
+        sinc * w
+    }).collect();
+
+    let gain: f64 = coefficients.iter().sum();
+    coefficients.iter().map(|c| c / gain).collect()
+}
+
+/// Difference of two low-passes, passing everything between two cutoffs given as fractions of the
+/// sample rate
+fn band(taps: usize, low: f64, high: f64, beta: f64) -> Vec<f64> {
+    let upper = windowed_sinc(taps, high, beta);
+    let lower = windowed_sinc(taps, low, beta);
+
+    upper.iter().zip(lower.iter()).map(|(u, l)| u - l).collect()
+}
+
+/// Turn a low-pass into a high-pass (or a band-pass into a band-stop) by subtracting it from an
+/// impulse in the middle of the filter
+fn spectral_inverse(mut taps: Vec<f64>) -> Vec<f64> {
+    taps.iter_mut().for_each(|tap| *tap = -*tap);
+    let middle = taps.len() / 2;
+    taps[middle] += 1.0;
+
+    taps
+}
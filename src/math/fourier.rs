@@ -1,3 +1,6 @@
+use alloc::boxed::Box;
+use alloc::vec;
+use alloc::vec::Vec;
 use core::f64;
 
 use num::Complex;
@@ -52,6 +55,9 @@ pub fn make_inverse_basis(n: usize) -> ndarray::Array2<num::Complex<f64>> {
     basis * (1.0 / libm::sqrt(n as f64))
 }
 
+/// Permutation matrix that moves the zero frequency bin towards the middle of a spectrum. For even
+/// `n` the last bin ends up first, so this does not quite match [`fftshift`], which should be
+/// preferred as it works in place without building an n × n matrix.
 pub fn fft_shift(n: usize) -> ndarray::Array2<Complex<f64>> {
     let mut shift: ndarray::Array2<Complex<f64>> = ndarray::Array2::zeros((n, n));
 
@@ -62,6 +68,7 @@ pub fn fft_shift(n: usize) -> ndarray::Array2<Complex<f64>> {
     shift
 }
 
+/// Undo [`fft_shift`]. See [`ifftshift`] for an in place version.
 pub fn fft_shift_inverse(n: usize) -> ndarray::Array2<Complex<f64>> {
     let mut shift: ndarray::Array2<Complex<f64>> = ndarray::Array2::zeros((n, n));
 
@@ -71,3 +78,280 @@ pub fn fft_shift_inverse(n: usize) -> ndarray::Array2<Complex<f64>> {
 
     shift
 }
+
+/// Precomputed twiddle factors for an FFT of one size. Power of two sizes use an in-place radix-2
+/// transform, every other size is turned into a power of two sized convolution with Bluestein's
+/// algorithm. Results are scaled by 1/sqrt(n) in both directions to match [`make_basis`] and
+/// [`make_inverse_basis`].
+#[derive(Clone, Debug)]
+pub struct FftPlan {
+    n: usize,
+    algorithm: Algorithm,
+}
+
+#[derive(Clone, Debug)]
+enum Algorithm {
+    Radix2 {
+        // e^(-2πik/n) for k in 0..n/2
+        twiddles: Vec<Complex<f64>>,
+    },
+    Bluestein {
+        inner: Box<FftPlan>,
+        // e^(-πik²/n) for k in 0..n
+        chirp: Vec<Complex<f64>>,
+        // Unscaled FFT of the conjugate chirp, zero padded and wrapped around to the inner size
+        chirp_spectrum: Vec<Complex<f64>>,
+    },
+}
+
+impl FftPlan {
+    /// Plan an FFT of `n` points
+    pub fn new(n: usize) -> FftPlan {
+        if n <= 1 || n.is_power_of_two() {
+            let twiddles = (0..n / 2).map(|k| calculate_root_of_unity(n as f64, 1.0, k as f64)).collect();
+
+            return FftPlan { n, algorithm: Algorithm::Radix2 { twiddles } };
+        }
+
+        // Linear convolution of two n point sequences needs 2n - 1 points
+        let inner = FftPlan::new((2 * n - 1).next_power_of_two());
+        let m = inner.n;
+
+        // k² is reduced mod 2n first so the angle stays small and accurate for large n
+        let chirp: Vec<Complex<f64>> = (0..n).map(|k| {
+            let k = k as u128;
+            calculate_root_of_unity(2.0 * n as f64, 1.0, ((k * k) % (2 * n as u128)) as f64)
+        }).collect();
+
+        let mut chirp_spectrum = vec![Complex::new(0.0, 0.0); m];
+        chirp_spectrum[0] = chirp[0].conj();
+        for k in 1..n {
+            chirp_spectrum[k] = chirp[k].conj();
+            chirp_spectrum[m - k] = chirp[k].conj();
+        }
+        inner.transform(&mut chirp_spectrum, false);
+
+        FftPlan { n, algorithm: Algorithm::Bluestein { inner: Box::new(inner), chirp, chirp_spectrum } }
+    }
+
+    /// Number of points this plan transforms
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Forward FFT in place, equivalent to `make_basis(n).dot(data)`. Panics if the slice is not
+    /// the planned length.
+    pub fn fft(&self, data: &mut [Complex<f64>]) {
+        self.transform(data, false);
+        self.normalize(data);
+    }
+
+    /// Inverse FFT in place, equivalent to `make_inverse_basis(n).dot(data)`. Panics if the slice is
+    /// not the planned length.
+    pub fn ifft(&self, data: &mut [Complex<f64>]) {
+        self.transform(data, true);
+        self.normalize(data);
+    }
+
+    fn normalize(&self, data: &mut [Complex<f64>]) {
+        let scale = 1.0 / libm::sqrt(self.n as f64);
+
+        for x in data.iter_mut() {
+            *x *= scale;
+        }
+    }
+
+    /// Unscaled transform
+    fn transform(&self, data: &mut [Complex<f64>], inverse: bool) {
+        assert_eq!(data.len(), self.n, "plan is for {} points but got {}", self.n, data.len());
+
+        match &self.algorithm {
+            Algorithm::Radix2 { twiddles } => radix2(data, twiddles, inverse),
+            Algorithm::Bluestein { inner, chirp, chirp_spectrum } => {
+                // The inverse transform is the forward transform of the conjugate, conjugated
+                if inverse {
+                    data.iter_mut().for_each(|x| *x = x.conj());
+                }
+
+                let m = inner.n;
+                let mut scratch = vec![Complex::new(0.0, 0.0); m];
+                for (s, (x, c)) in scratch.iter_mut().zip(data.iter().zip(chirp.iter())) {
+                    *s = x * c;
+                }
+
+                inner.transform(&mut scratch, false);
+                for (s, b) in scratch.iter_mut().zip(chirp_spectrum.iter()) {
+                    *s *= b;
+                }
+                inner.transform(&mut scratch, true);
+
+                let scale = 1.0 / m as f64;
+                for (x, (s, c)) in data.iter_mut().zip(scratch.iter().zip(chirp.iter())) {
+                    *x = s * c * scale;
+                }
+
+                if inverse {
+                    data.iter_mut().for_each(|x| *x = x.conj());
+                }
+            }
+        }
+    }
+}
+
+/// Iterative in-place Cooley-Tukey transform of a power of two length slice
+fn radix2(data: &mut [Complex<f64>], twiddles: &[Complex<f64>], inverse: bool) {
+    let n = data.len();
+    if n <= 1 {
+        return;
+    }
+
+    // Bit reversed reordering
+    let bits = n.trailing_zeros();
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - bits);
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let mut size = 2;
+    while size <= n {
+        let half = size / 2;
+        let step = n / size;
+
+        for start in (0..n).step_by(size) {
+            for k in 0..half {
+                let twiddle = if inverse { twiddles[k * step].conj() } else { twiddles[k * step] };
+                let odd = data[start + k + half] * twiddle;
+
+                data[start + k + half] = data[start + k] - odd;
+                data[start + k] += odd;
+            }
+        }
+
+        size *= 2;
+    }
+}
+
+/// Forward FFT of a whole slice in place. Build an [`FftPlan`] instead when transforming many
+/// slices of the same length.
+pub fn fft(data: &mut [Complex<f64>]) {
+    FftPlan::new(data.len()).fft(data);
+}
+
+/// Inverse FFT of a whole slice in place. Build an [`FftPlan`] instead when transforming many
+/// slices of the same length.
+pub fn ifft(data: &mut [Complex<f64>]) {
+    FftPlan::new(data.len()).ifft(data);
+}
+
+/// Move the zero frequency bin of a spectrum to the middle, with negative frequencies to the left
+/// of it, e.g. `[0, 1, 2, -2, -1]` becomes `[-2, -1, 0, 1, 2]`
+pub fn fftshift<T>(data: &mut [T]) {
+    let n = data.len();
+    data.rotate_right(n / 2);
+}
+
+/// Undo [`fftshift`], moving the zero frequency bin back to the start
+pub fn ifftshift<T>(data: &mut [T]) {
+    let n = data.len();
+    data.rotate_left(n / 2);
+}
+
+/// FFT of real samples. Only the first n/2 + 1 bins are produced since the rest are the complex
+/// conjugates of those. Even sizes are packed into a complex FFT of half the size, so they take
+/// roughly half the work of [`FftPlan`]. Scaled by 1/sqrt(n) like [`FftPlan::fft`].
+#[derive(Clone, Debug)]
+pub struct RealFftPlan {
+    n: usize,
+    inner: FftPlan,
+    // e^(-2πik/n) for k in 0..n/2, only used for even sizes
+    twiddles: Vec<Complex<f64>>,
+}
+
+impl RealFftPlan {
+    /// Plan a real FFT of `n` points
+    pub fn new(n: usize) -> RealFftPlan {
+        if n % 2 == 1 {
+            return RealFftPlan { n, inner: FftPlan::new(n), twiddles: Vec::new() };
+        }
+
+        RealFftPlan {
+            n,
+            inner: FftPlan::new(n / 2),
+            twiddles: (0..n / 2).map(|k| calculate_root_of_unity(n as f64, 1.0, k as f64)).collect(),
+        }
+    }
+
+    /// Number of real samples this plan transforms
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    /// Number of bins in the half spectrum, n/2 + 1
+    pub fn output_len(&self) -> usize {
+        self.n / 2 + 1
+    }
+
+    /// Transform `input` into the non-negative frequency bins of its spectrum. Panics if the input
+    /// is not the planned length or the output does not have [`RealFftPlan::output_len`] bins.
+    pub fn fft(&self, input: &[f64], output: &mut [Complex<f64>]) {
+        assert_eq!(input.len(), self.n, "plan is for {} points but got {}", self.n, input.len());
+        assert_eq!(output.len(), self.output_len(), "a real FFT of {} points has {} bins but got {}", self.n, self.output_len(), output.len());
+
+        if self.n == 0 {
+            output[0] = Complex::new(0.0, 0.0);
+            return;
+        }
+
+        if self.n % 2 == 1 {
+            let mut full: Vec<Complex<f64>> = input.iter().map(|x| Complex::new(*x, 0.0)).collect();
+            self.inner.transform(&mut full, false);
+            output.copy_from_slice(&full[..self.output_len()]);
+        } else {
+            self.packed(input, output);
+        }
+
+        let scale = 1.0 / libm::sqrt(self.n as f64);
+        for x in output.iter_mut() {
+            *x *= scale;
+        }
+    }
+
+    /// Treat even and odd samples as the real and imaginary parts of a half sized complex signal,
+    /// transform that in the output buffer and then pull the two real spectra apart
+    fn packed(&self, input: &[f64], output: &mut [Complex<f64>]) {
+        let half = self.n / 2;
+
+        for (z, pair) in output.iter_mut().zip(input.chunks_exact(2)) {
+            *z = Complex::new(pair[0], pair[1]);
+        }
+        self.inner.transform(&mut output[..half], false);
+
+        let z0 = output[0];
+        output[0] = Complex::new(z0.re + z0.im, 0.0);
+        output[half] = Complex::new(z0.re - z0.im, 0.0);
+
+        let split = |zk: Complex<f64>, zj: Complex<f64>, twiddle: Complex<f64>| {
+            let even = (zk + zj.conj()) * 0.5;
+            let odd = (zk - zj.conj()) * Complex::new(0.0, -0.5);
+            even + twiddle * odd
+        };
+
+        for k in 1..=half / 2 {
+            let j = half - k;
+            let (zk, zj) = (output[k], output[j]);
+
+            output[k] = split(zk, zj, self.twiddles[k]);
+            output[j] = split(zj, zk, self.twiddles[j]);
+        }
+    }
+}
@@ -0,0 +1,465 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::f64::consts::PI;
+
+use num::Complex;
+
+use crate::math::filter::FilterError;
+
+/// One second order section, (b0 + b1 z⁻¹ + b2 z⁻²) / (1 + a1 z⁻¹ + a2 z⁻²)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Biquad {
+    pub b0: f64,
+    pub b1: f64,
+    pub b2: f64,
+    pub a1: f64,
+    pub a2: f64,
+}
+
+impl Biquad {
+    pub fn new(b0: f64, b1: f64, b2: f64, a1: f64, a2: f64) -> Biquad {
+        Biquad { b0, b1, b2, a1, a2 }
+    }
+
+    /// Response of this section at `frequency`
+    pub fn frequency_response(&self, frequency: f64, sample_rate: f64) -> Complex<f64> {
+        let omega = -2.0 * PI * frequency / sample_rate;
+        let z1 = Complex::new(libm::cos(omega), libm::sin(omega));
+        let z2 = z1 * z1;
+
+        (z1 * self.b1 + z2 * self.b2 + self.b0) / (z1 * self.a1 + z2 * self.a2 + 1.0)
+    }
+}
+
+/// Response of a cascade of sections at `frequency`
+pub fn frequency_response(sections: &[Biquad], frequency: f64, sample_rate: f64) -> Complex<f64> {
+    sections.iter().map(|section| section.frequency_response(frequency, sample_rate)).product()
+}
+
+/// Magnitude of the response of a cascade of sections at `frequency`, in dB
+pub fn magnitude_db(sections: &[Biquad], frequency: f64, sample_rate: f64) -> f64 {
+    10.0 * libm::log10(frequency_response(sections, frequency, sample_rate).norm_sqr())
+}
+
+/// Which frequencies a filter passes. Edges are in the same unit as the sample rate.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BandType {
+    LowPass(f64),
+    HighPass(f64),
+    BandPass(f64, f64),
+    BandStop(f64, f64),
+}
+
+/// The analog prototype a filter is built from. The band edges of a Butterworth filter are its
+/// -3 dB points, Chebyshev I and elliptic edges are where the passband ripple ends, and Chebyshev II
+/// edges are where the stopband attenuation starts.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Prototype {
+    Butterworth,
+    /// Passband ripple in dB
+    ChebyshevI(f64),
+    /// Stopband attenuation in dB
+    ChebyshevII(f64),
+    /// Passband ripple and stopband attenuation in dB
+    Elliptic(f64, f64),
+}
+
+/// Butterworth filter of the given order. See [`design`].
+pub fn butterworth(order: usize, band: BandType, sample_rate: f64) -> Result<Vec<Biquad>, FilterError> {
+    design(Prototype::Butterworth, order, band, sample_rate)
+}
+
+/// Chebyshev type I filter with `ripple` dB of passband ripple. See [`design`].
+pub fn chebyshev1(order: usize, ripple: f64, band: BandType, sample_rate: f64) -> Result<Vec<Biquad>, FilterError> {
+    design(Prototype::ChebyshevI(ripple), order, band, sample_rate)
+}
+
+/// Chebyshev type II filter with `attenuation` dB of stopband attenuation. See [`design`].
+pub fn chebyshev2(order: usize, attenuation: f64, band: BandType, sample_rate: f64) -> Result<Vec<Biquad>, FilterError> {
+    design(Prototype::ChebyshevII(attenuation), order, band, sample_rate)
+}
+
+/// Elliptic filter with `ripple` dB of passband ripple and `attenuation` dB of stopband
+/// attenuation. See [`design`].
+pub fn elliptic(order: usize, ripple: f64, attenuation: f64, band: BandType, sample_rate: f64) -> Result<Vec<Biquad>, FilterError> {
+    design(Prototype::Elliptic(ripple, attenuation), order, band, sample_rate)
+}
+
+/// Design a digital IIR filter as a cascade of second order sections by transforming an analog
+/// prototype and mapping it with the bilinear transform. Band-pass and band-stop filters end up
+/// with twice the order of the prototype.
+pub fn design(prototype: Prototype, order: usize, band: BandType, sample_rate: f64) -> Result<Vec<Biquad>, FilterError> {
+    check(prototype, order, band, sample_rate)?;
+
+    let analog = match prototype {
+        Prototype::Butterworth => butterworth_prototype(order),
+        Prototype::ChebyshevI(ripple) => chebyshev1_prototype(order, ripple),
+        Prototype::ChebyshevII(attenuation) => chebyshev2_prototype(order, attenuation),
+        Prototype::Elliptic(ripple, attenuation) => elliptic_prototype(order, ripple, attenuation),
+    };
+
+    // Pre-warp the band edges so they land in the right place after the bilinear transform
+    let warp = |frequency: f64| 2.0 * sample_rate * libm::tan(PI * frequency / sample_rate);
+
+    let analog = match band {
+        BandType::LowPass(cutoff) => analog.lowpass(warp(cutoff)),
+        BandType::HighPass(cutoff) => analog.highpass(warp(cutoff)),
+        BandType::BandPass(low, high) => analog.bandpass(libm::sqrt(warp(low) * warp(high)), warp(high) - warp(low)),
+        BandType::BandStop(low, high) => analog.bandstop(libm::sqrt(warp(low) * warp(high)), warp(high) - warp(low)),
+    };
+
+    Ok(analog.bilinear(sample_rate).sections())
+}
+
+fn check(prototype: Prototype, order: usize, band: BandType, sample_rate: f64) -> Result<(), FilterError> {
+    if sample_rate.partial_cmp(&0.0) != Some(Ordering::Greater) {
+        return Err(FilterError::InvalidSampleRate(sample_rate));
+    }
+
+    if order == 0 {
+        return Err(FilterError::InvalidOrder(order));
+    }
+
+    let nyquist = sample_rate / 2.0;
+    let edges = match band {
+        BandType::LowPass(cutoff) | BandType::HighPass(cutoff) => vec![cutoff],
+        BandType::BandPass(low, high) | BandType::BandStop(low, high) => {
+            if low >= high {
+                return Err(FilterError::InvalidBand { low, high });
+            }
+            vec![low, high]
+        }
+    };
+
+    for frequency in edges {
+        if frequency <= 0.0 || frequency >= nyquist || frequency.is_nan() {
+            return Err(FilterError::InvalidFrequency { frequency, nyquist });
+        }
+    }
+
+    let positive = |value: f64| value.partial_cmp(&0.0) == Some(Ordering::Greater);
+
+    match prototype {
+        Prototype::Butterworth => {}
+        Prototype::ChebyshevI(ripple) if !positive(ripple) => return Err(FilterError::InvalidRipple(ripple)),
+        Prototype::ChebyshevII(attenuation) if !positive(attenuation) => return Err(FilterError::InvalidAttenuation(attenuation)),
+        Prototype::Elliptic(ripple, _) if !positive(ripple) => return Err(FilterError::InvalidRipple(ripple)),
+        Prototype::Elliptic(ripple, attenuation) if !positive(attenuation - ripple) => return Err(FilterError::InvalidAttenuation(attenuation)),
+        _ => {}
+    }
+
+    Ok(())
+}
+
+/// A filter described by its zeros, poles and gain
+struct Zpk {
+    zeros: Vec<Complex<f64>>,
+    poles: Vec<Complex<f64>>,
+    gain: f64,
+}
+
+fn product(roots: &[Complex<f64>], at: Complex<f64>) -> Complex<f64> {
+    roots.iter().map(|root| at - root).product()
+}
+
+fn butterworth_prototype(order: usize) -> Zpk {
+    let n = order as f64;
+    let poles = (0..order).map(|k| {
+        let theta = PI * (2.0 * k as f64 + n + 1.0) / (2.0 * n);
+        Complex::new(libm::cos(theta), libm::sin(theta))
+    }).collect();
+
+    Zpk { zeros: Vec::new(), poles, gain: 1.0 }
+}
+
+fn chebyshev1_prototype(order: usize, ripple: f64) -> Zpk {
+    let n = order as f64;
+    let epsilon = libm::sqrt(libm::pow(10.0, ripple / 10.0) - 1.0);
+    let mu = libm::asinh(1.0 / epsilon) / n;
+
+    let poles: Vec<Complex<f64>> = (0..order).map(|k| {
+        let theta = PI * (2.0 * k as f64 + 1.0 - n) / (2.0 * n);
+        -Complex::new(mu, theta).sinh()
+    }).collect();
+
+    // Odd orders have unity gain at DC, even orders start at the bottom of the ripple
+    let mut gain = product(&poles, Complex::new(0.0, 0.0)).re;
+    if order.is_multiple_of(2) {
+        gain /= libm::sqrt(1.0 + epsilon * epsilon);
+    }
+
+    Zpk { zeros: Vec::new(), poles, gain }
+}
+
+fn chebyshev2_prototype(order: usize, attenuation: f64) -> Zpk {
+    let n = order as f64;
+    let epsilon = 1.0 / libm::sqrt(libm::pow(10.0, attenuation / 10.0) - 1.0);
+    let mu = libm::asinh(1.0 / epsilon) / n;
+
+    // The middle zero of an odd order filter is at infinity
+    let zeros: Vec<Complex<f64>> = (0..order)
+        .filter(|k| 2 * k + 1 != order)
+        .map(|k| Complex::new(0.0, 1.0 / libm::sin(PI * (2.0 * k as f64 + 1.0 - n) / (2.0 * n))))
+        .collect();
+
+    let poles: Vec<Complex<f64>> = (0..order).map(|k| {
+        let theta = PI * (2.0 * k as f64 + 1.0 - n) / (2.0 * n);
+        let pole = -Complex::new(libm::cos(theta), libm::sin(theta));
+        Complex::new(libm::sinh(mu) * pole.re, libm::cosh(mu) * pole.im).inv()
+    }).collect();
+
+    let origin = Complex::new(0.0, 0.0);
+    let gain = (product(&poles, origin) / product(&zeros, origin)).re;
+
+    Zpk { zeros, poles, gain }
+}
+
+/// Elliptic prototype following Orfanidis, "Lecture Notes on Elliptic Filter Design", which builds
+/// the poles and zeros from Jacobi elliptic functions evaluated with Landen's transformation
+fn elliptic_prototype(order: usize, ripple: f64, attenuation: f64) -> Zpk {
+    let n = order as f64;
+    let epsilon_pass = libm::sqrt(libm::pow(10.0, ripple / 10.0) - 1.0);
+    let epsilon_stop = libm::sqrt(libm::pow(10.0, attenuation / 10.0) - 1.0);
+
+    let k1 = epsilon_pass / epsilon_stop;
+    let k = elliptic_degree(order, k1);
+
+    let i = Complex::new(0.0, 1.0);
+    let v0 = (-i * asne(i / epsilon_pass, k1) / n).re;
+
+    let mut zeros = Vec::new();
+    let mut poles = Vec::new();
+
+    for index in 1..=order / 2 {
+        let u = Complex::new((2 * index - 1) as f64 / n, 0.0);
+
+        let zero = i / (cde(u, k) * k);
+        zeros.push(zero);
+        zeros.push(zero.conj());
+
+        let pole = i * cde(u - i * v0, k);
+        poles.push(pole);
+        poles.push(pole.conj());
+    }
+
+    if order % 2 == 1 {
+        poles.push(i * sne(i * v0, k));
+    }
+
+    // Odd orders have unity gain at DC, even orders start at the bottom of the ripple
+    let origin = Complex::new(0.0, 0.0);
+    let mut gain = (product(&poles, origin) / product(&zeros, origin)).re;
+    if order.is_multiple_of(2) {
+        gain /= libm::sqrt(1.0 + epsilon_pass * epsilon_pass);
+    }
+
+    Zpk { zeros, poles, gain }
+}
+
+/// Descending Landen sequence of elliptic moduli starting from `k`
+fn landen(k: f64) -> Vec<f64> {
+    let mut moduli = Vec::new();
+    let mut k = k;
+
+    while k > 1e-15 && moduli.len() < 16 {
+        k = libm::pow(k / (1.0 + libm::sqrt(1.0 - k * k)), 2.0);
+        moduli.push(k);
+    }
+
+    moduli
+}
+
+/// Jacobi cd(uK, k), with u normalized to the quarter period K
+fn cde(u: Complex<f64>, k: f64) -> Complex<f64> {
+    ascend(landen(k), (u * PI / 2.0).cos())
+}
+
+/// Jacobi sn(uK, k), with u normalized to the quarter period K
+fn sne(u: Complex<f64>, k: f64) -> Complex<f64> {
+    ascend(landen(k), (u * PI / 2.0).sin())
+}
+
+fn ascend(moduli: Vec<f64>, mut w: Complex<f64>) -> Complex<f64> {
+    for v in moduli.iter().rev() {
+        w = w * (1.0 + v) / (w * w * *v + 1.0);
+    }
+
+    w
+}
+
+/// Inverse of [`sne`]
+fn asne(w: Complex<f64>, k: f64) -> Complex<f64> {
+    let moduli = landen(k);
+    let mut w = w;
+    let mut previous = k;
+
+    for v in moduli {
+        w = w / ((Complex::new(1.0, 0.0) - w * w * previous * previous).sqrt() + 1.0) * 2.0 / (1.0 + v);
+        previous = v;
+    }
+
+    Complex::new(1.0, 0.0) - w.acos() * 2.0 / PI
+}
+
+/// Solve the degree equation for the modulus of an elliptic filter of the given order
+fn elliptic_degree(order: usize, k1: f64) -> f64 {
+    let n = order as f64;
+    let k1_complement = libm::sqrt(1.0 - k1 * k1);
+
+    let mut k_complement = libm::pow(k1_complement, n);
+    for index in 1..=order / 2 {
+        let s = sne(Complex::new((2 * index - 1) as f64 / n, 0.0), k1_complement).re;
+        k_complement *= libm::pow(s, 4.0);
+    }
+
+    libm::sqrt(1.0 - k_complement * k_complement)
+}
+
+impl Zpk {
+    fn degree(&self) -> usize {
+        self.poles.len() - self.zeros.len()
+    }
+
+    /// Move the cutoff of a prototype from 1 rad/s to `cutoff`
+    fn lowpass(self, cutoff: f64) -> Zpk {
+        let degree = self.degree() as f64;
+
+        Zpk {
+            zeros: self.zeros.iter().map(|z| z * cutoff).collect(),
+            poles: self.poles.iter().map(|p| p * cutoff).collect(),
+            gain: self.gain * libm::pow(cutoff, degree),
+        }
+    }
+
+    fn highpass(self, cutoff: f64) -> Zpk {
+        let origin = Complex::new(0.0, 0.0);
+        let gain = self.gain * (product(&self.zeros, origin) / product(&self.poles, origin)).re;
+
+        let mut zeros: Vec<Complex<f64>> = self.zeros.iter().map(|z| cutoff / z).collect();
+        zeros.extend((0..self.degree()).map(|_| origin));
+
+        Zpk { zeros, poles: self.poles.iter().map(|p| cutoff / p).collect(), gain }
+    }
+
+    fn bandpass(self, center: f64, width: f64) -> Zpk {
+        let degree = self.degree();
+        let split = |roots: &[Complex<f64>]| -> Vec<Complex<f64>> {
+            roots.iter().flat_map(|root| {
+                let scaled = root * width / 2.0;
+                let offset = (scaled * scaled - center * center).sqrt();
+                [scaled + offset, scaled - offset]
+            }).collect()
+        };
+
+        let mut zeros = split(&self.zeros);
+        zeros.extend((0..degree).map(|_| Complex::new(0.0, 0.0)));
+
+        Zpk { zeros, poles: split(&self.poles), gain: self.gain * libm::pow(width, degree as f64) }
+    }
+
+    fn bandstop(self, center: f64, width: f64) -> Zpk {
+        let origin = Complex::new(0.0, 0.0);
+        let gain = self.gain * (product(&self.zeros, origin) / product(&self.poles, origin)).re;
+
+        let split = |roots: &[Complex<f64>]| -> Vec<Complex<f64>> {
+            roots.iter().flat_map(|root| {
+                let scaled = width / 2.0 / root;
+                let offset = (scaled * scaled - center * center).sqrt();
+                [scaled + offset, scaled - offset]
+            }).collect()
+        };
+
+        let mut zeros = split(&self.zeros);
+        for _ in 0..self.degree() {
+            zeros.push(Complex::new(0.0, center));
+            zeros.push(Complex::new(0.0, -center));
+        }
+
+        Zpk { zeros, poles: split(&self.poles), gain }
+    }
+
+    /// Map the s plane onto the z plane. Zeros at infinity end up at half the sample rate.
+    fn bilinear(self, sample_rate: f64) -> Zpk {
+        let fs2 = Complex::new(2.0 * sample_rate, 0.0);
+        let gain = self.gain * (product(&self.zeros, fs2) / product(&self.poles, fs2)).re;
+
+        let mut zeros: Vec<Complex<f64>> = self.zeros.iter().map(|z| (fs2 + z) / (fs2 - z)).collect();
+        zeros.extend((0..self.degree()).map(|_| Complex::new(-1.0, 0.0)));
+
+        Zpk { zeros, poles: self.poles.iter().map(|p| (fs2 + p) / (fs2 - p)).collect(), gain }
+    }
+
+    /// Group conjugate poles and zeros into second order sections. Poles closest to the unit
+    /// circle go last, and each pole pair gets the closest remaining pair of zeros.
+    fn sections(self) -> Vec<Biquad> {
+        let mut poles = root_pairs(&self.poles);
+        let mut zeros = root_pairs(&self.zeros);
+
+        poles.sort_by(|a, b| {
+            let distance = |pair: &(Complex<f64>, Option<Complex<f64>>)| (1.0 - pair.0.norm()).abs();
+            distance(b).partial_cmp(&distance(a)).unwrap_or(Ordering::Equal)
+        });
+
+        let mut sections = Vec::new();
+        for (p1, p2) in poles {
+            // A first order pole section should get the lone real zero
+            let same_kind: Vec<usize> = (0..zeros.len()).filter(|index| zeros[*index].1.is_some() == p2.is_some()).collect();
+            let candidates = if same_kind.is_empty() { (0..zeros.len()).collect() } else { same_kind };
+
+            let closest = candidates.into_iter().min_by(|a, b| {
+                (zeros[*a].0 - p1).norm().partial_cmp(&(zeros[*b].0 - p1).norm()).unwrap_or(Ordering::Equal)
+            });
+
+            let (z1, z2) = match closest {
+                Some(index) => {
+                    let (z1, z2) = zeros.remove(index);
+                    (Some(z1), z2)
+                }
+                None => (None, None),
+            };
+
+            let (b1, b2) = quadratic(z1, z2);
+            let (a1, a2) = quadratic(Some(p1), p2);
+            sections.push(Biquad::new(1.0, b1, b2, a1, a2));
+        }
+
+        if let Some(first) = sections.first_mut() {
+            first.b0 *= self.gain;
+            first.b1 *= self.gain;
+            first.b2 *= self.gain;
+        }
+
+        sections
+    }
+}
+
+/// Coefficients of (1 - r1 z⁻¹)(1 - r2 z⁻¹) after the leading 1
+fn quadratic(r1: Option<Complex<f64>>, r2: Option<Complex<f64>>) -> (f64, f64) {
+    match (r1, r2) {
+        (Some(r1), Some(r2)) => (-(r1 + r2).re, (r1 * r2).re),
+        (Some(r1), None) => (-r1.re, 0.0),
+        _ => (0.0, 0.0),
+    }
+}
+
+/// Pair every complex root with its conjugate and the real roots with each other, leaving at most
+/// one real root on its own
+fn root_pairs(roots: &[Complex<f64>]) -> Vec<(Complex<f64>, Option<Complex<f64>>)> {
+    let is_real = |root: &Complex<f64>| root.im.abs() <= 1e-10 * (1.0 + root.norm());
+
+    let mut pairs: Vec<(Complex<f64>, Option<Complex<f64>>)> = roots.iter()
+        .filter(|root| !is_real(root) && root.im > 0.0)
+        .map(|root| (*root, Some(root.conj())))
+        .collect();
+
+    let mut real: Vec<f64> = roots.iter().filter(|root| is_real(root)).map(|root| root.re).collect();
+    real.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+    for chunk in real.chunks(2) {
+        let first = Complex::new(chunk[0], 0.0);
+        pairs.push((first, chunk.get(1).map(|r| Complex::new(*r, 0.0))));
+    }
+
+    pairs
+}
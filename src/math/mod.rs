@@ -1,2 +1,11 @@
 pub mod fourier;
 
+pub mod window;
+pub mod filter;
+pub mod remez;
+pub mod iir;
+pub mod cic;
+pub mod nco;
+pub mod control_loop;
+pub mod constellation;
+pub mod ofdm;
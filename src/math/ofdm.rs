@@ -0,0 +1,202 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::SQRT_2;
+use core::fmt;
+
+use num::Complex;
+
+use crate::math::constellation::Constellation;
+use crate::math::fourier::FftPlan;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OfdmError {
+    /// The FFT size is not an even number of at least 8
+    InvalidFftSize(usize),
+    /// The cyclic prefix is longer than a symbol
+    InvalidCyclicPrefix { cyclic_prefix: usize, fft_size: usize },
+    /// A subcarrier is at DC, beyond half the FFT size or used twice
+    InvalidCarrier(i64),
+    /// There are no data subcarriers, or the preamble would have too few to detect
+    TooFewCarriers(usize),
+    /// A frame needs at least one data symbol
+    NoSymbols,
+}
+
+impl fmt::Display for OfdmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OfdmError::InvalidFftSize(size) => write!(f, "the FFT size must be even and at least 8, got {}", size),
+            OfdmError::InvalidCyclicPrefix { cyclic_prefix, fft_size } => write!(f, "cyclic prefix of {} samples is longer than the FFT size {}", cyclic_prefix, fft_size),
+            OfdmError::InvalidCarrier(carrier) => write!(f, "subcarrier {} is at DC, out of range or used twice", carrier),
+            OfdmError::TooFewCarriers(count) => write!(f, "need data subcarriers and at least 4 subcarriers in use, got {}", count),
+            OfdmError::NoSymbols => write!(f, "a frame needs at least one data symbol"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for OfdmError {}
+
+/// ±1 from the 7 bit scrambler of 802.11, x^7 + x^4 + 1, starting from `seed`
+fn pseudo_random(n: usize, seed: u8) -> Vec<f64> {
+    let mut state = seed & 0x7f;
+
+    (0..n).map(|_| {
+        let bit = ((state >> 6) ^ (state >> 3)) & 1;
+        state = ((state << 1) | bit) & 0x7f;
+        if bit == 1 { -1.0 } else { 1.0 }
+    }).collect()
+}
+
+/// Layout of an OFDM frame: the FFT size, cyclic prefix, which subcarriers carry data and
+/// pilots, and the constellation on the data subcarriers. Subcarriers are numbered from
+/// -fft_size/2 to fft_size/2 - 1 with 0 at DC.
+///
+/// A frame is a two symbol Schmidl-Cox preamble followed by `symbols_per_frame` data symbols,
+/// each with its cyclic prefix, and `gap` samples of silence. The first preamble symbol only
+/// uses even subcarriers, so its two halves are the same, and the second uses every subcarrier
+/// for channel estimation. Pilots are fixed BPSK values.
+#[derive(Clone, Debug, PartialEq)]
+pub struct OfdmConfig {
+    pub fft_size: usize,
+    pub cyclic_prefix: usize,
+    pub data_carriers: Vec<i64>,
+    pub pilot_carriers: Vec<i64>,
+    pub constellation: Constellation,
+    pub symbols_per_frame: usize,
+    /// Samples of silence after every frame
+    pub gap: usize,
+
+    pilots: Vec<Complex<f64>>,
+    preamble: [Vec<Complex<f64>>; 2],
+}
+
+impl OfdmConfig {
+    /// Create a frame layout
+    /// - fft_size: usize - The number of subcarriers, even and at least 8
+    /// - cyclic_prefix: usize - The samples repeated before each symbol, longer than the delay
+    ///   spread of the channel
+    /// - data_carriers: Vec<i64> - The subcarriers carrying data, in the order bits fill them
+    /// - pilot_carriers: Vec<i64> - The subcarriers carrying pilots
+    /// - constellation: Constellation - The points on the data subcarriers
+    /// - symbols_per_frame: usize - The number of data symbols after each preamble
+    pub fn new(fft_size: usize, cyclic_prefix: usize, data_carriers: Vec<i64>, pilot_carriers: Vec<i64>, constellation: Constellation, symbols_per_frame: usize) -> Result<OfdmConfig, OfdmError> {
+        if fft_size < 8 || !fft_size.is_multiple_of(2) {
+            return Err(OfdmError::InvalidFftSize(fft_size));
+        }
+        if cyclic_prefix > fft_size {
+            return Err(OfdmError::InvalidCyclicPrefix { cyclic_prefix, fft_size });
+        }
+        if symbols_per_frame == 0 {
+            return Err(OfdmError::NoSymbols);
+        }
+
+        let half = (fft_size / 2) as i64;
+        let mut used = vec![false; fft_size];
+        for carrier in data_carriers.iter().chain(pilot_carriers.iter()) {
+            if *carrier == 0 || *carrier < -half || *carrier >= half || used[carrier.rem_euclid(fft_size as i64) as usize] {
+                return Err(OfdmError::InvalidCarrier(*carrier));
+            }
+            used[carrier.rem_euclid(fft_size as i64) as usize] = true;
+        }
+
+        let count = data_carriers.len() + pilot_carriers.len();
+        if data_carriers.is_empty() || count < 4 {
+            return Err(OfdmError::TooFewCarriers(count));
+        }
+
+        let pilots = pseudo_random(pilot_carriers.len(), 0x5d).iter().map(|p| Complex::new(*p, 0.0)).collect();
+
+        // Even subcarriers at √2 in the first symbol keep its power the same as the others
+        let first = pseudo_random(fft_size, 0x7f);
+        let second = pseudo_random(fft_size, 0x2b);
+        let mut preamble = [vec![Complex::new(0.0, 0.0); fft_size], vec![Complex::new(0.0, 0.0); fft_size]];
+        for bin in (0..fft_size).filter(|bin| used[*bin]) {
+            if bin.is_multiple_of(2) {
+                preamble[0][bin] = Complex::new(first[bin] * SQRT_2, 0.0);
+            }
+            preamble[1][bin] = Complex::new(second[bin], 0.0);
+        }
+
+        Ok(OfdmConfig {
+            fft_size,
+            cyclic_prefix,
+            data_carriers,
+            pilot_carriers,
+            constellation,
+            symbols_per_frame,
+            gap: fft_size,
+            pilots,
+            preamble,
+        })
+    }
+
+    /// A layout shaped like 802.11a for any multiple of 64 subcarriers: a quarter symbol cyclic
+    /// prefix, 13/16 of the band in use around an empty DC subcarrier, and pilots at ±7/64 and
+    /// ±21/64 of the FFT size
+    /// - fft_size: usize - The number of subcarriers, a multiple of 64
+    /// - constellation: Constellation - The points on the data subcarriers
+    /// - symbols_per_frame: usize - The number of data symbols after each preamble
+    pub fn standard(fft_size: usize, constellation: Constellation, symbols_per_frame: usize) -> Result<OfdmConfig, OfdmError> {
+        if fft_size == 0 || !fft_size.is_multiple_of(64) {
+            return Err(OfdmError::InvalidFftSize(fft_size));
+        }
+
+        let scale = (fft_size / 64) as i64;
+        let edge = 26 * scale;
+        let pilots: Vec<i64> = [-21, -7, 7, 21].iter().map(|k| k * scale).collect();
+        let data = (-edge..=edge).filter(|k| *k != 0 && !pilots.contains(k)).collect();
+
+        OfdmConfig::new(fft_size, fft_size / 4, data, pilots, constellation, symbols_per_frame)
+    }
+
+    /// FFT bin of a subcarrier
+    pub fn bin(&self, carrier: i64) -> usize {
+        carrier.rem_euclid(self.fft_size as i64) as usize
+    }
+
+    /// The pilot value on each pilot subcarrier
+    pub fn pilots(&self) -> &[Complex<f64>] {
+        &self.pilots
+    }
+
+    /// The spectrum of each preamble symbol, indexed by FFT bin
+    pub fn preamble(&self) -> &[Vec<Complex<f64>>; 2] {
+        &self.preamble
+    }
+
+    /// Samples in a symbol with its cyclic prefix
+    pub fn symbol_length(&self) -> usize {
+        self.fft_size + self.cyclic_prefix
+    }
+
+    /// Samples in a frame, including the gap after it
+    pub fn frame_length(&self) -> usize {
+        (2 + self.symbols_per_frame) * self.symbol_length() + self.gap
+    }
+
+    /// Bits carried by one data symbol
+    pub fn bits_per_symbol(&self) -> usize {
+        self.data_carriers.len() * self.constellation.bits_per_symbol()
+    }
+
+    /// Bits carried by one frame
+    pub fn bits_per_frame(&self) -> usize {
+        self.symbols_per_frame * self.bits_per_symbol()
+    }
+
+    /// Turn a spectrum into a symbol with its cyclic prefix, scaled so a symbol using every
+    /// subcarrier at unit power has unit average power
+    pub(crate) fn symbol(&self, plan: &FftPlan, spectrum: &[Complex<f64>]) -> Vec<Complex<f64>> {
+        let used = (self.data_carriers.len() + self.pilot_carriers.len()) as f64;
+        let scale = libm::sqrt(self.fft_size as f64 / used);
+
+        let mut time = spectrum.to_vec();
+        plan.ifft(&mut time);
+        time.iter_mut().for_each(|x| *x *= scale);
+
+        let mut symbol = time[self.fft_size - self.cyclic_prefix..].to_vec();
+        symbol.extend_from_slice(&time);
+        symbol
+    }
+}
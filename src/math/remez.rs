@@ -0,0 +1,333 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::f64::consts::PI;
+
+use crate::math::filter::{frequency_response, FilterError};
+
+/// Grid points per coefficient, the usual choice for Parks-McClellan
+const GRID_DENSITY: usize = 16;
+
+/// Iterations [`remez`] allows before giving up
+pub const MAX_ITERATIONS: usize = 100;
+
+/// A band of frequencies with the gain the filter should have there
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Band {
+    pub low: f64,
+    pub high: f64,
+    pub gain: f64,
+    pub weight: f64,
+}
+
+impl Band {
+    /// Create a new band
+    /// - low: f64 - lower edge of the band
+    /// - high: f64 - upper edge of the band
+    /// - gain: f64 - desired gain, 0 for a stopband
+    /// - weight: f64 - how much errors in this band count relative to the other bands
+    pub fn new(low: f64, high: f64, gain: f64, weight: f64) -> Band {
+        Band { low, high, gain, weight }
+    }
+}
+
+/// An equiripple filter and how well it meets its bands
+#[derive(Clone, Debug, PartialEq)]
+pub struct RemezDesign {
+    pub taps: Vec<f64>,
+    /// Largest deviation from the desired gain in any passband, in dB. Zero if there are no
+    /// passbands.
+    pub passband_ripple: f64,
+    /// Attenuation of the worst point in any stopband, in dB. Infinite if there are no stopbands.
+    pub stopband_attenuation: f64,
+    /// Weighted error the exchange converged to
+    pub deviation: f64,
+    pub iterations: usize,
+}
+
+/// Design an equiripple linear phase FIR filter with the Parks-McClellan (Remez exchange)
+/// algorithm. Frequencies are in the same unit as `sample_rate`, and bands must be in increasing
+/// order without overlapping. An even number of taps always has zero gain at half the sample rate,
+/// so high-pass and band-stop filters need an odd number of taps.
+pub fn remez(num_taps: usize, bands: &[Band], sample_rate: f64) -> Result<RemezDesign, FilterError> {
+    remez_with_limit(num_taps, bands, sample_rate, MAX_ITERATIONS)
+}
+
+/// [`remez`] with a custom limit on the number of exchange iterations
+pub fn remez_with_limit(num_taps: usize, bands: &[Band], sample_rate: f64, max_iterations: usize) -> Result<RemezDesign, FilterError> {
+    check(num_taps, bands, sample_rate)?;
+
+    let odd = num_taps % 2 == 1;
+    let coefficients = num_taps.div_ceil(2);
+    let grid = make_grid(coefficients, odd, bands, sample_rate);
+
+    if grid.len() <= coefficients {
+        return Err(FilterError::InvalidTaps(num_taps));
+    }
+
+    // Start with extremal frequencies spread evenly over the grid
+    let mut extremals: Vec<usize> = (0..=coefficients).map(|i| i * (grid.len() - 1) / coefficients).collect();
+
+    for iteration in 1..=max_iterations {
+        let (deviation, response) = solve(&grid, &extremals);
+        let error: Vec<f64> = grid.iter().map(|point| point.weight * (point.desired - response.evaluate(point.x))).collect();
+        let max_error = error.iter().fold(0.0, |max: f64, e| max.max(e.abs()));
+
+        // Too few alternations left to exchange, which no further iteration can fix
+        let Some(next) = find_extremals(&grid, &error, deviation, coefficients + 1) else {
+            return Err(FilterError::DidNotConverge { iterations: iteration });
+        };
+
+        if next == extremals || max_error - deviation.abs() <= 1e-9 * deviation.abs() {
+            let taps = impulse_response(num_taps, &response);
+            let (passband_ripple, stopband_attenuation) = measure(&taps, bands, sample_rate);
+
+            return Ok(RemezDesign { taps, passband_ripple, stopband_attenuation, deviation: deviation.abs(), iterations: iteration });
+        }
+
+        extremals = next;
+    }
+
+    Err(FilterError::DidNotConverge { iterations: max_iterations })
+}
+
+fn check(num_taps: usize, bands: &[Band], sample_rate: f64) -> Result<(), FilterError> {
+    if sample_rate.partial_cmp(&0.0) != Some(Ordering::Greater) {
+        return Err(FilterError::InvalidSampleRate(sample_rate));
+    }
+
+    if num_taps < 3 {
+        return Err(FilterError::InvalidTaps(num_taps));
+    }
+
+    if bands.is_empty() {
+        return Err(FilterError::NoBands);
+    }
+
+    let nyquist = sample_rate / 2.0;
+    let mut previous = -1.0;
+
+    for band in bands {
+        for frequency in [band.low, band.high] {
+            if frequency < 0.0 || frequency > nyquist || frequency.is_nan() {
+                return Err(FilterError::InvalidFrequency { frequency, nyquist });
+            }
+        }
+
+        if band.low >= band.high || band.low <= previous {
+            return Err(FilterError::InvalidBand { low: band.low, high: band.high });
+        }
+        previous = band.high;
+
+        if band.weight.partial_cmp(&0.0) != Some(Ordering::Greater) {
+            return Err(FilterError::InvalidWeight(band.weight));
+        }
+    }
+
+    // An even number of taps is always zero at the nyquist frequency
+    if num_taps.is_multiple_of(2) && bands.iter().any(|band| band.high >= nyquist && band.gain != 0.0) {
+        return Err(FilterError::InvalidTaps(num_taps));
+    }
+
+    Ok(())
+}
+
+/// A point on the frequency grid, with x = cos(2πf)
+struct GridPoint {
+    x: f64,
+    desired: f64,
+    weight: f64,
+    band: usize,
+}
+
+fn make_grid(coefficients: usize, odd: bool, bands: &[Band], sample_rate: f64) -> Vec<GridPoint> {
+    let spacing = 0.5 / (GRID_DENSITY * coefficients) as f64;
+    let mut grid = Vec::new();
+
+    for (index, band) in bands.iter().enumerate() {
+        let low = band.low / sample_rate;
+        let mut high = band.high / sample_rate;
+
+        // Even length filters are forced to zero at half the sample rate
+        if !odd {
+            high = high.min(0.5 - spacing);
+        }
+        if low > high {
+            continue;
+        }
+
+        let count = libm::ceil((high - low) / spacing).max(1.0) as usize;
+        for i in 0..=count {
+            let f = low + (high - low) * i as f64 / count as f64;
+
+            // Even length filters are cos(πf) times a cosine series, so fit that series instead
+            let scale = if odd { 1.0 } else { libm::cos(PI * f) };
+
+            grid.push(GridPoint {
+                x: libm::cos(2.0 * PI * f),
+                desired: band.gain / scale,
+                weight: band.weight * scale,
+                band: index,
+            });
+        }
+    }
+
+    grid
+}
+
+/// Polynomial in x = cos(2πf) through a set of points, evaluated with the barycentric formula
+struct Interpolator {
+    nodes: Vec<f64>,
+    values: Vec<f64>,
+    weights: Vec<f64>,
+}
+
+impl Interpolator {
+    fn new(nodes: Vec<f64>, values: Vec<f64>) -> Interpolator {
+        let weights = barycentric_weights(&nodes);
+        Interpolator { nodes, values, weights }
+    }
+
+    fn evaluate(&self, x: f64) -> f64 {
+        let mut numerator = 0.0;
+        let mut denominator = 0.0;
+
+        for ((node, value), weight) in self.nodes.iter().zip(self.values.iter()).zip(self.weights.iter()) {
+            let difference = x - node;
+            if difference == 0.0 {
+                return *value;
+            }
+
+            numerator += weight * value / difference;
+            denominator += weight / difference;
+        }
+
+        numerator / denominator
+    }
+}
+
+/// 1 / Π(x_k - x_j) for every node. Each difference is doubled, which keeps the products from
+/// underflowing for long filters and does not change the interpolant.
+fn barycentric_weights(nodes: &[f64]) -> Vec<f64> {
+    nodes.iter().enumerate().map(|(k, xk)| {
+        let product: f64 = nodes.iter().enumerate().filter(|(j, _)| *j != k).map(|(_, xj)| 2.0 * (xk - xj)).product();
+        1.0 / product
+    }).collect()
+}
+
+/// Find the deviation δ for which a polynomial alternates between desired ± δ / weight on the
+/// extremal frequencies, and that polynomial
+fn solve(grid: &[GridPoint], extremals: &[usize]) -> (f64, Interpolator) {
+    let nodes: Vec<f64> = extremals.iter().map(|i| grid[*i].x).collect();
+    let weights = barycentric_weights(&nodes);
+
+    let mut numerator = 0.0;
+    let mut denominator = 0.0;
+    let mut sign = 1.0;
+
+    for (i, weight) in extremals.iter().zip(weights.iter()) {
+        numerator += weight * grid[*i].desired;
+        denominator += sign * weight / grid[*i].weight;
+        sign = -sign;
+    }
+
+    let deviation = numerator / denominator;
+
+    let mut sign = 1.0;
+    let values = extremals.iter().map(|i| {
+        let value = grid[*i].desired - sign * deviation / grid[*i].weight;
+        sign = -sign;
+        value
+    }).collect();
+
+    (deviation, Interpolator::new(nodes, values))
+}
+
+/// Local extrema of the error that are at least as large as the current deviation, trimmed to
+/// `count` alternating points
+fn find_extremals(grid: &[GridPoint], error: &[f64], deviation: f64, count: usize) -> Option<Vec<usize>> {
+    let threshold = deviation.abs() * (1.0 - 1e-9);
+    let mut extremals: Vec<usize> = Vec::new();
+
+    for i in 0..grid.len() {
+        let e = error[i];
+        if e.abs() < threshold {
+            continue;
+        }
+
+        // Neighbours in other bands are on the far side of a transition band
+        let neighbours = [i.checked_sub(1), Some(i + 1)];
+        let is_peak = neighbours.into_iter().flatten().filter(|j| *j < grid.len() && grid[*j].band == grid[i].band).all(|j| {
+            if e > 0.0 { e >= error[j] } else { e <= error[j] }
+        });
+        if !is_peak {
+            continue;
+        }
+
+        // Keep only the larger of two neighbouring extrema with the same sign
+        match extremals.last() {
+            Some(last) if (error[*last] > 0.0) == (e > 0.0) => {
+                if e.abs() > error[*last].abs() {
+                    *extremals.last_mut().unwrap() = i;
+                }
+            }
+            _ => extremals.push(i),
+        }
+    }
+
+    while extremals.len() > count {
+        if error[extremals[0]].abs() < error[extremals[extremals.len() - 1]].abs() {
+            extremals.remove(0);
+        } else {
+            extremals.pop();
+        }
+    }
+
+    if extremals.len() == count {
+        Some(extremals)
+    } else {
+        None
+    }
+}
+
+/// Sample the amplitude response at n evenly spaced frequencies and turn it back into n taps
+fn impulse_response(num_taps: usize, response: &Interpolator) -> Vec<f64> {
+    let n = num_taps as f64;
+    let odd = num_taps % 2 == 1;
+    let middle = (n - 1.0) / 2.0;
+
+    let amplitude: Vec<f64> = (0..=(num_taps - 1) / 2).map(|j| {
+        let f = j as f64 / n;
+        let scale = if odd { 1.0 } else { libm::cos(PI * f) };
+        scale * response.evaluate(libm::cos(2.0 * PI * f))
+    }).collect();
+
+    (0..num_taps).map(|k| {
+        let t = k as f64 - middle;
+        let sum: f64 = amplitude.iter().enumerate().skip(1).map(|(j, a)| 2.0 * a * libm::cos(2.0 * PI * j as f64 * t / n)).sum();
+        (amplitude[0] + sum) / n
+    }).collect()
+}
+
+/// Measure the passband ripple and stopband attenuation of the finished filter
+fn measure(taps: &[f64], bands: &[Band], sample_rate: f64) -> (f64, f64) {
+    let mut ripple: f64 = 0.0;
+    let mut attenuation = f64::INFINITY;
+
+    for band in bands {
+        let points = GRID_DENSITY * taps.len();
+
+        for i in 0..=points {
+            let frequency = band.low + (band.high - band.low) * i as f64 / points as f64;
+            let response = frequency_response(taps, frequency, sample_rate);
+            let magnitude = libm::hypot(response.re, response.im);
+
+            if band.gain == 0.0 {
+                attenuation = attenuation.min(-20.0 * libm::log10(magnitude));
+            } else {
+                ripple = ripple.max((20.0 * libm::log10(magnitude / band.gain)).abs());
+            }
+        }
+    }
+
+    (ripple, attenuation)
+}
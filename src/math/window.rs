@@ -0,0 +1,120 @@
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+
+/// Tapering windows for spectral analysis and filter design
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Window {
+    Rectangular,
+    Hann,
+    Hamming,
+    Blackman,
+    /// Four term Blackman-Harris
+    BlackmanHarris,
+    /// Five term flat-top, for reading amplitudes off a spectrum accurately
+    FlatTop,
+    /// Kaiser window with shape parameter β. Larger β trades a wider main lobe for lower sidelobes.
+    Kaiser(f64),
+    /// Gaussian window with a standard deviation given as a fraction of half the window length,
+    /// usually 0.5 or less
+    Gaussian(f64),
+    /// Tukey window with the given fraction of the window tapered, 0 is rectangular and 1 is Hann
+    Tukey(f64),
+}
+
+impl Window {
+    /// Symmetric window of `n` points, whose first and last points mirror each other. Use this
+    /// when designing filters.
+    pub fn symmetric(&self, n: usize) -> Vec<f64> {
+        if n == 1 {
+            return alloc::vec![1.0];
+        }
+
+        (0..n).map(|k| self.sample(k as f64 / (n - 1) as f64)).collect()
+    }
+
+    /// Periodic window of `n` points, which is one period of an n point window that repeats. Use
+    /// this in front of an FFT.
+    pub fn periodic(&self, n: usize) -> Vec<f64> {
+        (0..n).map(|k| self.sample(k as f64 / n as f64)).collect()
+    }
+
+    /// Coherent gain of the periodic window of `n` points
+    pub fn coherent_gain(&self, n: usize) -> f64 {
+        coherent_gain(&self.periodic(n))
+    }
+
+    /// Equivalent noise bandwidth in bins of the periodic window of `n` points
+    pub fn enbw(&self, n: usize) -> f64 {
+        enbw(&self.periodic(n))
+    }
+
+    /// Value of the window at `x`, running from 0 at the first point to 1 at the point after a
+    /// periodic window ends
+    fn sample(&self, x: f64) -> f64 {
+        match *self {
+            Window::Rectangular => 1.0,
+            Window::Hann => cosine_sum(&[0.5, 0.5], x),
+            Window::Hamming => cosine_sum(&[0.54, 0.46], x),
+            Window::Blackman => cosine_sum(&[0.42, 0.5, 0.08], x),
+            Window::BlackmanHarris => cosine_sum(&[0.35875, 0.48829, 0.14128, 0.01168], x),
+            Window::FlatTop => cosine_sum(&[0.21557895, 0.41663158, 0.277263158, 0.083578947, 0.006947368], x),
+            Window::Kaiser(beta) => {
+                let t = 2.0 * x - 1.0;
+                bessel_i0(beta * libm::sqrt((1.0 - t * t).max(0.0))) / bessel_i0(beta)
+            }
+            Window::Gaussian(sigma) => {
+                let t = (2.0 * x - 1.0) / sigma;
+                libm::exp(-0.5 * t * t)
+            }
+            Window::Tukey(alpha) => {
+                let alpha = alpha.clamp(0.0, 1.0);
+                let edge = x.min(1.0 - x);
+
+                if edge < alpha / 2.0 {
+                    0.5 * (1.0 - libm::cos(2.0 * PI * edge / alpha))
+                } else {
+                    1.0
+                }
+            }
+        }
+    }
+}
+
+/// a0 - a1 cos(2πx) + a2 cos(4πx) - ...
+fn cosine_sum(coefficients: &[f64], x: f64) -> f64 {
+    coefficients.iter().enumerate().map(|(k, a)| {
+        let sign = if k % 2 == 0 { 1.0 } else { -1.0 };
+        sign * a * libm::cos(2.0 * PI * k as f64 * x)
+    }).sum()
+}
+
+/// Modified Bessel function of the first kind of order zero, from its power series
+pub fn bessel_i0(x: f64) -> f64 {
+    let quarter_x2 = x * x / 4.0;
+    let mut term = 1.0;
+    let mut sum = 1.0;
+    let mut k = 1.0;
+
+    while term > sum * 1e-17 {
+        term *= quarter_x2 / (k * k);
+        sum += term;
+        k += 1.0;
+    }
+
+    sum
+}
+
+/// Average value of a window, which is how much it scales the amplitude of a tone in the middle
+/// of a bin
+pub fn coherent_gain(window: &[f64]) -> f64 {
+    window.iter().sum::<f64>() / window.len() as f64
+}
+
+/// Equivalent noise bandwidth of a window in bins, the width of a rectangular filter that would
+/// pass the same amount of white noise
+pub fn enbw(window: &[f64]) -> f64 {
+    let sum: f64 = window.iter().sum();
+    let sum_squares: f64 = window.iter().map(|w| w * w).sum();
+
+    window.len() as f64 * sum_squares / (sum * sum)
+}
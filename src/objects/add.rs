@@ -0,0 +1,52 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use num::Complex;
+
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Sums any number of inputs sample by sample
+#[derive(Clone)]
+pub struct Add {
+    pub sample_type: Type,
+    pub inputs: usize,
+}
+
+impl Add {
+    /// Create a new adder
+    /// - sample_type: Type - The type of every input and of the output
+    /// - inputs: usize - The number of input ports
+    pub fn new(sample_type: Type, inputs: usize) -> Add {
+        Add { sample_type, inputs }
+    }
+}
+
+impl DSPObject for Add {
+    fn input_types(&self) -> Vec<Type> {
+        vec![self.sample_type; self.inputs]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![self.sample_type]
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let n = inputs[0].len();
+
+        match &mut outputs[0] {
+            Samples::F64(output) => {
+                output.resize(n, 0.0);
+                for input in inputs {
+                    output.iter_mut().zip(input.as_f64()).for_each(|(y, x)| *y += x);
+                }
+            }
+            Samples::Complex(output) => {
+                output.resize(n, Complex::new(0.0, 0.0));
+                for input in inputs {
+                    output.iter_mut().zip(input.as_complex()).for_each(|(y, x)| *y += x);
+                }
+            }
+            Samples::NONE => {}
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Automatic gain control with a first order loop: after every sample the gain moves by `rate`
+/// times the difference between the reference level and the output magnitude. Levels are
+/// magnitudes, so a real signal settles with its average absolute value at the reference.
+#[derive(Clone)]
+pub struct Agc {
+    pub sample_type: Type,
+    pub reference: f64,
+    pub rate: f64,
+    pub max_gain: f64,
+
+    gain: f64,
+}
+
+impl Agc {
+    /// Create a new AGC starting at unity gain
+    /// - sample_type: Type - The type of the input and output
+    /// - reference: f64 - The output magnitude to aim for
+    /// - rate: f64 - How far the gain moves per sample and unit of error, e.g. 1e-4
+    /// - max_gain: f64 - The largest gain to apply, which stops noise being blown up when there
+    ///   is no signal
+    pub fn new(sample_type: Type, reference: f64, rate: f64, max_gain: f64) -> Agc {
+        Agc { sample_type, reference, rate, max_gain, gain: 1.0 }
+    }
+
+    /// The gain applied to the last sample
+    pub fn gain(&self) -> f64 {
+        self.gain
+    }
+}
+
+impl DSPObject for Agc {
+    fn input_types(&self) -> Vec<Type> {
+        vec![self.sample_type]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![self.sample_type]
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let rate = self.rate;
+        level(&inputs[0], &mut outputs[0], &mut self.gain, self.reference, self.max_gain, |_| rate);
+    }
+}
+
+/// Apply a gain that follows the output level, one sample at a time. `rate` gives the loop gain
+/// for an error, the reference minus the output magnitude, so attack and decay can differ.
+pub(crate) fn level(input: &Samples, output: &mut Samples, gain: &mut f64, reference: f64, max_gain: f64, rate: impl Fn(f64) -> f64) {
+    let update = |gain: &mut f64, magnitude: f64| {
+        let error = reference - magnitude;
+        *gain = (*gain + rate(error) * error).clamp(0.0, max_gain);
+    };
+
+    match (input, output) {
+        (Samples::F64(input), Samples::F64(output)) => {
+            for x in input {
+                let y = x * *gain;
+                output.push(y);
+                update(gain, y.abs());
+            }
+        }
+        (Samples::Complex(input), Samples::Complex(output)) => {
+            for x in input {
+                let y = x * *gain;
+                output.push(y);
+                update(gain, y.norm());
+            }
+        }
+        _ => {}
+    }
+}
@@ -0,0 +1,100 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::FRAC_1_SQRT_2;
+
+use crate::objects::am_modulator::AmMode;
+use crate::objects::costas_loop::{CostasLoop, Psk};
+use crate::objects::object::{DSPObject, Samples, Type};
+use crate::objects::rational_resampler::RationalResampler;
+
+/// Time constant of the carrier level tracker for full carrier AM, in seconds
+const CARRIER_TIME: f64 = 0.05;
+
+/// Bandwidth of the carrier recovery loop for suppressed carrier AM, in Hz
+const CARRIER_BANDWIDTH: f64 = 20.0;
+
+/// Double sideband AM demodulator, undoing an [`crate::objects::am_modulator::AmModulator`]
+/// with the same settings. The input is brought down to the audio rate first, which also
+/// filters out everything beyond the audio band. A full carrier is demodulated from the
+/// envelope, so the carrier does not have to be exactly at 0 Hz, and the envelope is divided by
+/// its average so the output does not depend on the signal level. A suppressed carrier is
+/// recovered with a BPSK Costas loop, which leaves the sign of the audio ambiguous.
+#[derive(Clone)]
+pub struct AmDemodulator {
+    pub mode: AmMode,
+    pub modulation_index: f64,
+    pub audio_rate: f64,
+    pub sample_rate: f64,
+
+    resampler: RationalResampler,
+    carrier: CostasLoop,
+    // Average envelope, which is the carrier amplitude
+    level: f64,
+}
+
+impl AmDemodulator {
+    /// Create a new AM demodulator
+    /// - mode: AmMode - Whether the carrier was sent
+    /// - modulation_index: f64 - The gain from audio to amplitude the modulator used
+    /// - audio_rate: f64 - The sample rate of the audio output (in Hz), a whole number
+    /// - sample_rate: f64 - The sample rate of the input (in Hz), a whole number
+    pub fn new(mode: AmMode, modulation_index: f64, audio_rate: f64, sample_rate: f64) -> AmDemodulator {
+        AmDemodulator {
+            mode,
+            modulation_index,
+            audio_rate,
+            sample_rate,
+            resampler: RationalResampler::between(Type::Complex, sample_rate, audio_rate),
+            carrier: CostasLoop::new(Psk::Bpsk, CARRIER_BANDWIDTH, FRAC_1_SQRT_2, audio_rate),
+            level: 0.0,
+        }
+    }
+
+    /// Frequency offset the carrier recovery has locked to (in Hz), only for a suppressed carrier
+    pub fn frequency(&self) -> f64 {
+        self.carrier.frequency()
+    }
+}
+
+impl DSPObject for AmDemodulator {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::F64]
+    }
+
+    fn input_sample_rate(&self) -> Option<f64> {
+        Some(self.sample_rate)
+    }
+
+    fn output_sample_rate(&self, _input: Option<f64>) -> Option<f64> {
+        Some(self.audio_rate)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let mut baseband = [Samples::new(Type::Complex)];
+        self.resampler.work(inputs, &mut baseband);
+        let output = outputs[0].as_f64_mut();
+
+        match self.mode {
+            AmMode::FullCarrier => {
+                let alpha = 1.0 - libm::exp(-1.0 / (CARRIER_TIME * self.audio_rate));
+                for x in baseband[0].as_complex() {
+                    let envelope = x.norm();
+                    self.level += alpha * (envelope - self.level);
+
+                    let audio = if self.level > 0.0 { (envelope / self.level - 1.0) / self.modulation_index } else { 0.0 };
+                    output.push(audio);
+                }
+            }
+            AmMode::SuppressedCarrier => {
+                let mut locked = [Samples::new(Type::Complex), Samples::new(Type::F64), Samples::new(Type::F64)];
+                let [baseband] = baseband;
+                self.carrier.work(&[baseband], &mut locked);
+                output.extend(locked[0].as_complex().iter().map(|y| y.re / self.modulation_index));
+            }
+        }
+    }
+}
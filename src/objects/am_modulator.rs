@@ -0,0 +1,78 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use num::Complex;
+
+use crate::objects::object::{DSPObject, Samples, Type};
+use crate::objects::rational_resampler::RationalResampler;
+
+/// Kinds of double sideband amplitude modulation
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AmMode {
+    /// DSB-FC, the audio rides on a carrier and can be recovered from the envelope
+    FullCarrier,
+    /// DSB-SC, no carrier is sent, so the receiver has to recover it
+    SuppressedCarrier,
+}
+
+/// Double sideband AM on the complex bus. Audio at the audio rate is brought up to the sample
+/// rate and sets the amplitude of a carrier at 0 Hz: `1 + m * audio` with a full carrier and
+/// `m * audio` without one, where m is the modulation index. The audio should stay within ±1, so
+/// an index of at most 1 keeps a full carrier envelope from crossing zero.
+#[derive(Clone)]
+pub struct AmModulator {
+    pub mode: AmMode,
+    pub modulation_index: f64,
+    pub audio_rate: f64,
+    pub sample_rate: f64,
+
+    resampler: RationalResampler,
+}
+
+impl AmModulator {
+    /// Create a new AM modulator
+    /// - mode: AmMode - Whether to send the carrier
+    /// - modulation_index: f64 - The gain from audio to amplitude, relative to the carrier
+    /// - audio_rate: f64 - The sample rate of the audio input (in Hz), a whole number
+    /// - sample_rate: f64 - The sample rate of the output (in Hz), a whole number
+    pub fn new(mode: AmMode, modulation_index: f64, audio_rate: f64, sample_rate: f64) -> AmModulator {
+        AmModulator {
+            mode,
+            modulation_index,
+            audio_rate,
+            sample_rate,
+            resampler: RationalResampler::between(Type::F64, audio_rate, sample_rate),
+        }
+    }
+}
+
+impl DSPObject for AmModulator {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::F64]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn input_sample_rate(&self) -> Option<f64> {
+        Some(self.audio_rate)
+    }
+
+    fn output_sample_rate(&self, _input: Option<f64>) -> Option<f64> {
+        Some(self.sample_rate)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let mut audio = [Samples::new(Type::F64)];
+        self.resampler.work(inputs, &mut audio);
+
+        let carrier = match self.mode {
+            AmMode::FullCarrier => 1.0,
+            AmMode::SuppressedCarrier => 0.0,
+        };
+
+        let output = outputs[0].as_complex_mut();
+        output.extend(audio[0].as_f64().iter().map(|a| Complex::new(carrier + self.modulation_index * a, 0.0)));
+    }
+}
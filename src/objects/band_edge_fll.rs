@@ -0,0 +1,124 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::{FRAC_1_SQRT_2, PI};
+
+use num::Complex;
+
+use crate::math::control_loop::ControlLoop;
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Frequency locked loop for root raised cosine shaped signals, using a pair of filters on the
+/// upper and lower edges of the signal band. An offset carrier puts more power in one edge than
+/// the other, and the loop turns until they balance. It pulls in offsets of up to about half the
+/// symbol rate without knowing anything about the symbols, which makes it a good first stage in
+/// front of a [`crate::objects::costas_loop::CostasLoop`].
+///
+/// Output 0 is the input shifted by the frequency estimate, and output 1 is the frequency
+/// estimate (in Hz) for every sample, which may be left unconnected. The loop gain depends on the
+/// signal power, so the input should be at around unit power, e.g. after an AGC.
+#[derive(Clone)]
+pub struct BandEdgeFll {
+    pub samples_per_symbol: f64,
+    pub rolloff: f64,
+    pub sample_rate: f64,
+
+    // Both stored back to front like the taps of a FIR filter
+    upper: Vec<Complex<f64>>,
+    lower: Vec<Complex<f64>>,
+    history: Vec<Complex<f64>>,
+    control: ControlLoop,
+}
+
+impl BandEdgeFll {
+    /// Create a new band edge FLL
+    /// - samples_per_symbol: f64 - The number of samples per symbol of the input
+    /// - rolloff: f64 - The excess bandwidth of the root raised cosine shaping, between 0 and 1
+    /// - filter_size: usize - The number of taps of the band edge filters, a few symbols long
+    /// - bandwidth: f64 - The loop bandwidth (in Hz), small compared to the sample rate
+    /// - sample_rate: f64 - The sample rate of the input (in Hz)
+    pub fn new(samples_per_symbol: f64, rolloff: f64, filter_size: usize, bandwidth: f64, sample_rate: f64) -> BandEdgeFll {
+        assert!(samples_per_symbol >= 1.0, "a band edge FLL needs at least one sample per symbol");
+        assert!(filter_size > 0, "the band edge filters need at least one tap");
+
+        let lower = band_edge_taps(samples_per_symbol, rolloff, filter_size);
+        let upper = lower.iter().map(|tap| tap.conj()).collect();
+        let max_frequency = (4.0 * PI / samples_per_symbol).min(PI);
+
+        BandEdgeFll {
+            samples_per_symbol,
+            rolloff,
+            sample_rate,
+            upper,
+            lower,
+            history: vec![Complex::new(0.0, 0.0); filter_size - 1],
+            control: ControlLoop::new(2.0 * PI * bandwidth / sample_rate, FRAC_1_SQRT_2, max_frequency),
+        }
+    }
+
+    /// Change the loop bandwidth (in Hz)
+    pub fn set_bandwidth(&mut self, bandwidth: f64) {
+        self.control.set_bandwidth(2.0 * PI * bandwidth / self.sample_rate);
+    }
+
+    /// Frequency estimate (in Hz)
+    pub fn frequency(&self) -> f64 {
+        self.control.frequency() * self.sample_rate / (2.0 * PI)
+    }
+}
+
+/// The lower band edge filter, reversed: the derivative of the raised cosine spectrum at the
+/// band edge is approximated by a half sine pulse, shifted down to -(1 + rolloff) / 2 symbol rates
+fn band_edge_taps(samples_per_symbol: f64, rolloff: f64, filter_size: usize) -> Vec<Complex<f64>> {
+    let sinc = |x: f64| if x == 0.0 { 1.0 } else { libm::sin(PI * x) / (PI * x) };
+    let middle = (filter_size - 1) as f64 / 2.0;
+
+    let baseband: Vec<f64> = (0..filter_size).map(|i| {
+        let k = (i as f64 - middle) * 2.0 / samples_per_symbol;
+        sinc(rolloff * k - 0.5) + sinc(rolloff * k + 0.5)
+    }).collect();
+    let power: f64 = baseband.iter().sum();
+
+    baseband.iter().enumerate().rev().map(|(i, tap)| {
+        let k = (i as f64 - middle) / (2.0 * samples_per_symbol);
+        Complex::from_polar(tap / power, -2.0 * PI * (1.0 + rolloff) * k)
+    }).collect()
+}
+
+impl DSPObject for BandEdgeFll {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::Complex, Type::F64]
+    }
+
+    fn input_sample_rate(&self) -> Option<f64> {
+        Some(self.sample_rate)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let [output, frequency] = outputs else {
+            return;
+        };
+        let (output, frequency) = (output.as_complex_mut(), frequency.as_f64_mut());
+        let n = self.upper.len();
+
+        for x in inputs[0].as_complex() {
+            let y = x * Complex::from_polar(1.0, -self.control.phase());
+            self.history.push(y);
+
+            let window = &self.history[self.history.len() - n..];
+            let upper: Complex<f64> = window.iter().zip(self.upper.iter()).map(|(x, tap)| x * tap).sum();
+            let lower: Complex<f64> = window.iter().zip(self.lower.iter()).map(|(x, tap)| x * tap).sum();
+
+            output.push(y);
+            frequency.push(self.frequency());
+
+            // More power in the upper edge means the signal sits above zero
+            self.control.advance(upper.norm_sqr() - lower.norm_sqr());
+        }
+
+        self.history.drain(..self.history.len() + 1 - n);
+    }
+}
@@ -0,0 +1,109 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use num::Complex;
+
+use crate::math::cic::{bit_growth, quantize, Combs, Integrators};
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Cascaded integrator-comb decimator. Divides the sample rate by a large factor without any
+/// multiplications, at the cost of a sinc^N shaped passband that
+/// [`crate::math::cic::compensator`] can flatten afterwards. Samples are quantized to `bits`
+/// bits and run through 64 bit integer registers, so the output has unity gain at DC and the
+/// only error is the quantization of the input.
+#[derive(Clone)]
+pub struct CicDecimator {
+    pub sample_type: Type,
+    pub decimation: usize,
+    pub order: usize,
+    pub delay: usize,
+    pub bits: u32,
+
+    // One pair of stages for real streams, two for complex streams
+    channels: Vec<(Integrators, Combs)>,
+    phase: usize,
+}
+
+impl CicDecimator {
+    /// Create a new CIC decimator
+    /// - sample_type: Type - The type of the input and output
+    /// - decimation: usize - R, the factor to divide the sample rate by
+    /// - order: usize - N, the number of integrator and comb stages
+    /// - delay: usize - M, the differential delay of the combs, usually 1 or 2
+    /// - bits: u32 - The number of bits to quantize the input to, with ±1.0 as full scale
+    pub fn new(sample_type: Type, decimation: usize, order: usize, delay: usize, bits: u32) -> CicDecimator {
+        assert!(decimation > 0 && order > 0 && delay > 0, "decimation, order and delay must be at least 1");
+        assert!(bits >= 2 && bits + bit_growth(order, delay, decimation) <= 64, "the registers need {} bits, more than the 64 available", bits + bit_growth(order, delay, decimation));
+
+        let channels = match sample_type {
+            Type::NONE => 0,
+            Type::F64 => 1,
+            Type::Complex => 2,
+        };
+
+        CicDecimator {
+            sample_type,
+            decimation,
+            order,
+            delay,
+            bits,
+            channels: vec![(Integrators::new(order), Combs::new(order, delay)); channels],
+            phase: 0,
+        }
+    }
+
+    /// Gain of the integer stages at DC times the input full scale, which every output is divided by
+    fn scale(&self) -> f64 {
+        libm::pow((self.decimation * self.delay) as f64, self.order as f64) * (1i64 << (self.bits - 1)) as f64
+    }
+
+    /// Integrate one sample on one channel, returning the comb output when a sample is due
+    fn step(&mut self, channel: usize, x: f64, due: bool) -> Option<i64> {
+        let (integrators, combs) = &mut self.channels[channel];
+        let y = integrators.push(quantize(x, self.bits));
+
+        due.then(|| combs.push(y))
+    }
+}
+
+impl DSPObject for CicDecimator {
+    fn input_types(&self) -> Vec<Type> {
+        vec![self.sample_type]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![self.sample_type]
+    }
+
+    fn output_sample_rate(&self, input: Option<f64>) -> Option<f64> {
+        input.map(|rate| rate / self.decimation as f64)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let scale = self.scale();
+
+        for i in 0..inputs[0].len() {
+            self.phase += 1;
+            let due = self.phase == self.decimation;
+            if due {
+                self.phase = 0;
+            }
+
+            match (&inputs[0], &mut outputs[0]) {
+                (Samples::F64(input), Samples::F64(output)) => {
+                    if let Some(y) = self.step(0, input[i], due) {
+                        output.push(y as f64 / scale);
+                    }
+                }
+                (Samples::Complex(input), Samples::Complex(output)) => {
+                    let re = self.step(0, input[i].re, due);
+                    let im = self.step(1, input[i].im, due);
+                    if let (Some(re), Some(im)) = (re, im) {
+                        output.push(Complex::new(re as f64 / scale, im as f64 / scale));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use num::Complex;
+
+use crate::math::cic::{bit_growth, quantize, Combs, Integrators};
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Cascaded integrator-comb interpolator. Multiplies the sample rate by a large factor without
+/// any multiplications, at the cost of a sinc^N shaped passband that
+/// [`crate::math::cic::compensator`] can flatten beforehand. Samples are quantized to `bits`
+/// bits and run through 64 bit integer registers, and the output is scaled back to unity gain at
+/// DC.
+#[derive(Clone)]
+pub struct CicInterpolator {
+    pub sample_type: Type,
+    pub interpolation: usize,
+    pub order: usize,
+    pub delay: usize,
+    pub bits: u32,
+
+    // One pair of stages for real streams, two for complex streams
+    channels: Vec<(Combs, Integrators)>,
+}
+
+impl CicInterpolator {
+    /// Create a new CIC interpolator
+    /// - sample_type: Type - The type of the input and output
+    /// - interpolation: usize - R, the factor to multiply the sample rate by
+    /// - order: usize - N, the number of comb and integrator stages
+    /// - delay: usize - M, the differential delay of the combs, usually 1 or 2
+    /// - bits: u32 - The number of bits to quantize the input to, with ±1.0 as full scale
+    pub fn new(sample_type: Type, interpolation: usize, order: usize, delay: usize, bits: u32) -> CicInterpolator {
+        assert!(interpolation > 0 && order > 0 && delay > 0, "interpolation, order and delay must be at least 1");
+        assert!(bits >= 2 && bits + bit_growth(order, delay, interpolation) <= 64, "the registers need {} bits, more than the 64 available", bits + bit_growth(order, delay, interpolation));
+
+        let channels = match sample_type {
+            Type::NONE => 0,
+            Type::F64 => 1,
+            Type::Complex => 2,
+        };
+
+        CicInterpolator {
+            sample_type,
+            interpolation,
+            order,
+            delay,
+            bits,
+            channels: vec![(Combs::new(order, delay), Integrators::new(order)); channels],
+        }
+    }
+
+    /// Gain of the integer stages at DC times the input full scale, which every output is divided by
+    fn scale(&self) -> f64 {
+        let length = (self.interpolation * self.delay) as f64;
+        libm::pow(length, self.order as f64) / self.interpolation as f64 * (1i64 << (self.bits - 1)) as f64
+    }
+
+    /// Run one input sample on one channel through the combs, then the comb output followed by
+    /// R - 1 zeros through the integrators
+    fn step(&mut self, channel: usize, x: f64, output: &mut Vec<i64>) {
+        let (combs, integrators) = &mut self.channels[channel];
+        let y = combs.push(quantize(x, self.bits));
+
+        output.push(integrators.push(y));
+        for _ in 1..self.interpolation {
+            output.push(integrators.push(0));
+        }
+    }
+}
+
+impl DSPObject for CicInterpolator {
+    fn input_types(&self) -> Vec<Type> {
+        vec![self.sample_type]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![self.sample_type]
+    }
+
+    fn output_sample_rate(&self, input: Option<f64>) -> Option<f64> {
+        input.map(|rate| rate * self.interpolation as f64)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let scale = self.scale();
+        let mut re = Vec::with_capacity(self.interpolation);
+        let mut im = Vec::with_capacity(self.interpolation);
+
+        for i in 0..inputs[0].len() {
+            re.clear();
+            im.clear();
+
+            match (&inputs[0], &mut outputs[0]) {
+                (Samples::F64(input), Samples::F64(output)) => {
+                    self.step(0, input[i], &mut re);
+                    output.extend(re.iter().map(|y| *y as f64 / scale));
+                }
+                (Samples::Complex(input), Samples::Complex(output)) => {
+                    self.step(0, input[i].re, &mut re);
+                    self.step(1, input[i].im, &mut im);
+                    output.extend(re.iter().zip(im.iter()).map(|(re, im)| Complex::new(*re as f64 / scale, *im as f64 / scale)));
+                }
+                _ => {}
+            }
+        }
+    }
+}
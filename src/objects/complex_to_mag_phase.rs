@@ -0,0 +1,32 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Splits a complex stream into its magnitude (output 0) and phase in radians (output 1)
+#[derive(Clone, Default)]
+pub struct ComplexToMagPhase;
+
+impl ComplexToMagPhase {
+    pub fn new() -> ComplexToMagPhase {
+        ComplexToMagPhase
+    }
+}
+
+impl DSPObject for ComplexToMagPhase {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::F64, Type::F64]
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let input = inputs[0].as_complex();
+        let (magnitude, phase) = outputs.split_at_mut(1);
+
+        magnitude[0].as_f64_mut().extend(input.iter().map(|x| libm::hypot(x.re, x.im)));
+        phase[0].as_f64_mut().extend(input.iter().map(|x| libm::atan2(x.im, x.re)));
+    }
+}
@@ -0,0 +1,32 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Splits a complex stream into its real part (output 0) and imaginary part (output 1)
+#[derive(Clone, Default)]
+pub struct ComplexToRealImag;
+
+impl ComplexToRealImag {
+    pub fn new() -> ComplexToRealImag {
+        ComplexToRealImag
+    }
+}
+
+impl DSPObject for ComplexToRealImag {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::F64, Type::F64]
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let input = inputs[0].as_complex();
+        let (real, imag) = outputs.split_at_mut(1);
+
+        real[0].as_f64_mut().extend(input.iter().map(|x| x.re));
+        imag[0].as_f64_mut().extend(input.iter().map(|x| x.im));
+    }
+}
@@ -0,0 +1,62 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::math::constellation::Constellation;
+use crate::objects::object::{DSPObject, Samples, Type};
+use crate::objects::psk_modulator::BitFormat;
+
+/// Maps groups of bits onto the points of a [`Constellation`], one complex sample per symbol.
+/// Pulse shaping is left to the next block, e.g. a
+/// [`crate::objects::psk_modulator::pulse_shaper`].
+#[derive(Clone)]
+pub struct ConstellationMapper {
+    pub constellation: Constellation,
+    pub format: BitFormat,
+
+    // Bits waiting for enough company to make a symbol
+    pending: Vec<u8>,
+}
+
+impl ConstellationMapper {
+    /// Create a new constellation mapper
+    /// - constellation: Constellation - The points to map onto
+    /// - format: BitFormat - How the input carries bits
+    pub fn new(constellation: Constellation, format: BitFormat) -> ConstellationMapper {
+        ConstellationMapper {
+            constellation,
+            format,
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl DSPObject for ConstellationMapper {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::F64]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn output_sample_rate(&self, input: Option<f64>) -> Option<f64> {
+        let bits = self.format.bits_per_sample() as f64 / self.constellation.bits_per_symbol() as f64;
+        input.map(|rate| rate * bits)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let width = self.format.bits_per_sample();
+        for value in inputs[0].as_f64() {
+            let value = *value as usize;
+            self.pending.extend((0..width).rev().map(|bit| ((value >> bit) & 1) as u8));
+        }
+
+        let bits = self.constellation.bits_per_symbol();
+        let output = outputs[0].as_complex_mut();
+        for chunk in self.pending.chunks_exact(bits) {
+            let label = chunk.iter().fold(0, |value, bit| (value << 1) | *bit as usize);
+            output.push(self.constellation.point(label));
+        }
+        self.pending.drain(..output.len() * bits);
+    }
+}
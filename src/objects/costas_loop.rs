@@ -0,0 +1,181 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::{PI, SQRT_2};
+
+use num::Complex;
+
+use crate::math::constellation::{gray, Constellation};
+use crate::math::control_loop::ControlLoop;
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Phase shift keyed constellations a [`CostasLoop`] can lock to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Psk {
+    /// Two points at 0 and π
+    Bpsk,
+    /// Four points at π/4 + kπ/2
+    Qpsk,
+    /// Eight points at π/8 + kπ/4
+    Psk8,
+}
+
+impl Psk {
+    /// Number of points in the constellation
+    pub fn order(self) -> usize {
+        match self {
+            Psk::Bpsk => 2,
+            Psk::Qpsk => 4,
+            Psk::Psk8 => 8,
+        }
+    }
+
+    /// Number of bits each symbol carries
+    pub fn bits_per_symbol(self) -> usize {
+        self.order().trailing_zeros() as usize
+    }
+
+    /// Angle of the first point, every other point follows at multiples of 2π over the order
+    fn offset(self) -> f64 {
+        match self {
+            Psk::Bpsk => 0.0,
+            Psk::Qpsk => PI / 4.0,
+            Psk::Psk8 => PI / 8.0,
+        }
+    }
+
+    /// Unit amplitude point number `index`, counting counterclockwise
+    pub fn point(self, index: usize) -> Complex<f64> {
+        Complex::from_polar(1.0, self.offset() + 2.0 * PI * index as f64 / self.order() as f64)
+    }
+
+    /// Number of the point nearest to `y`
+    pub fn nearest(self, y: Complex<f64>) -> usize {
+        let sector = libm::round((libm::atan2(y.im, y.re) - self.offset()) * self.order() as f64 / (2.0 * PI));
+        (sector as i64).rem_euclid(self.order() as i64) as usize
+    }
+
+    /// The points with the same Gray mapping as
+    /// [`crate::objects::psk_modulator::PskModulator`], e.g. for soft demapping
+    pub fn constellation(self) -> Constellation {
+        let points = (0..self.order()).map(|index| self.point(index)).collect();
+        let labels = (0..self.order()).map(gray).collect();
+
+        Constellation::new(points, labels).expect("PSK constellations are always valid")
+    }
+
+    /// Phase error of a symbol rotated by the current estimate, limited to ±1
+    fn phase_error(self, y: Complex<f64>) -> f64 {
+        let sign = |x: f64| if x < 0.0 { -1.0 } else { 1.0 };
+
+        let error = match self {
+            Psk::Bpsk => y.re * y.im,
+            Psk::Qpsk => sign(y.re) * y.im - sign(y.im) * y.re,
+            Psk::Psk8 => {
+                let k = SQRT_2 - 1.0;
+                if y.re.abs() >= y.im.abs() {
+                    sign(y.re) * y.im - k * sign(y.im) * y.re
+                } else {
+                    k * sign(y.re) * y.im - sign(y.im) * y.re
+                }
+            }
+        };
+
+        error.clamp(-1.0, 1.0)
+    }
+}
+
+/// Phase error of a symbol against the nearest point of `constellation`, limited to ±1
+fn decision_error(constellation: &Constellation, y: Complex<f64>) -> f64 {
+    let decision = constellation.point(constellation.nearest(y));
+    ((y * decision.conj()).im / decision.norm_sqr()).clamp(-1.0, 1.0)
+}
+
+/// Costas loop that recovers the carrier of a PSK signal at one sample per symbol. Output 0 is
+/// the input rotated by the phase estimate, which puts the symbols on the constellation points
+/// of the [`Psk`] variant once the loop has locked, up to the usual ambiguity of a multiple of
+/// 2π over the number of points. Outputs 1 and 2 are the frequency estimate (in Hz) and the
+/// phase estimate (in radians) for every sample and may be left unconnected.
+///
+/// For QAM and APSK, set `decisions` to the constellation that was sent once the loop has
+/// pulled in with the PSK detector of the same symmetry, usually QPSK. The phase error then
+/// comes from the nearest point, which has far less self noise on dense constellations.
+#[derive(Clone)]
+pub struct CostasLoop {
+    pub psk: Psk,
+    pub sample_rate: f64,
+    /// Constellation for decision directed tracking, or None to use the PSK detector
+    pub decisions: Option<Constellation>,
+
+    control: ControlLoop,
+}
+
+impl CostasLoop {
+    /// Create a new Costas loop
+    /// - psk: Psk - The constellation to lock to
+    /// - bandwidth: f64 - The loop bandwidth (in Hz), small compared to the sample rate
+    /// - damping: f64 - The damping factor, 1/√2 for a critically damped loop
+    /// - sample_rate: f64 - The sample rate of the input (in Hz), usually the symbol rate
+    pub fn new(psk: Psk, bandwidth: f64, damping: f64, sample_rate: f64) -> CostasLoop {
+        CostasLoop {
+            psk,
+            sample_rate,
+            decisions: None,
+            control: ControlLoop::new(2.0 * PI * bandwidth / sample_rate, damping, PI),
+        }
+    }
+
+    /// Change the loop bandwidth (in Hz), e.g. to narrow it once the loop has locked
+    pub fn set_bandwidth(&mut self, bandwidth: f64) {
+        self.control.set_bandwidth(2.0 * PI * bandwidth / self.sample_rate);
+    }
+
+    /// Change the damping factor
+    pub fn set_damping(&mut self, damping: f64) {
+        self.control.set_damping(damping);
+    }
+
+    /// Frequency estimate (in Hz)
+    pub fn frequency(&self) -> f64 {
+        self.control.frequency() * self.sample_rate / (2.0 * PI)
+    }
+
+    /// Phase estimate (in radians)
+    pub fn phase(&self) -> f64 {
+        self.control.phase()
+    }
+}
+
+impl DSPObject for CostasLoop {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::Complex, Type::F64, Type::F64]
+    }
+
+    fn input_sample_rate(&self) -> Option<f64> {
+        Some(self.sample_rate)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let [output, frequency, phase] = outputs else {
+            return;
+        };
+        let (output, frequency, phase) = (output.as_complex_mut(), frequency.as_f64_mut(), phase.as_f64_mut());
+
+        for x in inputs[0].as_complex() {
+            let y = x * Complex::from_polar(1.0, -self.control.phase());
+
+            output.push(y);
+            frequency.push(self.frequency());
+            phase.push(self.control.phase());
+
+            let error = match &self.decisions {
+                Some(constellation) => decision_error(constellation, y),
+                None => self.psk.phase_error(y),
+            };
+            self.control.advance(error);
+        }
+    }
+}
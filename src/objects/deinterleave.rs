@@ -0,0 +1,49 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Splits one stream into several by handing each sample to the next output in turn. The
+/// position is kept between blocks, so blocks do not have to be a multiple of the number of
+/// outputs.
+#[derive(Clone)]
+pub struct Deinterleave {
+    pub sample_type: Type,
+    pub outputs: usize,
+
+    next: usize,
+}
+
+impl Deinterleave {
+    /// Create a new deinterleaver
+    /// - sample_type: Type - The type of the input and of every output
+    /// - outputs: usize - The number of output ports
+    pub fn new(sample_type: Type, outputs: usize) -> Deinterleave {
+        Deinterleave { sample_type, outputs, next: 0 }
+    }
+}
+
+impl DSPObject for Deinterleave {
+    fn input_types(&self) -> Vec<Type> {
+        vec![self.sample_type]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![self.sample_type; self.outputs]
+    }
+
+    fn output_sample_rate(&self, input: Option<f64>) -> Option<f64> {
+        input.map(|rate| rate / self.outputs as f64)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        for i in 0..inputs[0].len() {
+            match (&mut outputs[self.next], &inputs[0]) {
+                (Samples::F64(output), Samples::F64(input)) => output.push(input[i]),
+                (Samples::Complex(output), Samples::Complex(input)) => output.push(input[i]),
+                _ => {}
+            }
+            self.next = (self.next + 1) % self.outputs;
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::objects::agc::level;
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Automatic gain control that cuts the gain quickly when the output rises above the reference
+/// and raises it slowly when the output falls below, so a sudden strong signal is tamed within a
+/// few samples while short fades do not pump the noise up.
+#[derive(Clone)]
+pub struct FastAttackAgc {
+    pub sample_type: Type,
+    pub reference: f64,
+    pub attack: f64,
+    pub decay: f64,
+    pub max_gain: f64,
+
+    gain: f64,
+}
+
+impl FastAttackAgc {
+    /// Create a new fast attack AGC starting at unity gain
+    /// - sample_type: Type - The type of the input and output
+    /// - reference: f64 - The output magnitude to aim for
+    /// - attack: f64 - How fast the gain falls when the output is too loud, e.g. 1e-1
+    /// - decay: f64 - How fast the gain rises when the output is too quiet, e.g. 1e-3
+    /// - max_gain: f64 - The largest gain to apply, which stops noise being blown up when there
+    ///   is no signal
+    pub fn new(sample_type: Type, reference: f64, attack: f64, decay: f64, max_gain: f64) -> FastAttackAgc {
+        FastAttackAgc { sample_type, reference, attack, decay, max_gain, gain: 1.0 }
+    }
+
+    /// The gain applied to the last sample
+    pub fn gain(&self) -> f64 {
+        self.gain
+    }
+}
+
+impl DSPObject for FastAttackAgc {
+    fn input_types(&self) -> Vec<Type> {
+        vec![self.sample_type]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![self.sample_type]
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let (attack, decay) = (self.attack, self.decay);
+        level(&inputs[0], &mut outputs[0], &mut self.gain, self.reference, self.max_gain, |error| if error < 0.0 { attack } else { decay });
+    }
+}
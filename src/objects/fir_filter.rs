@@ -0,0 +1,89 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::{Add, Mul};
+
+use num::{Complex, Zero};
+
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Finite impulse response filter with real taps, for F64 or Complex streams. The last
+/// `taps.len() - 1` input samples are kept between blocks so the output does not depend on how
+/// the input is split into blocks.
+#[derive(Clone)]
+pub struct FirFilter {
+    pub sample_type: Type,
+
+    // Stored back to front so each output is a dot product with a contiguous window of input
+    reversed: Vec<f64>,
+    history: Samples,
+}
+
+impl FirFilter {
+    /// Create a new FIR filter
+    /// - sample_type: Type - The type of the input and output
+    /// - taps: Vec<f64> - The impulse response of the filter, e.g. from [`crate::math::filter::lowpass`]
+    pub fn new(sample_type: Type, taps: Vec<f64>) -> FirFilter {
+        assert!(!taps.is_empty(), "a FIR filter needs at least one tap");
+
+        let mut filter = FirFilter {
+            sample_type,
+            reversed: taps.iter().rev().cloned().collect(),
+            history: Samples::new(sample_type),
+        };
+        filter.reset();
+
+        filter
+    }
+
+    /// The impulse response of the filter
+    pub fn taps(&self) -> Vec<f64> {
+        self.reversed.iter().rev().cloned().collect()
+    }
+
+    /// Forget every past sample, as if the filter had only ever seen zeros
+    pub fn reset(&mut self) {
+        let n = self.reversed.len() - 1;
+
+        match &mut self.history {
+            Samples::F64(history) => *history = vec![0.0; n],
+            Samples::Complex(history) => *history = vec![Complex::new(0.0, 0.0); n],
+            Samples::NONE => {}
+        }
+    }
+}
+
+/// Convolve `input` with the reversed taps, using and then updating the samples kept from the
+/// previous block
+fn convolve<T>(reversed: &[f64], history: &mut Vec<T>, input: &[T], output: &mut Vec<T>)
+where
+    T: Copy + Zero + Add<Output = T> + Mul<f64, Output = T>,
+{
+    let n = input.len();
+    history.extend_from_slice(input);
+
+    output.reserve(n);
+    for window in history.windows(reversed.len()).take(n) {
+        let y = window.iter().zip(reversed.iter()).fold(T::zero(), |acc, (x, tap)| acc + *x * *tap);
+        output.push(y);
+    }
+
+    history.drain(..n);
+}
+
+impl DSPObject for FirFilter {
+    fn input_types(&self) -> Vec<Type> {
+        vec![self.sample_type]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![self.sample_type]
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        match (&mut self.history, &mut outputs[0]) {
+            (Samples::F64(history), Samples::F64(output)) => convolve(&self.reversed, history, inputs[0].as_f64(), output),
+            (Samples::Complex(history), Samples::Complex(output)) => convolve(&self.reversed, history, inputs[0].as_complex(), output),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,348 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::objects::object::{connect, new_node, DSPObject, NodeRef, Type};
+
+/// Handle to a block that has been added to a [`Flowgraph`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockId(usize);
+
+impl BlockId {
+    /// Refer to one of the output ports of this block
+    pub fn output(self, port: usize) -> Port {
+        Port { block: self, port }
+    }
+
+    /// Refer to one of the input ports of this block
+    pub fn input(self, port: usize) -> Port {
+        Port { block: self, port }
+    }
+
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// A numbered input or output port on a block
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Port {
+    pub block: BlockId,
+    pub port: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum FlowgraphError {
+    /// The block does not belong to this flowgraph
+    NoSuchBlock(BlockId),
+    /// The block exists but does not have an output port with this number
+    NoSuchOutput { block: &'static str, port: Port, outputs: usize },
+    /// The block exists but does not have an input port with this number
+    NoSuchInput { block: &'static str, port: Port, inputs: usize },
+    /// The output produces a different type than the input consumes
+    TypeMismatch { from_block: &'static str, from: Port, output_type: Type, to_block: &'static str, to: Port, input_type: Type },
+    /// More than one output is connected to the same input
+    InputAlreadyConnected { block: &'static str, port: Port },
+    /// An input port was left unconnected
+    InputNotConnected { block: &'static str, port: Port },
+    /// The connections form a loop through this block
+    Cycle { block: &'static str, id: BlockId },
+    /// An input receives samples at a different rate than the block's other inputs or than the
+    /// block was configured for
+    SampleRateMismatch { block: &'static str, port: Port, expected: f64, found: f64 },
+}
+
+impl fmt::Display for FlowgraphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlowgraphError::NoSuchBlock(id) => write!(f, "block {} is not part of this flowgraph", id.0),
+            FlowgraphError::NoSuchOutput { block, port, outputs } => write!(f, "{} (block {}) has {} output port(s), there is no output {}", block, port.block.0, outputs, port.port),
+            FlowgraphError::NoSuchInput { block, port, inputs } => write!(f, "{} (block {}) has {} input port(s), there is no input {}", block, port.block.0, inputs, port.port),
+            FlowgraphError::TypeMismatch { from_block, from, output_type, to_block, to, input_type } => write!(
+                f,
+                "can not connect output {} of {} (block {}) which produces {:?} to input {} of {} (block {}) which expects {:?}",
+                from.port, from_block, from.block.0, output_type, to.port, to_block, to.block.0, input_type
+            ),
+            FlowgraphError::InputAlreadyConnected { block, port } => write!(f, "input {} of {} (block {}) is connected more than once", port.port, block, port.block.0),
+            FlowgraphError::InputNotConnected { block, port } => write!(f, "input {} of {} (block {}) is not connected", port.port, block, port.block.0),
+            FlowgraphError::Cycle { block, id } => write!(f, "{} (block {}) is part of a loop, feedback connections are not supported", block, id.0),
+            FlowgraphError::SampleRateMismatch { block, port, expected, found } => write!(
+                f,
+                "input {} of {} (block {}) receives {} samples/s but expects {} samples/s",
+                port.port, block, port.block.0, found, expected
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FlowgraphError {}
+
+/// A graph of objects wired together port to port. Blocks are added with [`Flowgraph::add`] and
+/// wired with [`Flowgraph::connect`]. Nothing is checked until the graph is built, at which point
+/// every connection is validated against the port types reported by each object.
+///
+/// ```
+/// use superdsp::objects::flowgraph::Flowgraph;
+/// use superdsp::objects::object::{Samples, Type};
+/// use superdsp::objects::vector_sink::VectorSink;
+/// use superdsp::objects::vector_source::VectorSource;
+///
+/// let sink = VectorSink::new(Type::F64);
+///
+/// let mut flowgraph = Flowgraph::new();
+/// let src = flowgraph.add(VectorSource::new(Samples::F64(vec![1.0, 2.0, 3.0]), 2, false));
+/// let dst = flowgraph.add(sink.clone());
+/// flowgraph.connect(src.output(0), dst.input(0));
+///
+/// flowgraph.run().unwrap();
+/// assert_eq!(sink.samples(), Samples::F64(vec![1.0, 2.0, 3.0]));
+/// ```
+#[derive(Default)]
+pub struct Flowgraph {
+    nodes: Vec<NodeRef>,
+    names: Vec<&'static str>,
+    connections: Vec<(Port, Port)>,
+    // Number of connections at the front of `connections` whose buses are already wired
+    wired: usize,
+    built: bool,
+}
+
+/// The last path segment of a type name, e.g. `WaveStepGen` for
+/// `superdsp::objects::wave_gen::WaveStepGen`
+fn short_type_name<T: ?Sized>() -> &'static str {
+    let name = core::any::type_name::<T>();
+    let name = name.split('<').next().unwrap_or(name);
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+impl Flowgraph {
+    pub fn new() -> Flowgraph {
+        Flowgraph {
+            nodes: Vec::new(),
+            names: Vec::new(),
+            connections: Vec::new(),
+            wired: 0,
+            built: false,
+        }
+    }
+
+    /// Add an object to the graph. The graph owns the object from now on.
+    pub fn add<T: DSPObject + 'static>(&mut self, object: T) -> BlockId {
+        self.nodes.push(new_node(object));
+        self.names.push(short_type_name::<T>());
+        self.built = false;
+
+        BlockId(self.nodes.len() - 1)
+    }
+
+    /// Connect an output port to an input port. The connection is validated when the graph is
+    /// built.
+    pub fn connect(&mut self, from: Port, to: Port) {
+        self.connections.push((from, to));
+        self.built = false;
+    }
+
+    /// Get the node wrapping a block
+    pub fn node(&self, block: BlockId) -> Option<&NodeRef> {
+        self.nodes.get(block.0)
+    }
+
+    fn name(&self, block: BlockId) -> &'static str {
+        self.names[block.0]
+    }
+
+    /// Every node in the graph, indexed by [`BlockId::index`]
+    #[cfg(feature = "multithreading-std")]
+    pub(crate) fn nodes(&self) -> &[NodeRef] {
+        &self.nodes
+    }
+
+    /// Every connection in the graph as (output, input) pairs
+    #[cfg(feature = "multithreading-std")]
+    pub(crate) fn connections(&self) -> &[(Port, Port)] {
+        &self.connections
+    }
+
+    /// Check every connection and wire together the buses of those added since the last build
+    pub fn build(&mut self) -> Result<(), FlowgraphError> {
+        if self.built {
+            return Ok(());
+        }
+
+        self.validate()?;
+
+        for (from, to) in self.connections[self.wired..].iter() {
+            connect(&self.nodes[from.block.0], from.port, &self.nodes[to.block.0], to.port);
+        }
+        self.wired = self.connections.len();
+
+        self.built = true;
+        Ok(())
+    }
+
+    /// Sample rate of the outputs of a block, as worked out from the sources upstream of it. None
+    /// if the rate is not known or the graph is not valid.
+    pub fn sample_rate(&self, block: BlockId) -> Option<f64> {
+        self.validate().ok()?;
+        self.sample_rates().ok()?.get(block.0).copied().flatten()
+    }
+
+    /// Check every connection and that the sample rates agree, without wiring anything together
+    pub fn validate(&self) -> Result<(), FlowgraphError> {
+        let input_types: Vec<Vec<Type>> = self.nodes.iter().map(|node| node.lock().object().input_types()).collect();
+        let output_types: Vec<Vec<Type>> = self.nodes.iter().map(|node| node.lock().object().output_types()).collect();
+        let mut connected: Vec<Vec<bool>> = input_types.iter().map(|inputs| vec![false; inputs.len()]).collect();
+
+        for (from, to) in self.connections.iter() {
+            for port in [from, to] {
+                if port.block.0 >= self.nodes.len() {
+                    return Err(FlowgraphError::NoSuchBlock(port.block));
+                }
+            }
+
+            let outputs = &output_types[from.block.0];
+            if from.port >= outputs.len() {
+                return Err(FlowgraphError::NoSuchOutput { block: self.name(from.block), port: *from, outputs: outputs.len() });
+            }
+
+            let inputs = &input_types[to.block.0];
+            if to.port >= inputs.len() {
+                return Err(FlowgraphError::NoSuchInput { block: self.name(to.block), port: *to, inputs: inputs.len() });
+            }
+
+            if outputs[from.port] != inputs[to.port] {
+                return Err(FlowgraphError::TypeMismatch {
+                    from_block: self.name(from.block),
+                    from: *from,
+                    output_type: outputs[from.port],
+                    to_block: self.name(to.block),
+                    to: *to,
+                    input_type: inputs[to.port],
+                });
+            }
+
+            if connected[to.block.0][to.port] {
+                return Err(FlowgraphError::InputAlreadyConnected { block: self.name(to.block), port: *to });
+            }
+            connected[to.block.0][to.port] = true;
+        }
+
+        for (index, ports) in connected.iter().enumerate() {
+            if let Some(port) = ports.iter().position(|connected| !connected) {
+                return Err(FlowgraphError::InputNotConnected { block: self.names[index], port: BlockId(index).input(port) });
+            }
+        }
+
+        self.check_cycles()?;
+        self.sample_rates().map(|_| ())
+    }
+
+    /// Work out the output sample rate of every block, visiting each block once all of its
+    /// upstream blocks are done. Inputs with an unknown rate match anything.
+    fn sample_rates(&self) -> Result<Vec<Option<f64>>, FlowgraphError> {
+        let mut rates: Vec<Option<f64>> = vec![None; self.nodes.len()];
+        let mut waiting: Vec<usize> = vec![0; self.nodes.len()];
+        for (_, to) in self.connections.iter() {
+            waiting[to.block.0] += 1;
+        }
+
+        let mut ready: Vec<usize> = (0..self.nodes.len()).filter(|block| waiting[*block] == 0).collect();
+
+        while let Some(block) = ready.pop() {
+            let node = self.nodes[block].lock();
+            let mut rate = node.object().input_sample_rate();
+
+            let mut inputs: Vec<&(Port, Port)> = self.connections.iter().filter(|(_, to)| to.block.0 == block).collect();
+            inputs.sort_by_key(|(_, to)| to.port);
+
+            for (from, to) in inputs {
+                let Some(found) = rates[from.block.0] else {
+                    continue;
+                };
+
+                match rate {
+                    Some(expected) if (found - expected).abs() > 1e-9 * expected.abs() => {
+                        return Err(FlowgraphError::SampleRateMismatch { block: self.names[block], port: *to, expected, found });
+                    }
+                    Some(_) => {}
+                    None => rate = Some(found),
+                }
+            }
+
+            rates[block] = node.object().output_sample_rate(rate);
+
+            for (_, to) in self.connections.iter().filter(|(from, _)| from.block.0 == block) {
+                waiting[to.block.0] -= 1;
+                if waiting[to.block.0] == 0 {
+                    ready.push(to.block.0);
+                }
+            }
+        }
+
+        Ok(rates)
+    }
+
+    /// Depth first search for back edges
+    fn check_cycles(&self) -> Result<(), FlowgraphError> {
+        // 0 = unvisited, 1 = on the current path, 2 = done
+        let mut state = vec![0u8; self.nodes.len()];
+
+        for start in 0..self.nodes.len() {
+            if state[start] != 0 {
+                continue;
+            }
+
+            let mut stack = vec![(start, 0)];
+            state[start] = 1;
+
+            while let Some((block, next)) = stack.pop() {
+                let downstream = self.connections.iter().filter(|(from, _)| from.block.0 == block).nth(next);
+
+                match downstream {
+                    Some((_, to)) => {
+                        stack.push((block, next + 1));
+
+                        match state[to.block.0] {
+                            0 => {
+                                state[to.block.0] = 1;
+                                stack.push((to.block.0, 0));
+                            }
+                            1 => return Err(FlowgraphError::Cycle { block: self.names[to.block.0], id: to.block }),
+                            _ => {}
+                        }
+                    }
+                    None => state[block] = 2,
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Build the graph and run its sources until every one of them is done. Blocks downstream of
+    /// a source run as soon as it produces a block. Sources that never finish make this run
+    /// forever.
+    pub fn run(&mut self) -> Result<(), FlowgraphError> {
+        self.build()?;
+
+        let sources: Vec<&NodeRef> = self.nodes.iter().filter(|node| node.lock().num_inputs() == 0).collect();
+
+        loop {
+            let mut running = false;
+
+            for source in sources.iter() {
+                let mut source = source.lock();
+
+                if !source.is_done() {
+                    source.process();
+                    running = true;
+                }
+            }
+
+            if !running {
+                return Ok(());
+            }
+        }
+    }
+}
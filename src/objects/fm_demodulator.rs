@@ -0,0 +1,97 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+
+use num::Complex;
+
+use crate::objects::fm_modulator::Emphasis;
+use crate::objects::object::{DSPObject, Samples, Type};
+use crate::objects::rational_resampler::RationalResampler;
+
+/// FM demodulator, undoing an [`crate::objects::fm_modulator::FmModulator`] with the same
+/// settings. A quadrature discriminator measures the instantaneous frequency at the sample rate,
+/// which is scaled so the deviation gives an audio level of 1, brought down to the audio rate
+/// and optionally de-emphasized. A carrier away from 0 Hz shows up as a DC offset.
+#[derive(Clone)]
+pub struct FmDemodulator {
+    pub deviation: f64,
+    pub tau: Option<f64>,
+    pub audio_rate: f64,
+    pub sample_rate: f64,
+
+    previous: Complex<f64>,
+    resampler: RationalResampler,
+    emphasis: Option<Emphasis>,
+}
+
+impl FmDemodulator {
+    /// Create a new FM demodulator
+    /// - deviation: f64 - The frequency swing for full scale audio (in Hz)
+    /// - tau: Option<f64> - The de-emphasis time constant (in seconds), or None for none
+    /// - audio_rate: f64 - The sample rate of the audio output (in Hz), a whole number
+    /// - sample_rate: f64 - The sample rate of the input (in Hz), a whole number
+    pub fn new(deviation: f64, tau: Option<f64>, audio_rate: f64, sample_rate: f64) -> FmDemodulator {
+        FmDemodulator {
+            deviation,
+            tau,
+            audio_rate,
+            sample_rate,
+            previous: Complex::new(0.0, 0.0),
+            resampler: RationalResampler::between(Type::F64, sample_rate, audio_rate),
+            emphasis: tau.map(|tau| Emphasis::new(tau, audio_rate)),
+        }
+    }
+
+    /// Create a new narrowband FM demodulator for voice, with 5 kHz deviation and 750 µs
+    /// de-emphasis
+    /// - audio_rate: f64 - The sample rate of the audio output (in Hz), a whole number
+    /// - sample_rate: f64 - The sample rate of the input (in Hz), a whole number
+    pub fn nbfm(audio_rate: f64, sample_rate: f64) -> FmDemodulator {
+        FmDemodulator::new(5e3, Some(750e-6), audio_rate, sample_rate)
+    }
+
+    /// Create a new wideband FM demodulator for broadcast, with 75 kHz deviation
+    /// - tau: f64 - The de-emphasis time constant (in seconds), 75 µs in the Americas and 50 µs
+    ///   elsewhere
+    /// - audio_rate: f64 - The sample rate of the audio output (in Hz), a whole number
+    /// - sample_rate: f64 - The sample rate of the input (in Hz), a whole number
+    pub fn wbfm(tau: f64, audio_rate: f64, sample_rate: f64) -> FmDemodulator {
+        FmDemodulator::new(75e3, Some(tau), audio_rate, sample_rate)
+    }
+}
+
+impl DSPObject for FmDemodulator {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::F64]
+    }
+
+    fn input_sample_rate(&self) -> Option<f64> {
+        Some(self.sample_rate)
+    }
+
+    fn output_sample_rate(&self, _input: Option<f64>) -> Option<f64> {
+        Some(self.audio_rate)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let scale = self.sample_rate / (2.0 * PI * self.deviation);
+        let frequencies = inputs[0].as_complex().iter().map(|x| {
+            let frequency = (x * self.previous.conj()).arg() * scale;
+            self.previous = *x;
+            frequency
+        }).collect();
+
+        let mut audio = [Samples::new(Type::F64)];
+        self.resampler.work(&[Samples::F64(frequencies)], &mut audio);
+
+        let output = outputs[0].as_f64_mut();
+        match &mut self.emphasis {
+            Some(emphasis) => output.extend(audio[0].as_f64().iter().map(|a| emphasis.deemphasis(*a))),
+            None => output.extend_from_slice(audio[0].as_f64()),
+        }
+    }
+}
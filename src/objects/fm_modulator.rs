@@ -0,0 +1,131 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::math::nco::Nco;
+use crate::objects::object::{DSPObject, Samples, Type};
+use crate::objects::rational_resampler::RationalResampler;
+
+/// First order emphasis network with time constant τ. De-emphasis is a one pole low-pass, and
+/// pre-emphasis is its exact inverse, so the two cancel out.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Emphasis {
+    pole: f64,
+    previous: f64,
+}
+
+impl Emphasis {
+    /// - tau: f64 - The time constant (in seconds), e.g. 75 µs
+    /// - sample_rate: f64 - The sample rate (in Hz)
+    pub(crate) fn new(tau: f64, sample_rate: f64) -> Emphasis {
+        Emphasis { pole: libm::exp(-1.0 / (tau * sample_rate)), previous: 0.0 }
+    }
+
+    /// Boost high frequencies, with unity gain at DC
+    pub(crate) fn preemphasis(&mut self, x: f64) -> f64 {
+        let y = (x - self.pole * self.previous) / (1.0 - self.pole);
+        self.previous = x;
+
+        y
+    }
+
+    /// Cut high frequencies, with unity gain at DC
+    pub(crate) fn deemphasis(&mut self, x: f64) -> f64 {
+        self.previous = (1.0 - self.pole) * x + self.pole * self.previous;
+
+        self.previous
+    }
+}
+
+/// Frequency modulation on the complex bus. Audio at the audio rate is optionally pre-emphasized,
+/// brought up to the sample rate and sets the frequency of a carrier at 0 Hz, an audio level of
+/// ±1 giving the full deviation. The output has unit amplitude.
+///
+/// Pre-emphasis boosts the treble by up to 1/(2π f τ) for audio above f = 1/(2π τ), so leave
+/// headroom for it when choosing the audio level.
+#[derive(Clone)]
+pub struct FmModulator {
+    pub deviation: f64,
+    pub tau: Option<f64>,
+    pub audio_rate: f64,
+    pub sample_rate: f64,
+
+    emphasis: Option<Emphasis>,
+    resampler: RationalResampler,
+    nco: Nco,
+}
+
+impl FmModulator {
+    /// Create a new FM modulator
+    /// - deviation: f64 - The frequency swing for full scale audio (in Hz)
+    /// - tau: Option<f64> - The pre-emphasis time constant (in seconds), or None for none
+    /// - audio_rate: f64 - The sample rate of the audio input (in Hz), a whole number
+    /// - sample_rate: f64 - The sample rate of the output (in Hz), a whole number
+    pub fn new(deviation: f64, tau: Option<f64>, audio_rate: f64, sample_rate: f64) -> FmModulator {
+        FmModulator {
+            deviation,
+            tau,
+            audio_rate,
+            sample_rate,
+            emphasis: tau.map(|tau| Emphasis::new(tau, audio_rate)),
+            resampler: RationalResampler::between(Type::F64, audio_rate, sample_rate),
+            nco: Nco::new(0.0, sample_rate),
+        }
+    }
+
+    /// Create a new narrowband FM modulator for voice, with 5 kHz deviation and 750 µs
+    /// pre-emphasis
+    /// - audio_rate: f64 - The sample rate of the audio input (in Hz), a whole number
+    /// - sample_rate: f64 - The sample rate of the output (in Hz), a whole number
+    pub fn nbfm(audio_rate: f64, sample_rate: f64) -> FmModulator {
+        FmModulator::new(5e3, Some(750e-6), audio_rate, sample_rate)
+    }
+
+    /// Create a new wideband FM modulator for broadcast, with 75 kHz deviation
+    /// - tau: f64 - The pre-emphasis time constant (in seconds), 75 µs in the Americas and 50 µs
+    ///   elsewhere
+    /// - audio_rate: f64 - The sample rate of the audio input (in Hz), a whole number
+    /// - sample_rate: f64 - The sample rate of the output (in Hz), a whole number
+    pub fn wbfm(tau: f64, audio_rate: f64, sample_rate: f64) -> FmModulator {
+        FmModulator::new(75e3, Some(tau), audio_rate, sample_rate)
+    }
+
+    /// Modulation index for a tone at `frequency` (in Hz) and full scale, the deviation over the
+    /// tone frequency
+    pub fn modulation_index(&self, frequency: f64) -> f64 {
+        self.deviation / frequency
+    }
+}
+
+impl DSPObject for FmModulator {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::F64]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn input_sample_rate(&self) -> Option<f64> {
+        Some(self.audio_rate)
+    }
+
+    fn output_sample_rate(&self, _input: Option<f64>) -> Option<f64> {
+        Some(self.sample_rate)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let audio = match &mut self.emphasis {
+            Some(emphasis) => Samples::F64(inputs[0].as_f64().iter().map(|a| emphasis.preemphasis(*a)).collect()),
+            None => inputs[0].clone(),
+        };
+
+        let mut upsampled = [Samples::new(Type::F64)];
+        self.resampler.work(&[audio], &mut upsampled);
+
+        let output = outputs[0].as_complex_mut();
+        for a in upsampled[0].as_f64() {
+            self.nco.set_frequency(self.deviation * a);
+            output.push(self.nco.next_sample());
+        }
+    }
+}
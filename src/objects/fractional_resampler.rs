@@ -0,0 +1,212 @@
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+use core::ops::{Add, Mul};
+
+use num::{Complex, Zero};
+use spin::Mutex;
+
+use crate::math::window::bessel_i0;
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Shape of the Kaiser window on the polyphase bank, giving roughly 60 dB sidelobes
+const BANK_BETA: f64 = 6.0;
+
+/// How a [`FractionalResampler`] works out the signal between input samples
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Interpolation {
+    /// Cubic Lagrange polynomial through the two samples either side, evaluated with a Farrow
+    /// structure. Cheap, and accurate for signals well below half the sample rate.
+    Farrow,
+    /// Windowed sinc filters for `phases` evenly spaced fractional delays with `taps` taps each,
+    /// interpolating linearly between neighbouring phases. `taps` must be even.
+    Polyphase { phases: usize, taps: usize },
+}
+
+/// Changes the sample rate by an arbitrary, possibly irrational, ratio that can be changed while
+/// the resampler runs, e.g. from a timing loop.
+///
+/// Nothing is filtered beyond what the interpolator does, so large rate reductions should be
+/// done by a [`crate::objects::rational_resampler::RationalResampler`] first to avoid aliasing.
+#[derive(Clone)]
+pub struct FractionalResampler {
+    pub sample_type: Type,
+    pub interpolation: Interpolation,
+
+    ratio: Arc<Mutex<f64>>,
+    // Weights for phases 0 to `phases` inclusive, for the polyphase interpolator
+    bank: Vec<Vec<f64>>,
+    history: Samples,
+    // Position of the next output in `history`, in input samples
+    position: f64,
+}
+
+impl FractionalResampler {
+    /// Create a new fractional resampler
+    /// - sample_type: Type - The type of the input and output
+    /// - ratio: f64 - Output sample rate divided by input sample rate
+    /// - interpolation: Interpolation - How to interpolate between input samples
+    pub fn new(sample_type: Type, ratio: f64, interpolation: Interpolation) -> FractionalResampler {
+        assert!(ratio > 0.0 && ratio.is_finite(), "the ratio must be a positive number");
+
+        let bank = match interpolation {
+            Interpolation::Farrow => Vec::new(),
+            Interpolation::Polyphase { phases, taps } => {
+                assert!(phases > 0, "the polyphase bank needs at least one phase");
+                assert!(taps >= 2 && taps.is_multiple_of(2), "the polyphase bank needs an even number of taps");
+                (0..=phases).map(|phase| sinc_weights(taps, phase as f64 / phases as f64)).collect()
+            }
+        };
+
+        let mut resampler = FractionalResampler {
+            sample_type,
+            interpolation,
+            ratio: Arc::new(Mutex::new(ratio)),
+            bank,
+            history: Samples::new(sample_type),
+            position: 0.0,
+        };
+        resampler.reset();
+
+        resampler
+    }
+
+    /// Output sample rate divided by input sample rate
+    pub fn ratio(&self) -> f64 {
+        *self.ratio.lock()
+    }
+
+    /// Change the ratio, taking effect from the next block. Every clone sees the new ratio.
+    pub fn set_ratio(&self, ratio: f64) {
+        assert!(ratio > 0.0 && ratio.is_finite(), "the ratio must be a positive number");
+        *self.ratio.lock() = ratio;
+    }
+
+    /// Forget every past sample, as if the resampler had only ever seen zeros
+    pub fn reset(&mut self) {
+        // Zeros before the first sample so the first output lines up with it
+        let n = self.half() - 1;
+        self.position = n as f64;
+
+        match &mut self.history {
+            Samples::F64(history) => *history = vec![0.0; n],
+            Samples::Complex(history) => *history = vec![Complex::new(0.0, 0.0); n],
+            Samples::NONE => {}
+        }
+    }
+
+    /// Number of input samples used on each side of an output
+    fn half(&self) -> usize {
+        match self.interpolation {
+            Interpolation::Farrow => 2,
+            Interpolation::Polyphase { taps, .. } => taps / 2,
+        }
+    }
+}
+
+/// Kaiser windowed sinc weights for an output `mu` samples after the middle of `taps` inputs,
+/// scaled to unity gain at DC
+fn sinc_weights(taps: usize, mu: f64) -> Vec<f64> {
+    let half = (taps / 2) as f64;
+
+    let weights: Vec<f64> = (0..taps).map(|j| {
+        let t = mu + half - 1.0 - j as f64;
+        let sinc = if t == 0.0 { 1.0 } else { libm::sin(PI * t) / (PI * t) };
+        let x = t / half;
+        let window = if x.abs() >= 1.0 { 0.0 } else { bessel_i0(BANK_BETA * libm::sqrt(1.0 - x * x)) / bessel_i0(BANK_BETA) };
+        sinc * window
+    }).collect();
+
+    let gain: f64 = weights.iter().sum();
+    weights.iter().map(|w| w / gain).collect()
+}
+
+/// Cubic Lagrange interpolation between `x[1]` and `x[2]`, `mu` of the way along. The four
+/// sub-filters give the coefficients of a polynomial in `mu`, which is evaluated with Horner's
+/// rule.
+pub(crate) fn farrow<T>(x: &[T], mu: f64) -> T
+where
+    T: Copy + Add<Output = T> + Mul<f64, Output = T>,
+{
+    let c0 = x[1];
+    let c1 = x[0] * (-1.0 / 3.0) + x[1] * -0.5 + x[2] + x[3] * (-1.0 / 6.0);
+    let c2 = x[0] * 0.5 + x[1] * -1.0 + x[2] * 0.5;
+    let c3 = x[0] * (-1.0 / 6.0) + x[1] * 0.5 + x[2] * -0.5 + x[3] * (1.0 / 6.0);
+
+    ((c3 * mu + c2) * mu + c1) * mu + c0
+}
+
+/// Filter `x` with the weights for `mu`, blending the two nearest phases of the bank
+fn polyphase<T>(bank: &[Vec<f64>], x: &[T], mu: f64) -> T
+where
+    T: Copy + Zero + Add<Output = T> + Mul<f64, Output = T>,
+{
+    let phases = bank.len() - 1;
+    let position = mu * phases as f64;
+    let phase = (position as usize).min(phases - 1);
+    let fraction = position - phase as f64;
+
+    x.iter().zip(bank[phase].iter().zip(bank[phase + 1].iter())).fold(T::zero(), |acc, (x, (a, b))| {
+        acc + *x * (a + (b - a) * fraction)
+    })
+}
+
+/// Produce every output whose neighbourhood is covered by the samples seen so far
+fn resample<T>(resampler: &FractionalResampler, step: f64, position: &mut f64, history: &mut Vec<T>, input: &[T], output: &mut Vec<T>)
+where
+    T: Copy + Zero + Add<Output = T> + Mul<f64, Output = T>,
+{
+    let half = resampler.half();
+    history.extend_from_slice(input);
+
+    loop {
+        let n = *position as usize;
+        if n + half >= history.len() {
+            break;
+        }
+
+        let window = &history[n + 1 - half..=n + half];
+        let mu = *position - n as f64;
+
+        output.push(match resampler.interpolation {
+            Interpolation::Farrow => farrow(window, mu),
+            Interpolation::Polyphase { .. } => polyphase(&resampler.bank, window, mu),
+        });
+        *position += step;
+    }
+
+    // Keep only the samples the next output still needs
+    let used = (*position as usize + 1).saturating_sub(half).min(history.len());
+    history.drain(..used);
+    *position -= used as f64;
+}
+
+impl DSPObject for FractionalResampler {
+    fn input_types(&self) -> Vec<Type> {
+        vec![self.sample_type]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![self.sample_type]
+    }
+
+    fn output_sample_rate(&self, input: Option<f64>) -> Option<f64> {
+        input.map(|rate| rate * self.ratio())
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let step = 1.0 / self.ratio();
+        let mut position = self.position;
+        let mut history = core::mem::take(&mut self.history);
+
+        match (&mut history, &mut outputs[0]) {
+            (Samples::F64(history), Samples::F64(output)) => resample(self, step, &mut position, history, inputs[0].as_f64(), output),
+            (Samples::Complex(history), Samples::Complex(output)) => resample(self, step, &mut position, history, inputs[0].as_complex(), output),
+            _ => {}
+        }
+
+        self.position = position;
+        self.history = history;
+    }
+}
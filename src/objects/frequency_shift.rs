@@ -0,0 +1,61 @@
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use spin::Mutex;
+
+use crate::math::nco::Nco;
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Moves a complex stream up or down in frequency by mixing it with a numerically controlled
+/// oscillator, e.g. to bring a signal somewhere in a wide capture down to 0 Hz.
+#[derive(Clone)]
+pub struct FrequencyShift {
+    shift: Arc<Mutex<f64>>,
+    nco: Nco,
+}
+
+impl FrequencyShift {
+    /// Create a new frequency shift
+    /// - shift: f64 - The frequency to add to every component of the input (in Hz), negative to
+    ///   shift down
+    /// - sample_rate: f64 - The sample rate of the input (in Hz)
+    pub fn new(shift: f64, sample_rate: f64) -> FrequencyShift {
+        FrequencyShift {
+            shift: Arc::new(Mutex::new(shift)),
+            nco: Nco::new(shift, sample_rate),
+        }
+    }
+
+    /// The frequency added to every component of the input (in Hz)
+    pub fn shift(&self) -> f64 {
+        *self.shift.lock()
+    }
+
+    /// Retune without a jump in phase, taking effect from the next block. Every clone sees the
+    /// new shift.
+    pub fn set_shift(&self, shift: f64) {
+        *self.shift.lock() = shift;
+    }
+}
+
+impl DSPObject for FrequencyShift {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn input_sample_rate(&self) -> Option<f64> {
+        Some(self.nco.sample_rate)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        self.nco.set_frequency(self.shift());
+
+        let output = outputs[0].as_complex_mut();
+        output.extend(inputs[0].as_complex().iter().map(|x| x * self.nco.next_sample()));
+    }
+}
@@ -0,0 +1,141 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::{FRAC_1_SQRT_2, PI};
+
+use num::Complex;
+
+use crate::math::filter::lowpass;
+use crate::objects::fir_filter::FirFilter;
+use crate::objects::fsk_modulator::{level, samples_per_symbol, soft_bits};
+use crate::objects::object::{DSPObject, Samples, Type};
+use crate::objects::symbol_sync::{SymbolSync, TimingDetector};
+
+/// Turns FSK, GFSK, MSK or GMSK from a [`crate::objects::fsk_modulator::FskModulator`] back
+/// into bits. A channel filter as wide as Carson's rule asks for takes out the noise beyond the
+/// signal, a quadrature discriminator measures the instantaneous frequency, which is averaged
+/// over a symbol and sampled by Gardner timing recovery, and the nearest tone is Gray decoded.
+///
+/// Output 0 carries hard bits as 0.0 or 1.0 and output 1 a soft bit for each of them, positive
+/// for a one and growing with confidence, in units of the deviation for binary FSK. The carrier
+/// should be within a small fraction of the deviation of 0 Hz.
+#[derive(Clone)]
+pub struct FskDemodulator {
+    pub order: usize,
+    pub deviation: f64,
+    pub baud: f64,
+    pub sample_rate: f64,
+
+    samples_per_symbol: usize,
+    channel: Option<FirFilter>,
+    previous: Complex<f64>,
+    matched: FirFilter,
+    timing: SymbolSync,
+}
+
+impl FskDemodulator {
+    /// Create a new FSK demodulator. The timing loop starts with a bandwidth of 1% of the baud
+    /// rate.
+    /// - order: usize - The number of tones, a power of two
+    /// - deviation: f64 - Half the spacing between neighbouring tones (in Hz)
+    /// - baud: f64 - The symbol rate (in Hz), which the sample rate must be a multiple of
+    /// - sample_rate: f64 - The sample rate of the input (in Hz)
+    pub fn new(order: usize, deviation: f64, baud: f64, sample_rate: f64) -> FskDemodulator {
+        let samples_per_symbol = samples_per_symbol(order, baud, sample_rate);
+
+        // Carson's rule, with the outermost tones at (order - 1) times the deviation. There is
+        // nothing to take out if that already fills most of the band.
+        let cutoff = (order - 1) as f64 * deviation + baud;
+        let width = (sample_rate / 2.0 - cutoff).min(cutoff / 2.0);
+        let channel = if width > 0.05 * sample_rate {
+            Some(FirFilter::new(Type::Complex, lowpass(sample_rate, cutoff + width / 2.0, width, 40.0).expect("invalid channel filter")))
+        } else {
+            None
+        };
+
+        FskDemodulator {
+            order,
+            deviation,
+            baud,
+            sample_rate,
+            samples_per_symbol,
+            channel,
+            previous: Complex::new(0.0, 0.0),
+            matched: FirFilter::new(Type::F64, vec![1.0 / samples_per_symbol as f64; samples_per_symbol]),
+            timing: SymbolSync::new(TimingDetector::Gardner, samples_per_symbol as f64, 0.01 * baud, FRAC_1_SQRT_2, sample_rate),
+        }
+    }
+
+    /// Create a new MSK or GMSK demodulator
+    /// - baud: f64 - The symbol rate (in Hz), which the sample rate must be a multiple of
+    /// - sample_rate: f64 - The sample rate of the input (in Hz)
+    pub fn msk(baud: f64, sample_rate: f64) -> FskDemodulator {
+        FskDemodulator::new(2, baud / 4.0, baud, sample_rate)
+    }
+
+    /// Change the bandwidth of the timing loop (in Hz)
+    pub fn set_timing_bandwidth(&mut self, bandwidth: f64) {
+        self.timing.set_bandwidth(bandwidth);
+    }
+}
+
+impl DSPObject for FskDemodulator {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::F64, Type::F64]
+    }
+
+    fn input_sample_rate(&self) -> Option<f64> {
+        Some(self.sample_rate)
+    }
+
+    fn output_sample_rate(&self, input: Option<f64>) -> Option<f64> {
+        let bits = self.order.trailing_zeros() as f64;
+        input.map(|rate| rate / self.samples_per_symbol as f64 * bits)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let [hard, soft_output] = outputs else {
+            return;
+        };
+
+        let mut filtered = [inputs[0].clone()];
+        if let Some(channel) = &mut self.channel {
+            filtered = [Samples::new(Type::Complex)];
+            channel.work(inputs, &mut filtered);
+        }
+
+        // Frequency of every sample in units of the deviation
+        let scale = self.sample_rate / (2.0 * PI * self.deviation);
+        let frequencies: Vec<f64> = filtered[0].as_complex().iter().map(|x| {
+            let frequency = (x * self.previous.conj()).arg() * scale;
+            self.previous = *x;
+            frequency
+        }).collect();
+
+        let mut averaged = [Samples::new(Type::F64)];
+        self.matched.work(&[Samples::F64(frequencies)], &mut averaged);
+
+        // The timing loop only looks at the real part
+        let [averaged] = averaged;
+        let averaged = averaged.as_f64().iter().map(|x| Complex::new(*x, 0.0)).collect();
+        let mut symbols = [Samples::new(Type::Complex), Samples::new(Type::F64)];
+        self.timing.work(&[Samples::Complex(averaged)], &mut symbols);
+
+        let mut soft = Vec::new();
+        let mut scores = vec![0.0; self.order];
+        for symbol in symbols[0].as_complex() {
+            // Scaled so binary FSK gives the frequency itself as the soft bit
+            for (index, score) in scores.iter_mut().enumerate() {
+                let distance = symbol.re - level(index, self.order);
+                *score = -distance * distance / 4.0;
+            }
+            soft_bits(&scores, &mut soft);
+        }
+
+        hard.as_f64_mut().extend(soft.iter().map(|bit| (*bit > 0.0) as u8 as f64));
+        soft_output.as_f64_mut().extend_from_slice(&soft);
+    }
+}
@@ -0,0 +1,165 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::math::constellation::{gray, gray_inverse};
+use crate::math::filter::gaussian;
+use crate::math::nco::Nco;
+use crate::objects::fir_filter::FirFilter;
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Symbols the Gaussian pulse of GFSK and GMSK spans
+const PULSE_SPAN: usize = 4;
+
+/// Frequency of tone `index` out of `order`, in units of the deviation. The tones sit at odd
+/// multiples of the deviation on either side of the carrier.
+pub(crate) fn level(index: usize, order: usize) -> f64 {
+    (2 * index) as f64 - (order - 1) as f64
+}
+
+/// Turn a score for every tone, higher meaning more likely, into a soft bit for every bit the
+/// tone carries, most significant first. Each soft bit is the best score of the tones where the
+/// bit is one minus the best score where it is zero, so it is positive for a one.
+pub(crate) fn soft_bits(scores: &[f64], output: &mut Vec<f64>) {
+    let bits = scores.len().trailing_zeros();
+
+    for bit in (0..bits).rev() {
+        let (mut one, mut zero) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for (index, score) in scores.iter().enumerate() {
+            if (gray(index) >> bit) & 1 == 1 {
+                one = one.max(*score);
+            } else {
+                zero = zero.max(*score);
+            }
+        }
+
+        output.push(one - zero);
+    }
+}
+
+/// Check the settings shared by the FSK modulator and demodulators and work out the number of
+/// samples per symbol
+pub(crate) fn samples_per_symbol(order: usize, baud: f64, sample_rate: f64) -> usize {
+    assert!(order >= 2 && order.is_power_of_two(), "the number of tones must be a power of two");
+
+    let samples_per_symbol = libm::round(sample_rate / baud);
+    assert!(samples_per_symbol >= 2.0 && (sample_rate / baud - samples_per_symbol).abs() < 1e-6, "the sample rate must be a whole multiple of the baud rate");
+
+    samples_per_symbol as usize
+}
+
+/// Continuous phase frequency shift keying. Every symbol carries log2(order) bits, Gray mapped
+/// onto one of `order` tones spaced twice the deviation apart around 0 Hz. With a bandwidth
+/// time product the frequency steps are smoothed by a Gaussian pulse, which makes GFSK, and
+/// binary FSK with a deviation of a quarter of the baud rate is MSK.
+///
+/// The input carries one bit per sample, as 0.0 or 1.0, and the output has unit amplitude.
+#[derive(Clone)]
+pub struct FskModulator {
+    pub order: usize,
+    pub deviation: f64,
+    pub baud: f64,
+    pub bt: Option<f64>,
+    pub sample_rate: f64,
+
+    samples_per_symbol: usize,
+    shaping: Option<FirFilter>,
+    nco: Nco,
+    // Bits waiting for enough company to make a symbol
+    pending: Vec<u8>,
+}
+
+impl FskModulator {
+    /// Create a new FSK modulator
+    /// - order: usize - The number of tones, a power of two
+    /// - deviation: f64 - Half the spacing between neighbouring tones (in Hz)
+    /// - baud: f64 - The symbol rate (in Hz), which the sample rate must be a multiple of
+    /// - bt: Option<f64> - The bandwidth time product of the Gaussian pulse, or None for
+    ///   unfiltered frequency steps
+    /// - sample_rate: f64 - The sample rate of the output (in Hz)
+    pub fn new(order: usize, deviation: f64, baud: f64, bt: Option<f64>, sample_rate: f64) -> FskModulator {
+        let samples_per_symbol = samples_per_symbol(order, baud, sample_rate);
+        let shaping = bt.map(|bt| FirFilter::new(Type::F64, gaussian(samples_per_symbol, bt, PULSE_SPAN).expect("invalid pulse shape")));
+
+        FskModulator {
+            order,
+            deviation,
+            baud,
+            bt,
+            sample_rate,
+            samples_per_symbol,
+            shaping,
+            nco: Nco::new(0.0, sample_rate),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Create a new MSK modulator, binary FSK with a modulation index of 0.5
+    /// - baud: f64 - The symbol rate (in Hz), which the sample rate must be a multiple of
+    /// - sample_rate: f64 - The sample rate of the output (in Hz)
+    pub fn msk(baud: f64, sample_rate: f64) -> FskModulator {
+        FskModulator::new(2, baud / 4.0, baud, None, sample_rate)
+    }
+
+    /// Create a new GMSK modulator, MSK with Gaussian smoothed frequency steps
+    /// - baud: f64 - The symbol rate (in Hz), which the sample rate must be a multiple of
+    /// - bt: f64 - The bandwidth time product of the Gaussian pulse
+    /// - sample_rate: f64 - The sample rate of the output (in Hz)
+    pub fn gmsk(baud: f64, bt: f64, sample_rate: f64) -> FskModulator {
+        FskModulator::new(2, baud / 4.0, baud, Some(bt), sample_rate)
+    }
+
+    /// The number of output samples per symbol
+    pub fn samples_per_symbol(&self) -> usize {
+        self.samples_per_symbol
+    }
+
+    /// The spacing between neighbouring tones divided by the baud rate
+    pub fn modulation_index(&self) -> f64 {
+        2.0 * self.deviation / self.baud
+    }
+}
+
+impl DSPObject for FskModulator {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::F64]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn input_sample_rate(&self) -> Option<f64> {
+        Some(self.baud * self.order.trailing_zeros() as f64)
+    }
+
+    fn output_sample_rate(&self, _input: Option<f64>) -> Option<f64> {
+        Some(self.sample_rate)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        self.pending.extend(inputs[0].as_f64().iter().map(|bit| (*bit != 0.0) as u8));
+
+        let bits = self.order.trailing_zeros() as usize;
+        let mut frequencies = Vec::with_capacity(self.pending.len() / bits * self.samples_per_symbol);
+        for chunk in self.pending.chunks_exact(bits) {
+            let value = chunk.iter().fold(0, |value, bit| (value << 1) | *bit as usize);
+            let frequency = level(gray_inverse(value), self.order) * self.deviation;
+            frequencies.extend(core::iter::repeat_n(frequency, self.samples_per_symbol));
+        }
+        self.pending.drain(..frequencies.len() / self.samples_per_symbol * bits);
+
+        let mut frequencies = Samples::F64(frequencies);
+        if let Some(shaping) = &mut self.shaping {
+            let mut shaped = [Samples::new(Type::F64)];
+            shaping.work(&[frequencies], &mut shaped);
+            let [shaped] = shaped;
+            frequencies = shaped;
+        }
+
+        let output = outputs[0].as_complex_mut();
+        for frequency in frequencies.as_f64() {
+            self.nco.set_frequency(*frequency);
+            output.push(self.nco.next_sample());
+        }
+    }
+}
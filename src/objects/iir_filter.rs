@@ -0,0 +1,88 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::{Add, Mul, Sub};
+
+use num::{Complex, Zero};
+
+use crate::math::iir::Biquad;
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Cascade of second order IIR sections in transposed direct form II, for F64 or Complex streams.
+/// Each section keeps two state values between blocks.
+#[derive(Clone)]
+pub struct IirFilter {
+    pub sample_type: Type,
+    pub sections: Vec<Biquad>,
+
+    // Two values per section
+    state: Samples,
+}
+
+impl IirFilter {
+    /// Create a new IIR filter
+    /// - sample_type: Type - The type of the input and output
+    /// - sections: Vec<Biquad> - The sections to run the signal through in order, e.g. from
+    ///   [`crate::math::iir::butterworth`]
+    pub fn new(sample_type: Type, sections: Vec<Biquad>) -> IirFilter {
+        let mut filter = IirFilter {
+            sample_type,
+            sections,
+            state: Samples::new(sample_type),
+        };
+        filter.reset();
+
+        filter
+    }
+
+    /// Clear the state of every section, as if the filter had only ever seen zeros
+    pub fn reset(&mut self) {
+        let n = 2 * self.sections.len();
+
+        match &mut self.state {
+            Samples::F64(state) => *state = vec![0.0; n],
+            Samples::Complex(state) => *state = vec![Complex::new(0.0, 0.0); n],
+            Samples::NONE => {}
+        }
+    }
+}
+
+fn filter<T>(sections: &[Biquad], state: &mut [T], input: &[T], output: &mut Vec<T>)
+where
+    T: Copy + Zero + Add<Output = T> + Sub<Output = T> + Mul<f64, Output = T>,
+{
+    output.extend_from_slice(input);
+
+    for (section, state) in sections.iter().zip(state.chunks_exact_mut(2)) {
+        let (mut s1, mut s2) = (state[0], state[1]);
+
+        for sample in output.iter_mut() {
+            let x = *sample;
+            let y = x * section.b0 + s1;
+
+            s1 = x * section.b1 - y * section.a1 + s2;
+            s2 = x * section.b2 - y * section.a2;
+            *sample = y;
+        }
+
+        state[0] = s1;
+        state[1] = s2;
+    }
+}
+
+impl DSPObject for IirFilter {
+    fn input_types(&self) -> Vec<Type> {
+        vec![self.sample_type]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![self.sample_type]
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        match (&mut self.state, &mut outputs[0]) {
+            (Samples::F64(state), Samples::F64(output)) => filter(&self.sections, state, inputs[0].as_f64(), output),
+            (Samples::Complex(state), Samples::Complex(output)) => filter(&self.sections, state, inputs[0].as_complex(), output),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Merges several inputs into one stream by taking one sample from each input in turn
+#[derive(Clone)]
+pub struct Interleave {
+    pub sample_type: Type,
+    pub inputs: usize,
+}
+
+impl Interleave {
+    /// Create a new interleaver
+    /// - sample_type: Type - The type of every input and of the output
+    /// - inputs: usize - The number of input ports
+    pub fn new(sample_type: Type, inputs: usize) -> Interleave {
+        Interleave { sample_type, inputs }
+    }
+}
+
+impl DSPObject for Interleave {
+    fn input_types(&self) -> Vec<Type> {
+        vec![self.sample_type; self.inputs]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![self.sample_type]
+    }
+
+    fn output_sample_rate(&self, input: Option<f64>) -> Option<f64> {
+        input.map(|rate| rate * self.inputs as f64)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        for i in 0..inputs[0].len() {
+            for input in inputs {
+                match (&mut outputs[0], input) {
+                    (Samples::F64(output), Samples::F64(input)) => output.push(input[i]),
+                    (Samples::Complex(output), Samples::Complex(input)) => output.push(input[i]),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use num::Complex;
+
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Builds a complex stream from a magnitude (input 0) and a phase in radians (input 1)
+#[derive(Clone, Default)]
+pub struct MagPhaseToComplex;
+
+impl MagPhaseToComplex {
+    pub fn new() -> MagPhaseToComplex {
+        MagPhaseToComplex
+    }
+}
+
+impl DSPObject for MagPhaseToComplex {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::F64, Type::F64]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let magnitude = inputs[0].as_f64();
+        let phase = inputs[1].as_f64();
+
+        outputs[0].as_complex_mut().extend(magnitude.iter().zip(phase).map(|(r, theta)| Complex::new(r * libm::cos(*theta), r * libm::sin(*theta))));
+    }
+}
@@ -0,0 +1,62 @@
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use spin::Mutex;
+
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Multiplies every sample by a gain that can be changed while it runs.
+#[derive(Clone)]
+pub struct ManualGain {
+    pub sample_type: Type,
+
+    gain: Arc<Mutex<f64>>,
+}
+
+impl ManualGain {
+    /// Create a new manual gain block
+    /// - sample_type: Type - The type of the input and output
+    /// - gain: f64 - The linear gain to apply
+    pub fn new(sample_type: Type, gain: f64) -> ManualGain {
+        ManualGain {
+            sample_type,
+            gain: Arc::new(Mutex::new(gain)),
+        }
+    }
+
+    /// The linear gain
+    pub fn gain(&self) -> f64 {
+        *self.gain.lock()
+    }
+
+    /// Change the linear gain, taking effect from the next block. Every clone sees the new gain.
+    pub fn set_gain(&self, gain: f64) {
+        *self.gain.lock() = gain;
+    }
+
+    /// Change the gain to a power ratio given in dB
+    pub fn set_gain_db(&self, gain: f64) {
+        self.set_gain(libm::pow(10.0, gain / 20.0));
+    }
+}
+
+impl DSPObject for ManualGain {
+    fn input_types(&self) -> Vec<Type> {
+        vec![self.sample_type]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![self.sample_type]
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let gain = self.gain();
+
+        match (&inputs[0], &mut outputs[0]) {
+            (Samples::F64(input), Samples::F64(output)) => output.extend(input.iter().map(|x| x * gain)),
+            (Samples::Complex(input), Samples::Complex(output)) => output.extend(input.iter().map(|x| x * gain)),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::{FRAC_1_SQRT_2, PI};
+
+use num::Complex;
+
+use crate::math::control_loop::ControlLoop;
+use crate::objects::fsk_modulator::{level, samples_per_symbol, soft_bits};
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Non-coherent M-FSK demodulator. Every symbol is correlated against each tone of a
+/// [`crate::objects::fsk_modulator::FskModulator`] with the same settings, and the tone with the
+/// most energy wins, so neither the carrier phase nor the phase the symbol started at matter.
+/// An early-late gate on the winning energy keeps the correlation window lined up with the
+/// symbols. The tones should be at least the baud rate apart, i.e. the deviation at least half
+/// the baud rate.
+///
+/// Output 0 carries hard bits as 0.0 or 1.0 and output 1 a soft bit between -1 and 1 for each
+/// of them, positive for a one: the energy of the best tone where the bit is one minus that of
+/// the best tone where it is zero, over the energy in all of them.
+#[derive(Clone)]
+pub struct MfskDemodulator {
+    pub order: usize,
+    pub deviation: f64,
+    pub baud: f64,
+    pub sample_rate: f64,
+    /// The largest fraction the symbol period may be off from the nominal one by
+    pub max_deviation: f64,
+
+    samples_per_symbol: usize,
+    // Conjugate of each tone over one symbol
+    references: Vec<Vec<Complex<f64>>>,
+    control: ControlLoop,
+    history: Vec<Complex<f64>>,
+    // Position in `history` of the last sample of the next symbol
+    position: f64,
+    // Correction to the symbol period, in samples
+    period_offset: f64,
+}
+
+impl MfskDemodulator {
+    /// Create a new non-coherent M-FSK demodulator. The timing loop starts with a bandwidth of
+    /// 1% of the baud rate.
+    /// - order: usize - The number of tones, a power of two
+    /// - deviation: f64 - Half the spacing between neighbouring tones (in Hz)
+    /// - baud: f64 - The symbol rate (in Hz), which the sample rate must be a multiple of
+    /// - sample_rate: f64 - The sample rate of the input (in Hz)
+    pub fn new(order: usize, deviation: f64, baud: f64, sample_rate: f64) -> MfskDemodulator {
+        let samples_per_symbol = samples_per_symbol(order, baud, sample_rate);
+
+        let references = (0..order).map(|index| {
+            let step = -2.0 * PI * level(index, order) * deviation / sample_rate;
+            (0..samples_per_symbol).map(|n| Complex::from_polar(1.0, step * n as f64)).collect()
+        }).collect();
+
+        let mut demodulator = MfskDemodulator {
+            order,
+            deviation,
+            baud,
+            sample_rate,
+            max_deviation: 0.05,
+            samples_per_symbol,
+            references,
+            control: ControlLoop::new(2.0 * PI * 0.01, FRAC_1_SQRT_2, PI),
+            history: Vec::new(),
+            position: 0.0,
+            period_offset: 0.0,
+        };
+        demodulator.position = (samples_per_symbol - 1 + demodulator.gate()) as f64;
+
+        demodulator
+    }
+
+    /// Change the bandwidth of the timing loop (in Hz)
+    pub fn set_timing_bandwidth(&mut self, bandwidth: f64) {
+        self.control.set_bandwidth(2.0 * PI * bandwidth / self.baud);
+    }
+
+    /// The current estimate of the symbol period, in samples
+    pub fn period(&self) -> f64 {
+        self.samples_per_symbol as f64 + self.period_offset
+    }
+
+    /// Distance from the symbol instant to the early and late correlations, in samples
+    fn gate(&self) -> usize {
+        (self.samples_per_symbol / 4).max(1)
+    }
+
+    /// Energy in each tone over the symbol ending at `end`
+    fn energies(&self, end: usize) -> Vec<f64> {
+        let window = &self.history[end + 1 - self.samples_per_symbol..=end];
+
+        self.references.iter().map(|reference| {
+            window.iter().zip(reference.iter()).map(|(x, r)| x * r).sum::<Complex<f64>>().norm_sqr()
+        }).collect()
+    }
+}
+
+/// Largest value in `x`
+fn peak(x: &[f64]) -> f64 {
+    x.iter().cloned().fold(0.0, f64::max)
+}
+
+impl DSPObject for MfskDemodulator {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::F64, Type::F64]
+    }
+
+    fn input_sample_rate(&self) -> Option<f64> {
+        Some(self.sample_rate)
+    }
+
+    fn output_sample_rate(&self, input: Option<f64>) -> Option<f64> {
+        let bits = self.order.trailing_zeros() as f64;
+        input.map(|rate| rate / self.samples_per_symbol as f64 * bits)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let [hard, soft] = outputs else {
+            return;
+        };
+        let (hard, soft) = (hard.as_f64_mut(), soft.as_f64_mut());
+        self.history.extend_from_slice(inputs[0].as_complex());
+
+        let gate = self.gate();
+        let (alpha, beta) = self.control.gains();
+        let limit = self.max_deviation * self.samples_per_symbol as f64;
+        let scale = self.samples_per_symbol as f64 / (2.0 * PI);
+
+        loop {
+            let n = libm::round(self.position) as usize;
+            if n + gate >= self.history.len() {
+                break;
+            }
+
+            let energies = self.energies(n);
+            let total: f64 = energies.iter().sum::<f64>().max(f64::MIN_POSITIVE);
+            let scores: Vec<f64> = energies.iter().map(|energy| energy / total).collect();
+
+            let start = soft.len();
+            soft_bits(&scores, soft);
+            hard.extend(soft[start..].iter().map(|bit| (*bit > 0.0) as u8 as f64));
+
+            // More energy in the late window than the early one means the symbols are being cut
+            // off too early. Over the sum of both the error stays between -1 and 1, even in
+            // silence after a burst.
+            let (late, early) = (peak(&self.energies(n + gate)), peak(&self.energies(n - gate)));
+            let error = (late - early) / (late + early).max(f64::MIN_POSITIVE);
+            self.period_offset = (self.period_offset + beta * error * scale).clamp(-limit, limit);
+
+            // Always move forward, so the early window never reaches before the history
+            let step = self.samples_per_symbol as f64 + self.period_offset + (alpha * error * scale).clamp(-limit, limit);
+            self.position += step.max(1.0);
+        }
+
+        // Keep everything the early correlation of the next symbol needs
+        let needed = libm::round(self.position) as usize + 1 - self.samples_per_symbol - gate;
+        let used = needed.min(self.history.len());
+        self.history.drain(..used);
+        self.position -= used as f64;
+    }
+}
@@ -1,18 +1,66 @@
-#[cfg(feature = "std")]
+#[cfg(feature = "gui")]
 use std::boxed::Box;
 
 #[cfg(feature = "gui")]
 use std::thread::spawn;
-#[cfg(feature = "std")]
+#[cfg(feature = "gui")]
 use std::vec::Vec;
 
-use spin::{Barrier, RwLock};
-
 #[cfg(feature = "gui")]
 use crate::gui::{DSPChart, GUI, Message};
-use crate::objects::object::{DSPObject};
+#[cfg(feature = "gui")]
+use crate::objects::flowgraph::Flowgraph;
+#[cfg(all(feature = "gui", feature = "multithreading-std"))]
+use crate::objects::scheduler::Scheduler;
 
 pub mod object;
+pub mod flowgraph;
+pub mod ring_buffer;
+
+#[cfg(feature = "multithreading-std")]
+pub mod scheduler;
+
+pub mod vector_source;
+pub mod vector_sink;
+pub mod add;
+pub mod multiply;
+pub mod multiply_conjugate;
+pub mod interleave;
+pub mod deinterleave;
+pub mod complex_to_real_imag;
+pub mod real_imag_to_complex;
+pub mod complex_to_mag_phase;
+pub mod mag_phase_to_complex;
+pub mod fir_filter;
+pub mod iir_filter;
+pub mod rational_resampler;
+pub mod fractional_resampler;
+pub mod cic_decimator;
+pub mod cic_interpolator;
+pub mod nco_source;
+pub mod frequency_shift;
+pub mod pll;
+pub mod costas_loop;
+pub mod band_edge_fll;
+pub mod manual_gain;
+pub mod agc;
+pub mod fast_attack_agc;
+pub mod symbol_sync;
+pub mod psk_modulator;
+pub mod psk_demodulator;
+pub mod fsk_modulator;
+pub mod fsk_demodulator;
+pub mod mfsk_demodulator;
+pub mod constellation_mapper;
+pub mod soft_demapper;
+pub mod am_modulator;
+pub mod am_demodulator;
+pub mod fm_modulator;
+pub mod fm_demodulator;
+pub mod ssb_modulator;
+pub mod ssb_demodulator;
+pub mod ofdm_transmitter;
+pub mod ofdm_receiver;
 pub mod wave_gen;
 
 #[cfg(feature = "std")]
@@ -22,53 +70,14 @@ pub mod wave_gen_time;
 pub mod wave_gen_time_complex;
 pub mod wave_gen_complex;
 
-pub(crate) static F64_OUTPUT_BUFFERS: [RwLock<f64>; 64] = [
-    RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0),
-    RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0),
-    RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0),
-    RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0),
-    RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0),
-    RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0),
-    RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0),
-    RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0), RwLock::new(0.0),
-];
-pub(crate) static COMPLEX_OUTPUT_BUFFERS: [RwLock<num::Complex<f64>>; 64] = [
-    RwLock::new(num::Complex::new(0.0, 0.0)), RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),
-    RwLock::new(num::Complex::new(0.0, 0.0)), RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),
-    RwLock::new(num::Complex::new(0.0, 0.0)), RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),
-    RwLock::new(num::Complex::new(0.0, 0.0)), RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),
-    RwLock::new(num::Complex::new(0.0, 0.0)), RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),
-    RwLock::new(num::Complex::new(0.0, 0.0)), RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),
-    RwLock::new(num::Complex::new(0.0, 0.0)), RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),
-    RwLock::new(num::Complex::new(0.0, 0.0)), RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),RwLock::new(num::Complex::new(0.0, 0.0)),
-];
-
-#[cfg(feature = "multithreading-std")]
-pub(crate) static BARRIERS: [Barrier; 64] = [
-    Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5),
-    Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5),
-    Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5),
-    Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5),
-    Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5),
-    Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5),
-    Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5),
-    Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5), Barrier::new(5),
-];
-pub(crate) static F64_OUTPUT_BUFFER_INDEX: spin::Mutex<usize> = spin::Mutex::new(0);
-pub(crate) static COMPLEX_OUTPUT_BUFFER_INDEX: spin::Mutex<usize> = spin::Mutex::new(0);
-pub(crate) static BARRIERS_INDEX: spin::Mutex<usize> = spin::Mutex::new(0);
-
 #[cfg(feature = "gui")]
-pub struct GUIExecutor{
-    arr: Vec<*mut dyn DSPObject>,
-    first: Box<dyn DSPObject>
-}
+pub struct GUIExecutor;
 
 #[cfg(feature = "gui")]
 impl GUIExecutor{
-    pub fn run(arr: Vec<Box<dyn DSPChart<Message=Message, State=()>>>, mut first_element: Box<dyn DSPObject>) {
+    pub fn run(arr: Vec<Box<dyn DSPChart<Message=Message, State=()>>>, mut flowgraph: Flowgraph) {
         spawn(move || {
-            first_element.start()
+            flowgraph.run().expect("Failed to run flowgraph")
         });
         
         let gui = GUI{
@@ -78,4 +87,20 @@ impl GUIExecutor{
         };
         gui.start();
     }
+
+    /// Same as [`GUIExecutor::run`], but the flowgraph runs on the threads of `scheduler` so a
+    /// slow chart does not hold up the source feeding it
+    #[cfg(feature = "multithreading-std")]
+    pub fn run_threaded(arr: Vec<Box<dyn DSPChart<Message=Message, State=()>>>, flowgraph: Flowgraph, scheduler: Scheduler) {
+        spawn(move || {
+            scheduler.run(&flowgraph).expect("Failed to run flowgraph")
+        });
+
+        let gui = GUI{
+            width: 800,
+            height: 600,
+            elements: arr
+        };
+        gui.start();
+    }
 }
@@ -0,0 +1,32 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Multiplies input 0 by the complex conjugate of input 1. Useful for correlation and for
+/// measuring the phase difference between two signals.
+#[derive(Clone, Default)]
+pub struct MultiplyConjugate;
+
+impl MultiplyConjugate {
+    pub fn new() -> MultiplyConjugate {
+        MultiplyConjugate
+    }
+}
+
+impl DSPObject for MultiplyConjugate {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::Complex, Type::Complex]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let a = inputs[0].as_complex();
+        let b = inputs[1].as_complex();
+
+        outputs[0].as_complex_mut().extend(a.iter().zip(b).map(|(a, b)| a * b.conj()));
+    }
+}
@@ -0,0 +1,74 @@
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use num::Complex;
+use spin::Mutex;
+
+use crate::math::nco::Nco;
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Complex tone from a numerically controlled oscillator. Unlike
+/// [`crate::objects::wave_gen_complex::WaveStepGenComplex`] the phase never loses precision and
+/// the frequency can be changed while it runs.
+#[derive(Clone)]
+pub struct NcoSource {
+    pub amplitude: f64,
+    pub block_size: usize,
+
+    frequency: Arc<Mutex<f64>>,
+    nco: Nco,
+}
+
+impl NcoSource {
+    /// Create a new oscillator source
+    /// - frequency: f64 - The frequency of the tone (in Hz), which may be negative
+    /// - amplitude: f64 - The amplitude of the tone
+    /// - sample_rate: f64 - The sample rate to generate at (in Hz)
+    /// - block_size: usize - The number of samples to produce per block
+    pub fn new(frequency: f64, amplitude: f64, sample_rate: f64, block_size: usize) -> NcoSource {
+        NcoSource {
+            amplitude,
+            block_size,
+            frequency: Arc::new(Mutex::new(frequency)),
+            nco: Nco::new(frequency, sample_rate),
+        }
+    }
+
+    /// The frequency of the tone (in Hz)
+    pub fn frequency(&self) -> f64 {
+        *self.frequency.lock()
+    }
+
+    /// Retune without a jump in phase, taking effect from the next block. Every clone sees the
+    /// new frequency.
+    pub fn set_frequency(&self, frequency: f64) {
+        *self.frequency.lock() = frequency;
+    }
+}
+
+impl DSPObject for NcoSource {
+    fn input_types(&self) -> Vec<Type> {
+        vec![]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn output_sample_rate(&self, _input: Option<f64>) -> Option<f64> {
+        Some(self.nco.sample_rate)
+    }
+
+    fn work(&mut self, _inputs: &[Samples], outputs: &mut [Samples]) {
+        self.nco.set_frequency(self.frequency());
+
+        let output = outputs[0].as_complex_mut();
+        output.resize(self.block_size, Complex::new(0.0, 0.0));
+        self.nco.fill(output);
+
+        if self.amplitude != 1.0 {
+            output.iter_mut().for_each(|sample| *sample *= self.amplitude);
+        }
+    }
+}
@@ -1,149 +1,282 @@
+use alloc::boxed::Box;
+use alloc::sync::{Arc, Weak};
+use alloc::vec::Vec;
 use core::cmp::PartialEq;
-use num::Complex;
-use spin::barrier::Barrier;
-use spin::RwLock;
-
-#[cfg(feature = "multithreading-std")]
-use crate::objects::{BARRIERS, BARRIERS_INDEX};
+use core::mem;
 
-use crate::objects::{COMPLEX_OUTPUT_BUFFER_INDEX, COMPLEX_OUTPUT_BUFFERS, F64_OUTPUT_BUFFER_INDEX, F64_OUTPUT_BUFFERS};
+use num::Complex;
+use spin::{Mutex, RwLock};
 
-#[derive(Clone,Copy,PartialEq)]
+#[derive(Clone,Copy,PartialEq,Debug)]
 pub enum Type {
     NONE,
     F64,
     Complex,
 }
 
-#[derive(Clone,Copy)]
-pub struct Bus<'a>{
-    pub bust_type: Type,
-
-    pub buffer_f64: Option<&'a RwLock<f64>>,
-    pub buffer_complex: Option<&'a RwLock<Complex<f64>>>,
-
-    subscribers: [Option<*mut dyn DSPObject>; 64],
-    subscriber_index: usize,
-
-    #[cfg(feature = "multithreading-std")]
-    pub barrier: Option<&'a Barrier>
+/// A block of samples. Objects consume and produce whole blocks at a time so the cost of locking
+/// a bus and calling subscribers is paid once per block rather than once per sample.
+#[derive(Clone,PartialEq,Debug,Default)]
+pub enum Samples {
+    #[default]
+    NONE,
+    F64(Vec<f64>),
+    Complex(Vec<Complex<f64>>),
 }
 
-#[cfg(not(feature = "multithreading-std"))]
-fn increment_barrier() {}
+impl Samples {
+    /// Create an empty block of the given type
+    pub fn new(sample_type: Type) -> Samples {
+        match sample_type {
+            Type::NONE => Samples::NONE,
+            Type::F64 => Samples::F64(Vec::new()),
+            Type::Complex => Samples::Complex(Vec::new()),
+        }
+    }
 
-#[cfg(feature = "multithreading-std")]
-fn increment_barrier() {
-    *BARRIERS_INDEX.lock() += 1;
-}
+    pub fn get_type(&self) -> Type {
+        match self {
+            Samples::NONE => Type::NONE,
+            Samples::F64(_) => Type::F64,
+            Samples::Complex(_) => Type::Complex,
+        }
+    }
 
-impl Bus<'_> {
-    pub fn new() -> Bus<'static> {
-        Bus {
-            bust_type: Type::NONE,
-            buffer_f64: None,
-            buffer_complex: None,
-            subscribers: [None; 64],
-            subscriber_index: 0,
+    pub fn len(&self) -> usize {
+        match self {
+            Samples::NONE => 0,
+            Samples::F64(block) => block.len(),
+            Samples::Complex(block) => block.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
 
-            #[cfg(feature = "multithreading-std")]
-            barrier: None,
+    /// Remove every sample while keeping the allocation around for the next block
+    pub fn clear(&mut self) {
+        match self {
+            Samples::NONE => {}
+            Samples::F64(block) => block.clear(),
+            Samples::Complex(block) => block.clear(),
         }
     }
 
-    pub fn new_f64() -> Bus<'static> {
-        let mut locked = F64_OUTPUT_BUFFER_INDEX.lock();
+    /// View the block as real samples. Panics if the block is not of type F64.
+    pub fn as_f64(&self) -> &[f64] {
+        match self {
+            Samples::F64(block) => block,
+            _ => panic!("expected a F64 block, got {:?}", self.get_type()),
+        }
+    }
 
-        let bus = Bus {
-            bust_type: Type::F64,
-            buffer_f64: Some(&F64_OUTPUT_BUFFERS[*locked]),
-            buffer_complex: None,
-            subscribers: [None; 64],
-            subscriber_index: 0,
+    /// View the block as complex samples. Panics if the block is not of type Complex.
+    pub fn as_complex(&self) -> &[Complex<f64>] {
+        match self {
+            Samples::Complex(block) => block,
+            _ => panic!("expected a Complex block, got {:?}", self.get_type()),
+        }
+    }
 
-            #[cfg(feature = "multithreading-std")]
-            barrier: Some(&BARRIERS[*BARRIERS_INDEX.lock()]),
-        };
+    /// Get the underlying buffer of a F64 block so it can be resized and filled.
+    pub fn as_f64_mut(&mut self) -> &mut Vec<f64> {
+        match self {
+            Samples::F64(block) => block,
+            _ => panic!("expected a F64 block, got {:?}", self.get_type()),
+        }
+    }
 
-        *locked += 1;
+    /// Get the underlying buffer of a Complex block so it can be resized and filled.
+    pub fn as_complex_mut(&mut self) -> &mut Vec<Complex<f64>> {
+        match self {
+            Samples::Complex(block) => block,
+            _ => panic!("expected a Complex block, got {:?}", self.get_type()),
+        }
+    }
 
-        increment_barrier();
+    /// Append the first `n` samples of `other` to this block. Panics if the types differ.
+    pub fn extend_from(&mut self, other: &Samples, n: usize) {
+        match (self, other) {
+            (Samples::NONE, Samples::NONE) => {}
+            (Samples::F64(block), Samples::F64(other)) => block.extend_from_slice(&other[..n]),
+            (Samples::Complex(block), Samples::Complex(other)) => block.extend_from_slice(&other[..n]),
+            (block, other) => panic!("can not extend a {:?} block with a {:?} block", block.get_type(), other.get_type()),
+        }
+    }
 
-        bus
+    /// Remove the first `n` samples of the block
+    pub fn remove_front(&mut self, n: usize) {
+        match self {
+            Samples::NONE => {}
+            Samples::F64(block) => {
+                block.drain(..n);
+            }
+            Samples::Complex(block) => {
+                block.drain(..n);
+            }
+        }
     }
+}
 
-    pub fn new_complex() -> Bus<'static> {
+/// A reference counted node that can be subscribed to a bus. Buses only keep a weak reference
+/// to their subscribers, so whoever builds the graph is responsible for keeping these alive.
+pub type NodeRef = Arc<Mutex<Node>>;
+type WeakNodeRef = Weak<Mutex<Node>>;
 
-        let mut locked = COMPLEX_OUTPUT_BUFFER_INDEX.lock();
+/// Wrap an object in a node so it can be connected to other nodes
+pub fn new_node<T: DSPObject + 'static>(object: T) -> NodeRef {
+    Arc::new(Mutex::new(Node::new(Box::new(object))))
+}
 
-        let bus = Bus {
-            bust_type: Type::Complex,
-            buffer_f64: None,
-            buffer_complex: Some(&COMPLEX_OUTPUT_BUFFERS[*locked]),
-            subscribers: [None; 64],
-            subscriber_index: 0,
+/// Subscribe input port `input` of `subscriber` to output port `output` of `publisher`
+pub fn connect(publisher: &NodeRef, output: usize, subscriber: &NodeRef, input: usize) {
+    let bus = publisher.lock().output_bus(output).clone();
+    bus.subscribe(subscriber, input);
+}
 
-            #[cfg(feature = "multithreading-std")]
-            barrier: Some(&BARRIERS[*BARRIERS_INDEX.lock()]),
-        };
+/// A handle to an output port. Each typed bus owns its own heap allocated block, which is shared
+/// by every clone of the bus and freed once the last clone is dropped.
+#[derive(Clone)]
+pub struct Bus {
+    pub bust_type: Type,
 
-        *locked += 1;
-        
-        increment_barrier();
+    pub buffer: Option<Arc<RwLock<Samples>>>,
 
-        bus
-    }
+    subscribers: Arc<RwLock<Vec<(WeakNodeRef, usize)>>>,
 }
 
-unsafe impl Send for Bus<'_> {}
-unsafe impl Sync for Bus<'_> {}
+impl Bus {
+    pub fn new() -> Bus {
+        Bus {
+            bust_type: Type::NONE,
+            buffer: None,
+            subscribers: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
 
+    pub fn new_f64() -> Bus {
+        Bus {
+            bust_type: Type::F64,
+            buffer: Some(Arc::new(RwLock::new(Samples::new(Type::F64)))),
+            subscribers: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
 
-impl Bus<'_> {
-    pub fn trigger_f64(&self, value: f64) {
-        debug_assert!(self.bust_type == Type::F64);
+    pub fn new_complex() -> Bus {
+        Bus {
+            bust_type: Type::Complex,
+            buffer: Some(Arc::new(RwLock::new(Samples::new(Type::Complex)))),
+            subscribers: Arc::new(RwLock::new(Vec::new())),
+        }
+    }
 
-        if let Some(buffer) = self.buffer_f64 {
-            *buffer.write() = value;
+    /// Create a bus carrying blocks of the given type
+    pub fn of_type(bus_type: Type) -> Bus {
+        match bus_type {
+            Type::NONE => Bus::new(),
+            Type::F64 => Bus::new_f64(),
+            Type::Complex => Bus::new_complex(),
         }
+    }
+}
 
-        self.run_subscribers();
+impl Default for Bus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Bus {
+    /// Take the current block off the bus so the owner can refill it without reallocating. The
+    /// block should be handed back with [`Bus::trigger`].
+    pub fn take(&self) -> Samples {
+        match &self.buffer {
+            Some(buffer) => mem::replace(&mut *buffer.write(), Samples::new(self.bust_type)),
+            None => Samples::NONE,
+        }
     }
 
-    pub fn trigger_complex(&self, value: Complex<f64>) {
-        debug_assert!(self.bust_type == Type::Complex);
+    /// Put a new block on the bus and hand it to every subscriber
+    pub fn trigger(&self, block: Samples) {
+        debug_assert!(self.bust_type == block.get_type());
 
-        if let Some(buffer) = self.buffer_complex {
-            *buffer.write() = value;
+        if let Some(buffer) = &self.buffer {
+            *buffer.write() = block;
         }
 
         self.run_subscribers();
     }
 
-
-    pub fn subscribe(&mut self, subscriber: *mut dyn DSPObject) {
-        self.subscribers[self.subscriber_index] = Some(subscriber);
-
-        self.subscriber_index += 1;
+    /// Feed every block put on this bus into input port `input` of `subscriber`. Only a weak
+    /// reference is kept, so the subscription ends once the subscriber is dropped.
+    pub fn subscribe(&self, subscriber: &NodeRef, input: usize) {
+        self.subscribers.write().push((Arc::downgrade(subscriber), input));
     }
 
+    /// Number of subscribers that are still alive
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers.read().iter().filter(|(subscriber, _)| subscriber.strong_count() > 0).count()
+    }
 }
 
-impl Bus<'_> {
+impl Bus {
     fn run_subscribers(&self) {
-        for i in 0..self.subscriber_index {
-            unsafe { self.subscribers[i].unwrap_unchecked().as_mut().unwrap_unchecked().process() };
+        let Some(buffer) = &self.buffer else {
+            return;
+        };
+        let block = buffer.read();
+        let mut dangling = false;
+
+        for (subscriber, input) in self.subscribers.read().iter() {
+            match subscriber.upgrade() {
+                Some(subscriber) => {
+                    let mut subscriber = subscriber.lock();
+                    subscriber.push(*input, &block);
+                    subscriber.process();
+                }
+                None => dangling = true,
+            }
+        }
+
+        // Drop subscribers that no longer exist instead of running them
+        if dangling {
+            self.subscribers.write().retain(|(subscriber, _)| subscriber.strong_count() > 0);
         }
     }
 }
 
+/// A signal processing block with numbered, typed input and output ports
 pub trait DSPObject: Send + Sync + DSPObjectClonable {
-    fn return_type(&self) -> Type;
-    fn input_type(&self) -> Type;
-    fn get_bus(&mut self) -> &mut Bus<'static>;
-    fn set_bus(&mut self, bus: &mut Bus<'static>);
-    fn process(&mut self);
-    fn start(&mut self);
+    /// Types of the input ports, in port order. A source has no input ports.
+    fn input_types(&self) -> Vec<Type>;
+
+    /// Types of the output ports, in port order. A sink has no output ports.
+    fn output_types(&self) -> Vec<Type>;
+
+    /// Returns true once a source has no more samples to produce. Sources that run forever never
+    /// finish.
+    fn is_done(&self) -> bool {
+        false
+    }
+
+    /// Sample rate the inputs are expected to run at, for blocks configured with one. The
+    /// flowgraph checks it against the rate coming from upstream.
+    fn input_sample_rate(&self) -> Option<f64> {
+        None
+    }
+
+    /// Sample rate of the outputs given the sample rate of the inputs, where `None` means the
+    /// rate is not known. Sources return their own rate and blocks that change the rate scale
+    /// the input rate.
+    fn output_sample_rate(&self, input: Option<f64>) -> Option<f64> {
+        input
+    }
+
+    /// Consume one block per input port and append the results to one block per output port.
+    /// Every input block has the same length and every output block starts out empty with the
+    /// type of its port. Blocks that change the sample rate may produce any number of samples.
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]);
 }
 
 pub trait DSPObjectClonable {
@@ -158,3 +291,118 @@ where
         self
     }
 }
+
+/// An object together with the samples waiting on each of its inputs and a bus for each of its
+/// outputs. Inputs are lined up so that every call to [`DSPObject::work`] gets the same number of
+/// samples on every port.
+pub struct Node {
+    object: Box<dyn DSPObject>,
+
+    pending: Vec<Samples>,
+    ready: Vec<Samples>,
+    outputs: Vec<Bus>,
+}
+
+impl Node {
+    pub fn new(object: Box<dyn DSPObject>) -> Node {
+        let input_types = object.input_types();
+        let output_types = object.output_types();
+
+        Node {
+            object,
+
+            pending: input_types.iter().map(|t| Samples::new(*t)).collect(),
+            ready: input_types.iter().map(|t| Samples::new(*t)).collect(),
+            outputs: output_types.iter().map(|t| Bus::of_type(*t)).collect(),
+        }
+    }
+
+    pub fn object(&self) -> &dyn DSPObject {
+        self.object.as_ref()
+    }
+
+    pub fn object_mut(&mut self) -> &mut dyn DSPObject {
+        self.object.as_mut()
+    }
+
+    pub fn num_inputs(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn output_bus(&self, output: usize) -> &Bus {
+        &self.outputs[output]
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.object.is_done()
+    }
+
+    /// Queue a block on an input port
+    pub fn push(&mut self, input: usize, block: &Samples) {
+        self.pending[input].extend_from(block, block.len());
+    }
+
+    /// Queue a block on an input port without copying it when nothing else is queued there
+    pub fn push_owned(&mut self, input: usize, block: Samples) {
+        if self.pending[input].is_empty() {
+            self.pending[input] = block;
+        } else {
+            self.pending[input].extend_from(&block, block.len());
+        }
+    }
+
+    /// Number of samples queued on an input port
+    pub fn pending(&self, input: usize) -> usize {
+        self.pending[input].len()
+    }
+
+    /// Number of samples that can be processed right now, which is limited by the emptiest input.
+    /// Sources can always run.
+    pub fn available(&self) -> Option<usize> {
+        self.pending.iter().map(|block| block.len()).min()
+    }
+
+    /// Run the object on whatever is lined up on every input and publish the results on the
+    /// output buses. Does nothing if any input is still empty.
+    pub fn process(&mut self) {
+        if self.available() == Some(0) {
+            return;
+        }
+
+        let mut outputs: Vec<Samples> = self.outputs.iter().map(|bus| bus.take()).collect();
+        self.work_into(&mut outputs);
+
+        for (bus, block) in self.outputs.iter().zip(outputs) {
+            bus.trigger(block);
+        }
+    }
+
+    /// Run the object on whatever is lined up on every input, writing one block per output port
+    /// into `outputs` instead of publishing it. Returns false without running the object if any
+    /// input is still empty.
+    pub fn work_into(&mut self, outputs: &mut [Samples]) -> bool {
+        let n = match self.available() {
+            Some(0) => return false,
+            Some(n) => n,
+            None => 0,
+        };
+
+        for (pending, ready) in self.pending.iter_mut().zip(self.ready.iter_mut()) {
+            ready.clear();
+
+            if pending.len() == n {
+                mem::swap(pending, ready);
+            } else {
+                ready.extend_from(pending, n);
+                pending.remove_front(n);
+            }
+        }
+
+        for block in outputs.iter_mut() {
+            block.clear();
+        }
+        self.object.work(&self.ready, outputs);
+
+        true
+    }
+}
@@ -0,0 +1,263 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+
+use num::Complex;
+
+use crate::math::fourier::FftPlan;
+use crate::math::ofdm::OfdmConfig;
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Finds the frames of an [`crate::objects::ofdm_transmitter::OfdmTransmitter`] with the same
+/// [`OfdmConfig`] and turns them back into bits.
+///
+/// The Schmidl-Cox metric, how alike the two halves of a window are, finds the first preamble
+/// symbol, and the symbol is timed from the middle of the plateau its cyclic prefix makes. The
+/// phase between the halves gives the carrier offset modulo two subcarriers, and comparing the
+/// spectra of both preamble symbols at every even shift finds the whole number of subcarriers
+/// left over. Once the offset is taken out the second preamble symbol gives the channel on every
+/// subcarrier and the difference between the two symbols the noise. Every data symbol is
+/// equalized, turned back by the phase error the pilots show, and demapped.
+///
+/// Output 0 carries hard bits as 0.0 or 1.0 and output 1 the log likelihood ratio of each of
+/// them, positive for a one.
+#[derive(Clone)]
+pub struct OfdmReceiver {
+    pub config: OfdmConfig,
+    /// Schmidl-Cox metric, between 0 and 1, above which a window is taken for a preamble
+    pub threshold: f64,
+    /// Largest carrier offset to look for, in subcarriers
+    pub max_offset: f64,
+
+    plan: FftPlan,
+    history: Vec<Complex<f64>>,
+    // Position in `history` of the next window to test for a preamble
+    position: usize,
+    cfo: f64,
+    noise_variance: f64,
+    frames: usize,
+}
+
+/// Correlation between the two halves of the window starting at `d`, and the energy of the
+/// whole window
+fn halves(x: &[Complex<f64>], d: usize, half: usize) -> (Complex<f64>, f64) {
+    let correlation = (d..d + half).map(|n| x[n].conj() * x[n + half]).sum();
+    let energy = x[d..d + 2 * half].iter().map(|x| x.norm_sqr()).sum();
+
+    (correlation, energy)
+}
+
+/// Schmidl-Cox timing metric, |P|² over the square of the energy in one half
+fn metric(correlation: Complex<f64>, energy: f64) -> f64 {
+    if energy > 0.0 {
+        correlation.norm_sqr() / (energy * energy / 4.0)
+    } else {
+        0.0
+    }
+}
+
+impl OfdmReceiver {
+    /// Create a new OFDM receiver, finding preambles with a metric of at least 0.5 and carrier
+    /// offsets of up to an eighth of the subcarriers
+    /// - config: OfdmConfig - The frame layout, which the transmitter must share
+    pub fn new(config: OfdmConfig) -> OfdmReceiver {
+        let plan = FftPlan::new(config.fft_size);
+        let max_offset = config.fft_size as f64 / 8.0;
+
+        OfdmReceiver {
+            config,
+            threshold: 0.5,
+            max_offset,
+            plan,
+            history: Vec::new(),
+            position: 0,
+            cfo: 0.0,
+            noise_variance: 0.0,
+            frames: 0,
+        }
+    }
+
+    /// Carrier offset of the last frame, in subcarriers
+    pub fn cfo(&self) -> f64 {
+        self.cfo
+    }
+
+    /// Noise power on each subcarrier of the last frame, before equalization
+    pub fn noise_variance(&self) -> f64 {
+        self.noise_variance
+    }
+
+    /// Number of frames received so far
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    /// Samples of history a frame whose preamble first crossed the threshold at `d` may need
+    fn frame_end(&self, d: usize) -> usize {
+        let config = &self.config;
+        let latest = d + config.fft_size / 2 + 2 * config.cyclic_prefix;
+
+        latest + (1 + config.symbols_per_frame) * config.symbol_length() + config.fft_size + 1
+    }
+
+    /// Window start in the middle of the plateau the cyclic prefix of the first preamble symbol
+    /// makes, searched for from `d` on, and the correlation there
+    fn plateau(&self, d: usize) -> (usize, Complex<f64>) {
+        let half = self.config.fft_size / 2;
+        let metrics: Vec<(f64, Complex<f64>)> = (d..=d + half + self.config.cyclic_prefix).map(|n| {
+            let (correlation, energy) = halves(&self.history, n, half);
+            (metric(correlation, energy), correlation)
+        }).collect();
+
+        let peak = (0..metrics.len()).max_by(|a, b| metrics[*a].0.total_cmp(&metrics[*b].0)).unwrap();
+        let level = 0.9 * metrics[peak].0;
+        let first = (0..=peak).rev().take_while(|n| metrics[*n].0 >= level).last().unwrap();
+        let last = (peak..metrics.len()).take_while(|n| metrics[*n].0 >= level).last().unwrap();
+
+        let middle = (first + last) / 2;
+        (d + middle, metrics[middle].1)
+    }
+
+    /// Spectra of `count` symbols from the window at `start` on, with a carrier offset of `omega`
+    /// radians per sample taken out
+    fn spectra(&self, start: usize, count: usize, omega: f64) -> Vec<Vec<Complex<f64>>> {
+        let config = &self.config;
+
+        (0..count).map(|symbol| {
+            let offset = symbol * config.symbol_length();
+            let mut spectrum: Vec<Complex<f64>> = (offset..offset + config.fft_size).map(|n| {
+                self.history[start + n] * Complex::from_polar(1.0, -omega * n as f64)
+            }).collect();
+            self.plan.fft(&mut spectrum);
+            spectrum
+        }).collect()
+    }
+
+    /// Demodulate the frame whose first preamble symbol has its window at `start`, given the
+    /// correlation between its halves, and return where its last window ends
+    fn frame(&mut self, start: usize, correlation: Complex<f64>, hard: &mut Vec<f64>, soft: &mut Vec<f64>) -> usize {
+        let n = self.config.fft_size;
+        let used: Vec<usize> = self.config.data_carriers.iter().chain(self.config.pilot_carriers.iter()).map(|carrier| self.config.bin(*carrier)).collect();
+        let [first, second] = self.config.preamble().clone();
+
+        // Fraction of two subcarriers from the phase between the halves
+        let fraction = correlation.arg() / (n / 2) as f64;
+        let preamble = self.spectra(start, 2, fraction);
+
+        // Whole number of subcarriers from where both spectra line up with the known preamble
+        let reach = libm::ceil(self.max_offset / 2.0) as i64;
+        let score = |shift: i64| -> f64 {
+            used.iter().filter(|bin| first[**bin].re != 0.0).map(|bin| {
+                let shifted = (*bin as i64 + shift).rem_euclid(n as i64) as usize;
+                preamble[0][shifted].conj() * preamble[1][shifted] * (second[*bin] / first[*bin])
+            }).sum::<Complex<f64>>().norm_sqr()
+        };
+        let shift = (-reach..=reach).map(|g| 2 * g).max_by(|a, b| score(*a).total_cmp(&score(*b))).unwrap();
+
+        let omega = fraction + 2.0 * PI * shift as f64 / n as f64;
+        self.cfo = omega * n as f64 / (2.0 * PI);
+
+        let symbols = self.spectra(start, 2 + self.config.symbols_per_frame, omega);
+
+        // The channel from the second preamble symbol, and from the first as well on the
+        // subcarriers they share once what is left of the carrier offset has been turned out
+        // between them. The first has half the noise there, so it gets twice the weight, and how
+        // far apart the two are gives the noise.
+        let mut channel: Vec<Complex<f64>> = (0..n).map(|bin| {
+            if second[bin].re != 0.0 { symbols[1][bin] / second[bin] } else { Complex::new(0.0, 0.0) }
+        }).collect();
+        let others: Vec<(usize, Complex<f64>)> = used.iter().filter(|bin| first[**bin].re != 0.0).map(|bin| (*bin, symbols[0][*bin] / first[*bin])).collect();
+        let drift = others.iter().map(|(bin, other)| channel[*bin] * other.conj()).sum::<Complex<f64>>();
+        let drift = Complex::from_polar(1.0, drift.arg());
+
+        let mut noise = 0.0;
+        for (bin, other) in others.iter() {
+            let other = other * drift;
+            noise += (channel[*bin] - other).norm_sqr();
+            channel[*bin] = (channel[*bin] + 2.0 * other) / 3.0;
+        }
+        let power = used.iter().map(|bin| channel[*bin].norm_sqr()).sum::<f64>() / used.len() as f64;
+        self.noise_variance = (noise / others.len().max(1) as f64 / 1.5).max(1e-10 * power);
+
+        for spectrum in symbols[2..].iter() {
+            let equalized: Vec<Complex<f64>> = spectrum.iter().zip(channel.iter()).map(|(y, h)| y / h).collect();
+
+            let error = self.config.pilot_carriers.iter().zip(self.config.pilots().iter()).map(|(carrier, pilot)| {
+                equalized[self.config.bin(*carrier)] * pilot.conj()
+            }).sum::<Complex<f64>>();
+            let rotation = Complex::from_polar(1.0, -error.arg());
+
+            let start = soft.len();
+            for carrier in self.config.data_carriers.iter() {
+                let bin = self.config.bin(*carrier);
+                let variance = self.noise_variance / channel[bin].norm_sqr();
+                self.config.constellation.llrs(equalized[bin] * rotation, variance, soft);
+            }
+            hard.extend(soft[start..].iter().map(|llr| (*llr > 0.0) as u8 as f64));
+        }
+
+        self.frames += 1;
+        start + (1 + self.config.symbols_per_frame) * self.config.symbol_length() + n
+    }
+}
+
+impl DSPObject for OfdmReceiver {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::F64, Type::F64]
+    }
+
+    fn output_sample_rate(&self, input: Option<f64>) -> Option<f64> {
+        input.map(|rate| rate / self.config.frame_length() as f64 * self.config.bits_per_frame() as f64)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let [hard, soft] = outputs else {
+            return;
+        };
+        let (hard, soft) = (hard.as_f64_mut(), soft.as_f64_mut());
+        self.history.extend_from_slice(inputs[0].as_complex());
+
+        let half = self.config.fft_size / 2;
+        // Correlation and energy of the window at `position`, slid along a sample at a time and
+        // worked out afresh every so often so rounding errors do not build up
+        let mut running: Option<(Complex<f64>, f64)> = None;
+
+        while self.position + 2 * half <= self.history.len() {
+            let d = self.position;
+            let (correlation, energy) = match running {
+                Some(sums) if !d.is_multiple_of(half) => sums,
+                _ => halves(&self.history, d, half),
+            };
+
+            if metric(correlation, energy) < self.threshold {
+                if d + 2 * half < self.history.len() {
+                    let x = &self.history;
+                    let correlation = correlation - x[d].conj() * x[d + half] + x[d + half].conj() * x[d + 2 * half];
+                    let energy = energy - x[d].norm_sqr() + x[d + 2 * half].norm_sqr();
+                    running = Some((correlation, energy));
+                }
+                self.position += 1;
+                continue;
+            }
+
+            // Wait until the whole frame is in
+            if self.frame_end(d) > self.history.len() {
+                break;
+            }
+
+            // The middle of the plateau is half a prefix before the symbol. Starting a quarter of
+            // the prefix early leaves room for the channel to spread into it either way.
+            let (middle, correlation) = self.plateau(d);
+            let start = middle + self.config.cyclic_prefix / 4;
+            self.position = self.frame(start, correlation, hard, soft);
+            running = None;
+        }
+
+        self.history.drain(..self.position);
+        self.position = 0;
+    }
+}
@@ -0,0 +1,91 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use num::Complex;
+
+use crate::math::fourier::FftPlan;
+use crate::math::ofdm::OfdmConfig;
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Turns bits into OFDM frames laid out by an [`OfdmConfig`]: the two preamble symbols, then the
+/// data symbols with the bits mapped onto the data subcarriers in order and the pilots in place,
+/// then the gap. Each symbol gets its cyclic prefix.
+///
+/// The input carries one bit per sample, as 0.0 or 1.0, and a frame goes out as soon as there
+/// are enough bits to fill it. Symbols using every subcarrier have unit average power.
+#[derive(Clone)]
+pub struct OfdmTransmitter {
+    pub config: OfdmConfig,
+
+    plan: FftPlan,
+    preamble: Vec<Complex<f64>>,
+    // Bits waiting for enough company to make a frame
+    pending: Vec<u8>,
+}
+
+impl OfdmTransmitter {
+    /// Create a new OFDM transmitter
+    /// - config: OfdmConfig - The frame layout, which the receiver must share
+    pub fn new(config: OfdmConfig) -> OfdmTransmitter {
+        let plan = FftPlan::new(config.fft_size);
+        let mut preamble = config.symbol(&plan, &config.preamble()[0]);
+        preamble.extend(config.symbol(&plan, &config.preamble()[1]));
+
+        OfdmTransmitter {
+            config,
+            plan,
+            preamble,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Map one symbol worth of bits onto the subcarriers and add the symbol to `output`
+    fn symbol(&self, bits: &[u8], output: &mut Vec<Complex<f64>>) {
+        let config = &self.config;
+        let mut spectrum = vec![Complex::new(0.0, 0.0); config.fft_size];
+
+        let per_carrier = config.constellation.bits_per_symbol();
+        for (carrier, chunk) in config.data_carriers.iter().zip(bits.chunks_exact(per_carrier)) {
+            let label = chunk.iter().fold(0, |label, bit| (label << 1) | *bit as usize);
+            spectrum[config.bin(*carrier)] = config.constellation.point(label);
+        }
+        for (carrier, pilot) in config.pilot_carriers.iter().zip(config.pilots().iter()) {
+            spectrum[config.bin(*carrier)] = *pilot;
+        }
+
+        output.extend(config.symbol(&self.plan, &spectrum));
+    }
+}
+
+impl DSPObject for OfdmTransmitter {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::F64]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn output_sample_rate(&self, input: Option<f64>) -> Option<f64> {
+        input.map(|rate| rate / self.config.bits_per_frame() as f64 * self.config.frame_length() as f64)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        self.pending.extend(inputs[0].as_f64().iter().map(|bit| (*bit != 0.0) as u8));
+
+        let bits_per_frame = self.config.bits_per_frame();
+        let frames = self.pending.len() / bits_per_frame;
+
+        let mut frame = Vec::with_capacity(frames * self.config.frame_length());
+        for bits in self.pending.chunks_exact(bits_per_frame) {
+            frame.extend_from_slice(&self.preamble);
+            for bits in bits.chunks_exact(self.config.bits_per_symbol()) {
+                self.symbol(bits, &mut frame);
+            }
+            frame.extend(core::iter::repeat_n(Complex::new(0.0, 0.0), self.config.gap));
+        }
+        self.pending.drain(..frames * bits_per_frame);
+
+        outputs[0].as_complex_mut().extend(frame);
+    }
+}
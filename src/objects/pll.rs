@@ -0,0 +1,83 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+
+use num::Complex;
+
+use crate::math::control_loop::ControlLoop;
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Second order phase locked loop that tracks an unmodulated carrier. Output 0 is the input
+/// rotated by the phase estimate, which brings the carrier to 0 Hz and zero phase once the loop
+/// has locked. Outputs 1 and 2 are the frequency estimate (in Hz) and the phase estimate (in
+/// radians) for every sample and may be left unconnected.
+#[derive(Clone)]
+pub struct Pll {
+    pub sample_rate: f64,
+
+    control: ControlLoop,
+}
+
+impl Pll {
+    /// Create a new phase locked loop
+    /// - bandwidth: f64 - The loop bandwidth (in Hz), small compared to the sample rate
+    /// - damping: f64 - The damping factor, 1/√2 for a critically damped loop
+    /// - sample_rate: f64 - The sample rate of the input (in Hz)
+    pub fn new(bandwidth: f64, damping: f64, sample_rate: f64) -> Pll {
+        Pll {
+            sample_rate,
+            control: ControlLoop::new(2.0 * PI * bandwidth / sample_rate, damping, PI),
+        }
+    }
+
+    /// Change the loop bandwidth (in Hz), e.g. to narrow it once the loop has locked
+    pub fn set_bandwidth(&mut self, bandwidth: f64) {
+        self.control.set_bandwidth(2.0 * PI * bandwidth / self.sample_rate);
+    }
+
+    /// Change the damping factor
+    pub fn set_damping(&mut self, damping: f64) {
+        self.control.set_damping(damping);
+    }
+
+    /// Frequency estimate (in Hz)
+    pub fn frequency(&self) -> f64 {
+        self.control.frequency() * self.sample_rate / (2.0 * PI)
+    }
+
+    /// Phase estimate (in radians)
+    pub fn phase(&self) -> f64 {
+        self.control.phase()
+    }
+}
+
+impl DSPObject for Pll {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::Complex, Type::F64, Type::F64]
+    }
+
+    fn input_sample_rate(&self) -> Option<f64> {
+        Some(self.sample_rate)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let [output, frequency, phase] = outputs else {
+            return;
+        };
+        let (output, frequency, phase) = (output.as_complex_mut(), frequency.as_f64_mut(), phase.as_f64_mut());
+
+        for x in inputs[0].as_complex() {
+            let y = x * Complex::from_polar(1.0, -self.control.phase());
+
+            output.push(y);
+            frequency.push(self.frequency());
+            phase.push(self.control.phase());
+
+            self.control.advance(libm::atan2(y.im, y.re));
+        }
+    }
+}
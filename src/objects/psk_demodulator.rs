@@ -0,0 +1,143 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::FRAC_1_SQRT_2;
+
+use crate::math::constellation::gray;
+use crate::math::filter::root_raised_cosine;
+use crate::objects::costas_loop::{CostasLoop, Psk};
+use crate::objects::fir_filter::FirFilter;
+use crate::objects::object::{DSPObject, Samples, Type};
+use crate::objects::psk_modulator::{BitFormat, PULSE_SPAN};
+use crate::objects::symbol_sync::{SymbolSync, TimingDetector};
+
+/// Root raised cosine filter matched to [`crate::objects::psk_modulator::pulse_shaper`], which
+/// leaves unit amplitude symbols at the right instants, as used by [`PskDemodulator`]
+/// - samples_per_symbol: usize - The number of input samples per symbol
+/// - rolloff: f64 - The excess bandwidth of the pulse, between 0 and 1
+pub fn matched_filter(samples_per_symbol: usize, rolloff: f64) -> FirFilter {
+    let taps = root_raised_cosine(samples_per_symbol, rolloff, PULSE_SPAN).expect("invalid pulse shape");
+
+    let scale = 1.0 / libm::sqrt(samples_per_symbol as f64);
+    FirFilter::new(Type::Complex, taps.iter().map(|tap| tap * scale).collect())
+}
+
+/// Turns root raised cosine shaped PSK at complex baseband back into bits, undoing a
+/// [`crate::objects::psk_modulator::PskModulator`] with the same settings. The signal goes
+/// through a matched filter, Gardner timing recovery, a Costas loop and a slicer, and is then
+/// Gray decoded and, if it was differentially encoded, differentially decoded.
+///
+/// The input should be at around unit power, e.g. after an AGC, and within a few percent of the
+/// symbol rate of 0 Hz, e.g. after a band edge FLL.
+#[derive(Clone)]
+pub struct PskDemodulator {
+    pub psk: Psk,
+    pub format: BitFormat,
+    pub samples_per_symbol: usize,
+    pub differential: bool,
+    pub sample_rate: f64,
+
+    matched: FirFilter,
+    timing: SymbolSync,
+    carrier: CostasLoop,
+    // Bits waiting to be packed into a byte
+    pending: Vec<u8>,
+    previous: usize,
+}
+
+impl PskDemodulator {
+    /// Create a new PSK demodulator. The timing loop starts with a bandwidth of 0.5% of the
+    /// symbol rate and the carrier loop with 1%.
+    /// - psk: Psk - The constellation that was sent
+    /// - format: BitFormat - How the output carries bits
+    /// - samples_per_symbol: usize - The number of input samples per symbol
+    /// - rolloff: f64 - The excess bandwidth of the root raised cosine pulse, between 0 and 1
+    /// - differential: bool - Whether the bits were encoded in the step between symbols
+    /// - sample_rate: f64 - The sample rate of the input (in Hz)
+    pub fn new(psk: Psk, format: BitFormat, samples_per_symbol: usize, rolloff: f64, differential: bool, sample_rate: f64) -> PskDemodulator {
+        let symbol_rate = sample_rate / samples_per_symbol as f64;
+
+        PskDemodulator {
+            psk,
+            format,
+            samples_per_symbol,
+            differential,
+            sample_rate,
+            matched: matched_filter(samples_per_symbol, rolloff),
+            timing: SymbolSync::new(TimingDetector::Gardner, samples_per_symbol as f64, 0.005 * symbol_rate, FRAC_1_SQRT_2, sample_rate),
+            carrier: CostasLoop::new(psk, 0.01 * symbol_rate, FRAC_1_SQRT_2, symbol_rate),
+            pending: Vec::new(),
+            previous: 0,
+        }
+    }
+
+    /// Change the bandwidth of the timing loop (in Hz)
+    pub fn set_timing_bandwidth(&mut self, bandwidth: f64) {
+        self.timing.set_bandwidth(bandwidth);
+    }
+
+    /// Change the bandwidth of the carrier loop (in Hz)
+    pub fn set_carrier_bandwidth(&mut self, bandwidth: f64) {
+        self.carrier.set_bandwidth(bandwidth);
+    }
+
+    /// Frequency offset the carrier loop has locked to (in Hz)
+    pub fn frequency(&self) -> f64 {
+        self.carrier.frequency()
+    }
+}
+
+/// Run an object with one input and return its first output
+fn stage<T: DSPObject>(object: &mut T, input: Samples) -> Samples {
+    let mut outputs: Vec<Samples> = object.output_types().iter().map(|t| Samples::new(*t)).collect();
+    object.work(&[input], &mut outputs);
+
+    outputs.swap_remove(0)
+}
+
+impl DSPObject for PskDemodulator {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::F64]
+    }
+
+    fn input_sample_rate(&self) -> Option<f64> {
+        Some(self.sample_rate)
+    }
+
+    fn output_sample_rate(&self, input: Option<f64>) -> Option<f64> {
+        let bits = self.psk.bits_per_symbol() as f64 / self.format.bits_per_sample() as f64;
+        input.map(|rate| rate / self.samples_per_symbol as f64 * bits)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let filtered = stage(&mut self.matched, inputs[0].clone());
+        let symbols = stage(&mut self.timing, filtered);
+        let symbols = stage(&mut self.carrier, symbols);
+
+        let bits = self.psk.bits_per_symbol();
+        let order = self.psk.order();
+
+        for symbol in symbols.as_complex() {
+            let mut index = self.psk.nearest(*symbol);
+
+            if self.differential {
+                let step = (index + order - self.previous) % order;
+                self.previous = index;
+                index = step;
+            }
+
+            let value = gray(index);
+            self.pending.extend((0..bits).rev().map(|bit| ((value >> bit) & 1) as u8));
+        }
+
+        let width = self.format.bits_per_sample();
+        let output = outputs[0].as_f64_mut();
+        for chunk in self.pending.chunks_exact(width) {
+            output.push(chunk.iter().fold(0, |value, bit| (value << 1) | *bit as usize) as f64);
+        }
+        self.pending.drain(..output.len() * width);
+    }
+}
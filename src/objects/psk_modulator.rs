@@ -0,0 +1,122 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use num::Complex;
+
+use crate::math::constellation::gray_inverse;
+use crate::math::filter::root_raised_cosine;
+use crate::objects::costas_loop::Psk;
+use crate::objects::object::{DSPObject, Samples, Type};
+use crate::objects::rational_resampler::RationalResampler;
+
+/// Symbols the root raised cosine pulse of the PSK modulator and demodulator spans
+pub(crate) const PULSE_SPAN: usize = 10;
+
+/// How bits are carried on a F64 bus
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitFormat {
+    /// One bit per sample, as 0.0 or 1.0
+    Bits,
+    /// Eight bits per sample, as a byte value from 0.0 to 255.0 sent most significant bit first
+    Bytes,
+}
+
+impl BitFormat {
+    pub fn bits_per_sample(self) -> usize {
+        match self {
+            BitFormat::Bits => 1,
+            BitFormat::Bytes => 8,
+        }
+    }
+}
+
+/// Root raised cosine interpolator that turns unit power symbols, one per sample, into unit power
+/// pulses, as used by [`PskModulator`]
+/// - samples_per_symbol: usize - The number of output samples per symbol
+/// - rolloff: f64 - The excess bandwidth of the pulse, between 0 and 1
+pub fn pulse_shaper(samples_per_symbol: usize, rolloff: f64) -> RationalResampler {
+    let taps = root_raised_cosine(samples_per_symbol, rolloff, PULSE_SPAN).expect("invalid pulse shape");
+
+    // The resampler scales the pulse up by the number of samples per symbol, so take that out
+    // again and leave a factor of √sps for unit output power
+    let scale = libm::sqrt(samples_per_symbol as f64) / samples_per_symbol as f64;
+    RationalResampler::with_taps(Type::Complex, samples_per_symbol, 1, taps.iter().map(|tap| tap * scale).collect())
+}
+
+/// Turns bits into root raised cosine shaped PSK at complex baseband. Groups of bits are Gray
+/// mapped onto the constellation points of [`Psk`], so the most likely symbol errors only cost a
+/// single bit. With differential encoding the bits choose the step from one point to the next
+/// instead of the point itself, which lets the demodulator ignore the phase ambiguity of its
+/// carrier recovery. The output has unit average power.
+#[derive(Clone)]
+pub struct PskModulator {
+    pub psk: Psk,
+    pub format: BitFormat,
+    pub samples_per_symbol: usize,
+    pub differential: bool,
+
+    shaping: RationalResampler,
+    // Bits waiting for enough company to make a symbol
+    pending: Vec<u8>,
+    previous: usize,
+}
+
+impl PskModulator {
+    /// Create a new PSK modulator
+    /// - psk: Psk - The constellation to map onto
+    /// - format: BitFormat - How the input carries bits
+    /// - samples_per_symbol: usize - The number of output samples per symbol
+    /// - rolloff: f64 - The excess bandwidth of the root raised cosine pulse, between 0 and 1
+    /// - differential: bool - Whether to encode the bits in the step between symbols
+    pub fn new(psk: Psk, format: BitFormat, samples_per_symbol: usize, rolloff: f64, differential: bool) -> PskModulator {
+        PskModulator {
+            psk,
+            format,
+            samples_per_symbol,
+            differential,
+            shaping: pulse_shaper(samples_per_symbol, rolloff),
+            pending: Vec::new(),
+            previous: 0,
+        }
+    }
+}
+
+impl DSPObject for PskModulator {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::F64]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn output_sample_rate(&self, input: Option<f64>) -> Option<f64> {
+        let bits = self.format.bits_per_sample() as f64 / self.psk.bits_per_symbol() as f64;
+        input.map(|rate| rate * bits * self.samples_per_symbol as f64)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let width = self.format.bits_per_sample();
+        for value in inputs[0].as_f64() {
+            let value = *value as usize;
+            self.pending.extend((0..width).rev().map(|bit| ((value >> bit) & 1) as u8));
+        }
+
+        let bits = self.psk.bits_per_symbol();
+        let order = self.psk.order();
+        let symbols: Vec<Complex<f64>> = self.pending.chunks_exact(bits).map(|chunk| {
+            let value = chunk.iter().fold(0, |value, bit| (value << 1) | *bit as usize);
+            let mut index = gray_inverse(value);
+
+            if self.differential {
+                index = (self.previous + index) % order;
+                self.previous = index;
+            }
+
+            self.psk.point(index)
+        }).collect();
+        self.pending.drain(..symbols.len() * bits);
+
+        self.shaping.work(&[Samples::Complex(symbols)], outputs);
+    }
+}
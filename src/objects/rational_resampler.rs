@@ -0,0 +1,172 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::{Add, Mul};
+
+use num::{Complex, Zero};
+
+use crate::math::filter::lowpass;
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Part of the narrower of the input and output bands the designed filter passes untouched
+const PASSBAND: f64 = 0.8;
+
+/// Attenuation of the designed filter at and above the narrower Nyquist frequency, in dB
+const ATTENUATION: f64 = 60.0;
+
+/// Changes the sample rate by a rational factor L/M: conceptually it inserts L - 1 zeros after
+/// every input sample, low-pass filters and keeps every Mth sample. The filter is split into L
+/// polyphase branches so only the outputs that are kept get computed, and no multiplications by
+/// the inserted zeros are done.
+///
+/// The output lags the input by `(taps - 1) / 2` samples at L times the input rate.
+#[derive(Clone)]
+pub struct RationalResampler {
+    pub sample_type: Type,
+
+    interpolation: usize,
+    decimation: usize,
+    taps: Vec<f64>,
+    // Branch p holds taps p, p + L, p + 2L, ... stored back to front
+    branches: Vec<Vec<f64>>,
+    history: Samples,
+    // Index of the next output on the upsampled time line, counted from the start of the next block
+    next: usize,
+}
+
+impl RationalResampler {
+    /// Create a new resampler with an anti-alias filter designed for the ratio. The filter passes
+    /// 80% of the narrower of the input and output bands and attenuates everything that would
+    /// alias by at least 60 dB.
+    /// - sample_type: Type - The type of the input and output
+    /// - interpolation: usize - L, the factor to multiply the sample rate by
+    /// - decimation: usize - M, the factor to divide the sample rate by
+    pub fn new(sample_type: Type, interpolation: usize, decimation: usize) -> RationalResampler {
+        assert!(interpolation > 0 && decimation > 0, "interpolation and decimation must be at least 1");
+
+        let divisor = gcd(interpolation, decimation);
+        let (interpolation, decimation) = (interpolation / divisor, decimation / divisor);
+
+        // Design at the upsampled rate, in units of the input sample rate
+        let nyquist = 0.5 * (1.0_f64).min(interpolation as f64 / decimation as f64);
+        let width = (1.0 - PASSBAND) * nyquist;
+        let taps = lowpass(interpolation as f64, nyquist - width / 2.0, width, ATTENUATION).expect("the cutoff is always below the upsampled nyquist frequency");
+
+        RationalResampler::with_taps(sample_type, interpolation, decimation, taps)
+    }
+
+    /// Create a new resampler with a designed anti-alias filter between two sample rates, which
+    /// must be whole numbers of samples per second
+    /// - sample_type: Type - The type of the input and output
+    /// - input_rate: f64 - The sample rate of the input (in Hz)
+    /// - output_rate: f64 - The sample rate of the output (in Hz)
+    pub fn between(sample_type: Type, input_rate: f64, output_rate: f64) -> RationalResampler {
+        let whole = |rate: f64| rate >= 1.0 && libm::fmod(rate, 1.0) == 0.0;
+        assert!(whole(input_rate) && whole(output_rate), "sample rates must be whole numbers to resample between them");
+
+        RationalResampler::new(sample_type, output_rate as usize, input_rate as usize)
+    }
+
+    /// Create a new resampler with a custom filter. The taps run at L times the input rate and
+    /// should have a gain of 1, the resampler makes up for the inserted zeros itself.
+    /// - sample_type: Type - The type of the input and output
+    /// - interpolation: usize - L, the factor to multiply the sample rate by
+    /// - decimation: usize - M, the factor to divide the sample rate by
+    /// - taps: Vec<f64> - The anti-alias filter, e.g. from [`crate::math::filter::lowpass`]
+    pub fn with_taps(sample_type: Type, interpolation: usize, decimation: usize, taps: Vec<f64>) -> RationalResampler {
+        assert!(interpolation > 0 && decimation > 0, "interpolation and decimation must be at least 1");
+        assert!(!taps.is_empty(), "a resampler needs at least one tap");
+
+        let length = taps.len().div_ceil(interpolation);
+        let branches = (0..interpolation).map(|phase| {
+            (0..length).rev().map(|k| taps.get(k * interpolation + phase).map_or(0.0, |tap| tap * interpolation as f64)).collect()
+        }).collect();
+
+        let mut resampler = RationalResampler {
+            sample_type,
+            interpolation,
+            decimation,
+            taps,
+            branches,
+            history: Samples::new(sample_type),
+            next: 0,
+        };
+        resampler.reset();
+
+        resampler
+    }
+
+    /// L, the factor the sample rate is multiplied by, after reducing the ratio
+    pub fn interpolation(&self) -> usize {
+        self.interpolation
+    }
+
+    /// M, the factor the sample rate is divided by, after reducing the ratio
+    pub fn decimation(&self) -> usize {
+        self.decimation
+    }
+
+    /// The anti-alias filter, running at L times the input rate
+    pub fn taps(&self) -> &[f64] {
+        &self.taps
+    }
+
+    /// Forget every past sample, as if the resampler had only ever seen zeros
+    pub fn reset(&mut self) {
+        let n = self.branches[0].len() - 1;
+        self.next = 0;
+
+        match &mut self.history {
+            Samples::F64(history) => *history = vec![0.0; n],
+            Samples::Complex(history) => *history = vec![Complex::new(0.0, 0.0); n],
+            Samples::NONE => {}
+        }
+    }
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Compute every output whose position on the upsampled time line falls inside `input`
+fn resample<T>(branches: &[Vec<f64>], decimation: usize, next: &mut usize, history: &mut Vec<T>, input: &[T], output: &mut Vec<T>)
+where
+    T: Copy + Zero + Add<Output = T> + Mul<f64, Output = T>,
+{
+    let interpolation = branches.len();
+    let length = branches[0].len();
+    let end = input.len() * interpolation;
+    history.extend_from_slice(input);
+
+    while *next < end {
+        let (i, phase) = (*next / interpolation, *next % interpolation);
+        let window = &history[i..i + length];
+
+        output.push(window.iter().zip(branches[phase].iter()).fold(T::zero(), |acc, (x, tap)| acc + *x * *tap));
+        *next += decimation;
+    }
+
+    *next -= end;
+    history.drain(..input.len());
+}
+
+impl DSPObject for RationalResampler {
+    fn input_types(&self) -> Vec<Type> {
+        vec![self.sample_type]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![self.sample_type]
+    }
+
+    fn output_sample_rate(&self, input: Option<f64>) -> Option<f64> {
+        input.map(|rate| rate * self.interpolation as f64 / self.decimation as f64)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        match (&mut self.history, &mut outputs[0]) {
+            (Samples::F64(history), Samples::F64(output)) => resample(&self.branches, self.decimation, &mut self.next, history, inputs[0].as_f64(), output),
+            (Samples::Complex(history), Samples::Complex(output)) => resample(&self.branches, self.decimation, &mut self.next, history, inputs[0].as_complex(), output),
+            _ => {}
+        }
+    }
+}
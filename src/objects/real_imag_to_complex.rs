@@ -0,0 +1,33 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use num::Complex;
+
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Builds a complex stream from a real part (input 0) and an imaginary part (input 1)
+#[derive(Clone, Default)]
+pub struct RealImagToComplex;
+
+impl RealImagToComplex {
+    pub fn new() -> RealImagToComplex {
+        RealImagToComplex
+    }
+}
+
+impl DSPObject for RealImagToComplex {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::F64, Type::F64]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let real = inputs[0].as_f64();
+        let imag = inputs[1].as_f64();
+
+        outputs[0].as_complex_mut().extend(real.iter().zip(imag).map(|(re, im)| Complex::new(*re, *im)));
+    }
+}
@@ -0,0 +1,115 @@
+use alloc::boxed::Box;
+use alloc::sync::Arc;
+use core::cell::UnsafeCell;
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Bounded single-producer single-consumer queue. The producer and consumer only ever touch their
+/// own end of the queue, so neither side takes a lock.
+struct RingBuffer<T> {
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+
+    // Both indices count up forever and are wrapped on access, which keeps full and empty apart
+    head: AtomicUsize,
+    tail: AtomicUsize,
+}
+
+unsafe impl<T: Send> Send for RingBuffer<T> {}
+unsafe impl<T: Send> Sync for RingBuffer<T> {}
+
+impl<T> Drop for RingBuffer<T> {
+    fn drop(&mut self) {
+        let head = *self.head.get_mut();
+        let tail = *self.tail.get_mut();
+
+        for index in head..tail {
+            unsafe { self.slots[index % self.slots.len()].get_mut().assume_init_drop() };
+        }
+    }
+}
+
+/// The writing end of a ring buffer
+pub struct Producer<T> {
+    ring: Arc<RingBuffer<T>>,
+}
+
+/// The reading end of a ring buffer
+pub struct Consumer<T> {
+    ring: Arc<RingBuffer<T>>,
+}
+
+/// Create a ring buffer that holds up to `capacity` items and return its two ends
+pub fn ring_buffer<T>(capacity: usize) -> (Producer<T>, Consumer<T>) {
+    assert!(capacity > 0, "a ring buffer needs room for at least one item");
+
+    let ring = Arc::new(RingBuffer {
+        slots: (0..capacity).map(|_| UnsafeCell::new(MaybeUninit::uninit())).collect(),
+        head: AtomicUsize::new(0),
+        tail: AtomicUsize::new(0),
+    });
+
+    (Producer { ring: ring.clone() }, Consumer { ring })
+}
+
+impl<T> Producer<T> {
+    /// Add an item to the queue, handing it back if the queue is full
+    pub fn push(&mut self, item: T) -> Result<(), T> {
+        let tail = self.ring.tail.load(Ordering::Relaxed);
+        let head = self.ring.head.load(Ordering::Acquire);
+
+        if tail - head == self.ring.slots.len() {
+            return Err(item);
+        }
+
+        // Only the producer writes to the slot at `tail`, and the consumer will not read it until
+        // the new tail is published below
+        unsafe { (*self.ring.slots[tail % self.ring.slots.len()].get()).write(item) };
+        self.ring.tail.store(tail + 1, Ordering::Release);
+
+        Ok(())
+    }
+
+    /// Number of items that can be pushed before the queue is full
+    pub fn free(&self) -> usize {
+        let tail = self.ring.tail.load(Ordering::Relaxed);
+        let head = self.ring.head.load(Ordering::Acquire);
+
+        self.ring.slots.len() - (tail - head)
+    }
+
+    /// Whether the consumer has been dropped, so nothing pushed will ever be read
+    pub fn is_abandoned(&self) -> bool {
+        Arc::strong_count(&self.ring) == 1
+    }
+}
+
+impl<T> Consumer<T> {
+    /// Take the oldest item off the queue
+    pub fn pop(&mut self) -> Option<T> {
+        let head = self.ring.head.load(Ordering::Relaxed);
+        let tail = self.ring.tail.load(Ordering::Acquire);
+
+        if head == tail {
+            return None;
+        }
+
+        // The producer published this slot before moving the tail past it and will not reuse it
+        // until the new head is published below
+        let item = unsafe { (*self.ring.slots[head % self.ring.slots.len()].get()).assume_init_read() };
+        self.ring.head.store(head + 1, Ordering::Release);
+
+        Some(item)
+    }
+
+    /// Number of items waiting in the queue
+    pub fn len(&self) -> usize {
+        let head = self.ring.head.load(Ordering::Relaxed);
+        let tail = self.ring.tail.load(Ordering::Acquire);
+
+        tail - head
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
@@ -0,0 +1,205 @@
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::vec::Vec;
+
+use crate::objects::flowgraph::{Flowgraph, FlowgraphError};
+use crate::objects::object::{NodeRef, Samples};
+use crate::objects::ring_buffer::{ring_buffer, Consumer, Producer};
+
+/// Runs a flowgraph on a fixed number of threads. Every connection becomes a lock-free ring buffer
+/// of blocks, so a slow block only holds up its own thread. Once a ring buffer is full the block
+/// feeding it stops running until there is room again, and a block with several inputs only takes
+/// in more on the inputs that are behind, which keeps memory use bounded.
+///
+/// ```
+/// use superdsp::objects::flowgraph::Flowgraph;
+/// use superdsp::objects::object::{Samples, Type};
+/// use superdsp::objects::scheduler::Scheduler;
+/// use superdsp::objects::vector_sink::VectorSink;
+/// use superdsp::objects::vector_source::VectorSource;
+///
+/// let sink = VectorSink::new(Type::F64);
+///
+/// let mut flowgraph = Flowgraph::new();
+/// let src = flowgraph.add(VectorSource::new(Samples::F64(vec![1.0, 2.0, 3.0]), 2, false));
+/// let dst = flowgraph.add(sink.clone());
+/// flowgraph.connect(src.output(0), dst.input(0));
+///
+/// Scheduler::new(2, 4).run(&flowgraph).unwrap();
+/// assert_eq!(sink.samples(), Samples::F64(vec![1.0, 2.0, 3.0]));
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct Scheduler {
+    pub threads: usize,
+    pub capacity: usize,
+}
+
+impl Scheduler {
+    /// Create a new scheduler
+    ///
+    /// - threads: usize - number of threads the blocks are spread across
+    /// - capacity: usize - number of blocks each connection can hold before the block feeding it
+    ///   has to wait
+    pub fn new(threads: usize, capacity: usize) -> Scheduler {
+        assert!(threads > 0, "a scheduler needs at least one thread");
+        assert!(capacity > 0, "a connection needs room for at least one block");
+
+        Scheduler { threads, capacity }
+    }
+
+    /// Validate the graph and run it until every source is done and every block has drained its
+    /// inputs. Blocks are dealt out to the threads in the order they were added. Sources that never
+    /// finish make this run forever, unless every block downstream of them finishes first.
+    pub fn run(&self, flowgraph: &Flowgraph) -> Result<(), FlowgraphError> {
+        flowgraph.validate()?;
+
+        let mut tasks: Vec<Task> = flowgraph.nodes().iter().map(Task::new).collect();
+
+        for (from, to) in flowgraph.connections() {
+            let (producer, consumer) = ring_buffer(self.capacity);
+            let done = tasks[from.block.index()].done.clone();
+
+            tasks[from.block.index()].outputs[from.port].push(producer);
+            tasks[to.block.index()].inputs.push((to.port, consumer));
+            tasks[to.block.index()].upstream.push(done);
+        }
+
+        let mut partitions: Vec<Vec<Task>> = (0..self.threads).map(|_| Vec::new()).collect();
+        for (index, task) in tasks.into_iter().enumerate() {
+            partitions[index % self.threads].push(task);
+        }
+
+        let handles: Vec<_> = partitions.into_iter().filter(|tasks| !tasks.is_empty()).map(|mut tasks| {
+            thread::spawn(move || loop {
+                let mut progress = false;
+                let mut running = false;
+
+                for task in tasks.iter_mut() {
+                    progress |= task.step();
+                    running |= !task.is_done();
+                }
+
+                if !running {
+                    return;
+                }
+
+                if !progress {
+                    thread::yield_now();
+                }
+            })
+        }).collect();
+
+        for handle in handles {
+            if let Err(error) = handle.join() {
+                panic::resume_unwind(error);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A node together with the ring buffers connecting it to the rest of the graph
+struct Task {
+    node: NodeRef,
+
+    // (input port, ring buffer) for every incoming connection
+    inputs: Vec<(usize, Consumer<Samples>)>,
+    // Every ring buffer fed by each output port
+    outputs: Vec<Vec<Producer<Samples>>>,
+    blocks: Vec<Samples>,
+
+    // Whether the block feeding each entry of `inputs` has finished
+    upstream: Vec<Arc<AtomicBool>>,
+    done: Arc<AtomicBool>,
+}
+
+impl Task {
+    fn new(node: &NodeRef) -> Task {
+        let output_types = node.lock().object().output_types();
+
+        Task {
+            node: node.clone(),
+
+            inputs: Vec::new(),
+            outputs: output_types.iter().map(|_| Vec::new()).collect(),
+            blocks: output_types.iter().map(|t| Samples::new(*t)).collect(),
+
+            upstream: Vec::new(),
+            done: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.done.load(Ordering::Relaxed)
+    }
+
+    /// Run the node once if it has input and room for its output. Returns true if it ran.
+    fn step(&mut self) -> bool {
+        if self.is_done() {
+            return false;
+        }
+
+        // Blocks downstream that have finished no longer read what they are sent, and once none
+        // of them are left there is no point in running this one
+        let connected: usize = self.outputs.iter().map(Vec::len).sum();
+        for rings in self.outputs.iter_mut() {
+            rings.retain(|ring| !ring.is_abandoned());
+        }
+        if connected > 0 && self.outputs.iter().all(Vec::is_empty) {
+            self.inputs.clear();
+            self.done.store(true, Ordering::Release);
+            return false;
+        }
+
+        // Wait for the slowest consumer before taking in more samples
+        if self.outputs.iter().flatten().any(|ring| ring.free() == 0) {
+            return false;
+        }
+
+        let mut node = self.node.lock();
+
+        // Checked before draining the inputs, so everything pushed before an upstream block
+        // finished is still picked up below
+        let upstream_done: Vec<bool> = self.upstream.iter().map(|done| done.load(Ordering::Acquire)).collect();
+
+        // Only top up the inputs that hold the node back, so a fast input can not pile up
+        // samples while another one is starved
+        for (port, ring) in self.inputs.iter_mut() {
+            while node.available().is_some_and(|n| node.pending(*port) <= n) {
+                let Some(block) = ring.pop() else {
+                    break;
+                };
+                node.push_owned(*port, block);
+            }
+        }
+
+        // An input that is empty and will never get anything more stops the node for good
+        let finished = match node.available() {
+            None => node.is_done(),
+            Some(_) => self.inputs.iter().zip(upstream_done).any(|((port, ring), done)| done && ring.is_empty() && node.pending(*port) == 0),
+        };
+
+        if finished {
+            // Let go of the inputs so the blocks feeding them stop waiting for room
+            self.inputs.clear();
+            self.done.store(true, Ordering::Release);
+            return false;
+        }
+
+        if !node.work_into(&mut self.blocks) {
+            return false;
+        }
+
+        for (block, rings) in self.blocks.iter().zip(self.outputs.iter_mut()) {
+            for ring in rings.iter_mut() {
+                // Every ring had room when this step started and only this thread fills it
+                let _ = ring.push(block.clone());
+            }
+        }
+
+        true
+    }
+}
@@ -0,0 +1,49 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::math::constellation::Constellation;
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Turns symbols into a log likelihood ratio for every bit they carry, most significant first,
+/// positive for a one, for a soft decision decoder. Hard bits are the signs.
+///
+/// The input is one sample per symbol at unit average power, e.g. after the matched filter,
+/// [`crate::objects::symbol_sync::SymbolSync`] and a
+/// [`crate::objects::costas_loop::CostasLoop`] of the PSK chain. A QPSK Costas loop also locks
+/// to square QAM, though with the same ambiguity of a quarter turn.
+#[derive(Clone)]
+pub struct SoftDemapper {
+    pub constellation: Constellation,
+    /// The noise power per symbol the ratios are scaled by, E|n|²
+    pub noise_variance: f64,
+}
+
+impl SoftDemapper {
+    /// Create a new soft demapper
+    /// - constellation: Constellation - The points that were sent
+    /// - noise_variance: f64 - The noise power per symbol, E|n|², e.g. 10^(-SNR/10)
+    pub fn new(constellation: Constellation, noise_variance: f64) -> SoftDemapper {
+        SoftDemapper { constellation, noise_variance }
+    }
+}
+
+impl DSPObject for SoftDemapper {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::F64]
+    }
+
+    fn output_sample_rate(&self, input: Option<f64>) -> Option<f64> {
+        input.map(|rate| rate * self.constellation.bits_per_symbol() as f64)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let output = outputs[0].as_f64_mut();
+        for symbol in inputs[0].as_complex() {
+            self.constellation.llrs(*symbol, self.noise_variance, output);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::objects::object::{DSPObject, Samples, Type};
+use crate::objects::rational_resampler::RationalResampler;
+use crate::objects::ssb_modulator::{Analytic, Sideband, SsbMethod};
+
+/// Single sideband demodulator, undoing an [`crate::objects::ssb_modulator::SsbModulator`]. The
+/// input is brought down to the audio rate, and the chosen sideband is turned back into real
+/// audio with the same method the modulator can use, which rejects anything on the other side of
+/// the carrier. A carrier away from 0 Hz shifts the pitch of the audio.
+#[derive(Clone)]
+pub struct SsbDemodulator {
+    pub sideband: Sideband,
+    pub method: SsbMethod,
+    pub audio_rate: f64,
+    pub sample_rate: f64,
+
+    resampler: RationalResampler,
+    analytic: Analytic,
+}
+
+impl SsbDemodulator {
+    /// Create a new SSB demodulator
+    /// - sideband: Sideband - Which sideband to listen to
+    /// - method: SsbMethod - How to reject the other sideband
+    /// - audio_rate: f64 - The sample rate of the audio output (in Hz), a whole number
+    /// - sample_rate: f64 - The sample rate of the input (in Hz), a whole number
+    pub fn new(sideband: Sideband, method: SsbMethod, audio_rate: f64, sample_rate: f64) -> SsbDemodulator {
+        SsbDemodulator {
+            sideband,
+            method,
+            audio_rate,
+            sample_rate,
+            resampler: RationalResampler::between(Type::Complex, sample_rate, audio_rate),
+            analytic: Analytic::new(method, audio_rate),
+        }
+    }
+}
+
+impl DSPObject for SsbDemodulator {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::F64]
+    }
+
+    fn input_sample_rate(&self) -> Option<f64> {
+        Some(self.sample_rate)
+    }
+
+    fn output_sample_rate(&self, _input: Option<f64>) -> Option<f64> {
+        Some(self.audio_rate)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let mut baseband = [Samples::new(Type::Complex)];
+        self.resampler.work(inputs, &mut baseband);
+
+        let mut audio = baseband[0].as_complex().to_vec();
+        if self.sideband == Sideband::Lower {
+            audio.iter_mut().for_each(|z| *z = z.conj());
+        }
+
+        outputs[0].as_f64_mut().extend(self.analytic.real(&audio));
+    }
+}
@@ -0,0 +1,178 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+
+use num::Complex;
+
+use crate::math::filter::{hilbert, lowpass};
+use crate::math::nco::Nco;
+use crate::math::window::Window;
+use crate::objects::fir_filter::FirFilter;
+use crate::objects::object::{DSPObject, Samples, Type};
+use crate::objects::rational_resampler::RationalResampler;
+
+/// Length of the Hilbert transformer, which shifts audio from about a 50th of the audio rate up
+const HILBERT_TAPS: usize = 129;
+
+/// Which side of the carrier a single sideband signal is on
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Sideband {
+    Upper,
+    Lower,
+}
+
+/// How the unwanted sideband is removed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SsbMethod {
+    /// The phasing method: a Hilbert transformer makes the audio analytic
+    Hilbert,
+    /// Weaver's method: the audio band from `low` to `high` Hz is mixed down around 0 Hz,
+    /// low-pass filtered to half its width and mixed back up. `low` must be above 0 Hz.
+    Weaver { low: f64, high: f64 },
+}
+
+/// Positive frequency part of real audio at the audio rate, scaled to the amplitude of the audio,
+/// for either method
+#[derive(Clone)]
+pub(crate) enum Analytic {
+    Hilbert { delay: FirFilter, transformer: FirFilter },
+    Weaver { filter: FirFilter, down: Nco, up: Nco },
+}
+
+impl Analytic {
+    pub(crate) fn new(method: SsbMethod, audio_rate: f64) -> Analytic {
+        match method {
+            SsbMethod::Hilbert => {
+                let mut impulse = vec![0.0; HILBERT_TAPS];
+                impulse[HILBERT_TAPS / 2] = 1.0;
+
+                Analytic::Hilbert {
+                    delay: FirFilter::new(Type::F64, impulse),
+                    transformer: FirFilter::new(Type::F64, hilbert(HILBERT_TAPS, Window::Blackman).expect("invalid Hilbert transformer")),
+                }
+            }
+            SsbMethod::Weaver { low, high } => {
+                assert!(low > 0.0 && high > low && high < audio_rate / 2.0, "the Weaver band must lie between 0 Hz and half the audio rate");
+
+                // The other sideband lands at least 2 low beyond the edge of the passband
+                let (centre, half) = ((low + high) / 2.0, (high - low) / 2.0);
+                let width = (2.0 * low).min(audio_rate / 2.0 - half);
+                let taps = lowpass(audio_rate, half + width / 2.0, width, 60.0).expect("invalid Weaver filter");
+
+                // Mix back up in step with the delay of the filter so the audio keeps its phase
+                let mut up = Nco::new(centre, audio_rate);
+                up.set_phase(-2.0 * PI * centre * ((taps.len() - 1) / 2) as f64 / audio_rate);
+
+                Analytic::Weaver {
+                    filter: FirFilter::new(Type::Complex, taps),
+                    down: Nco::new(-centre, audio_rate),
+                    up,
+                }
+            }
+        }
+    }
+
+    /// Analytic signal for real audio, delayed by the filters
+    pub(crate) fn analytic(&mut self, audio: &[f64]) -> Vec<Complex<f64>> {
+        match self {
+            Analytic::Hilbert { delay, transformer } => {
+                let real = filter(delay, Samples::F64(audio.to_vec()));
+                let imaginary = filter(transformer, Samples::F64(audio.to_vec()));
+                real.as_f64().iter().zip(imaginary.as_f64().iter()).map(|(re, im)| Complex::new(*re, *im)).collect()
+            }
+            Analytic::Weaver { filter: lowpass, down, up } => {
+                // The positive frequencies carry half the amplitude of the real signal
+                let mixed = audio.iter().map(|a| down.next_sample() * (2.0 * a)).collect();
+                let filtered = filter(lowpass, Samples::Complex(mixed));
+                filtered.as_complex().iter().map(|z| z * up.next_sample()).collect()
+            }
+        }
+    }
+
+    /// Pick out the positive frequencies of complex audio and return them as real audio
+    pub(crate) fn real(&mut self, audio: &[Complex<f64>]) -> Vec<f64> {
+        match self {
+            Analytic::Hilbert { delay, transformer } => {
+                // For an analytic signal the transform of the imaginary part is minus the real
+                // part, and for its mirror image it is the real part, which cancels
+                let real = filter(delay, Samples::F64(audio.iter().map(|z| z.re).collect()));
+                let imaginary = filter(transformer, Samples::F64(audio.iter().map(|z| z.im).collect()));
+                real.as_f64().iter().zip(imaginary.as_f64().iter()).map(|(re, im)| (re - im) / 2.0).collect()
+            }
+            Analytic::Weaver { filter: lowpass, down, up } => {
+                let mixed = audio.iter().map(|z| z * down.next_sample()).collect();
+                let filtered = filter(lowpass, Samples::Complex(mixed));
+                filtered.as_complex().iter().map(|z| (z * up.next_sample()).re).collect()
+            }
+        }
+    }
+}
+
+/// Run a filter over one block
+fn filter(filter: &mut FirFilter, input: Samples) -> Samples {
+    let mut output = [Samples::new(filter.sample_type)];
+    filter.work(&[input], &mut output);
+
+    let [output] = output;
+    output
+}
+
+/// Single sideband modulation on the complex bus, with the suppressed carrier at 0 Hz. The upper
+/// sideband puts the audio at positive frequencies and the lower sideband mirrors it to negative
+/// ones. The sideband is made at the audio rate and then brought up to the sample rate, and a
+/// tone at full scale gives unit amplitude.
+#[derive(Clone)]
+pub struct SsbModulator {
+    pub sideband: Sideband,
+    pub method: SsbMethod,
+    pub audio_rate: f64,
+    pub sample_rate: f64,
+
+    analytic: Analytic,
+    resampler: RationalResampler,
+}
+
+impl SsbModulator {
+    /// Create a new SSB modulator
+    /// - sideband: Sideband - Which sideband to send
+    /// - method: SsbMethod - How to remove the other sideband
+    /// - audio_rate: f64 - The sample rate of the audio input (in Hz), a whole number
+    /// - sample_rate: f64 - The sample rate of the output (in Hz), a whole number
+    pub fn new(sideband: Sideband, method: SsbMethod, audio_rate: f64, sample_rate: f64) -> SsbModulator {
+        SsbModulator {
+            sideband,
+            method,
+            audio_rate,
+            sample_rate,
+            analytic: Analytic::new(method, audio_rate),
+            resampler: RationalResampler::between(Type::Complex, audio_rate, sample_rate),
+        }
+    }
+}
+
+impl DSPObject for SsbModulator {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::F64]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn input_sample_rate(&self) -> Option<f64> {
+        Some(self.audio_rate)
+    }
+
+    fn output_sample_rate(&self, _input: Option<f64>) -> Option<f64> {
+        Some(self.sample_rate)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let mut sideband = self.analytic.analytic(inputs[0].as_f64());
+        if self.sideband == Sideband::Lower {
+            sideband.iter_mut().for_each(|z| *z = z.conj());
+        }
+
+        self.resampler.work(&[Samples::Complex(sideband)], outputs);
+    }
+}
@@ -0,0 +1,201 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+
+use num::Complex;
+
+use crate::math::control_loop::ControlLoop;
+use crate::objects::fractional_resampler::farrow;
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Timing error detectors a [`SymbolSync`] can use
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimingDetector {
+    /// Compares the sample half way between two symbols with the difference between them. Needs no
+    /// decisions, so it works before the carrier is locked.
+    Gardner,
+    /// Mueller and Müller's decision directed detector, which only needs one sample per symbol. The
+    /// carrier has to be locked first.
+    MuellerMuller,
+    /// Gardner's detector with the symbols replaced by decisions, which is less noisy once the
+    /// carrier is locked
+    ZeroCrossing,
+}
+
+impl TimingDetector {
+    /// Whether the detector looks at the sample half way between symbols
+    fn needs_midpoint(self) -> bool {
+        !matches!(self, TimingDetector::MuellerMuller)
+    }
+
+    /// Power of the signal level the error grows with
+    fn degree(self) -> i32 {
+        match self {
+            TimingDetector::Gardner => 2,
+            TimingDetector::MuellerMuller | TimingDetector::ZeroCrossing => 1,
+        }
+    }
+
+    /// Timing error for a symbol given the previous symbol and the midpoint between them, positive
+    /// when the symbols are being sampled too early
+    fn error(self, previous: Complex<f64>, middle: Complex<f64>, current: Complex<f64>) -> f64 {
+        match self {
+            TimingDetector::Gardner => (middle.conj() * (previous - current)).re,
+            TimingDetector::ZeroCrossing => (middle.conj() * (decide(previous) - decide(current))).re,
+            TimingDetector::MuellerMuller => (decide(previous).conj() * current - decide(current).conj() * previous).re,
+        }
+    }
+}
+
+/// Hard decision on the sign of each component
+fn decide(x: Complex<f64>) -> Complex<f64> {
+    let sign = |x: f64| if x < 0.0 { -1.0 } else { 1.0 };
+    Complex::new(sign(x.re), sign(x.im))
+}
+
+/// Symbol timing recovery. A second order loop steers a cubic interpolator so it lands on the
+/// best sampling instant of every symbol, following a clock that is off by a little from the
+/// nominal number of samples per symbol. The input should already be matched filtered, e.g. with
+/// [`crate::math::filter::root_raised_cosine`].
+///
+/// Output 0 carries one sample per symbol and output 1 the timing error for every symbol, scaled
+/// to the signal level, which may be left unconnected.
+#[derive(Clone)]
+pub struct SymbolSync {
+    pub detector: TimingDetector,
+    pub samples_per_symbol: f64,
+    pub sample_rate: f64,
+    /// The largest fraction the symbol period may be off from `samples_per_symbol` by
+    pub max_deviation: f64,
+
+    control: ControlLoop,
+    history: Vec<Complex<f64>>,
+    // Position of the next interpolation point in `history`, in samples
+    position: f64,
+    // Correction to the symbol period, in samples
+    period_offset: f64,
+    // Length of the step to the next interpolation point
+    step: f64,
+    // Average power of the symbols, which the timing error is divided out of
+    power: f64,
+    at_midpoint: bool,
+    previous: Complex<f64>,
+    middle: Complex<f64>,
+}
+
+impl SymbolSync {
+    /// Create a new symbol synchronizer
+    /// - detector: TimingDetector - How to measure the timing error
+    /// - samples_per_symbol: f64 - The nominal number of samples per symbol, at least 2 for the
+    ///   detectors that need a midpoint
+    /// - bandwidth: f64 - The loop bandwidth (in Hz), small compared to the symbol rate
+    /// - damping: f64 - The damping factor, 1/√2 for a critically damped loop
+    /// - sample_rate: f64 - The sample rate of the input (in Hz)
+    pub fn new(detector: TimingDetector, samples_per_symbol: f64, bandwidth: f64, damping: f64, sample_rate: f64) -> SymbolSync {
+        assert!(samples_per_symbol >= if detector.needs_midpoint() { 2.0 } else { 1.0 }, "too few samples per symbol for the timing detector");
+
+        let symbol_rate = sample_rate / samples_per_symbol;
+        let step = if detector.needs_midpoint() { samples_per_symbol / 2.0 } else { samples_per_symbol };
+
+        SymbolSync {
+            detector,
+            samples_per_symbol,
+            sample_rate,
+            max_deviation: 0.05,
+            control: ControlLoop::new(2.0 * PI * bandwidth / symbol_rate, damping, PI),
+            // One sample before the first so the interpolator always has a sample on either side
+            history: vec![Complex::new(0.0, 0.0)],
+            position: 1.0,
+            period_offset: 0.0,
+            step,
+            power: 1.0,
+            at_midpoint: false,
+            previous: Complex::new(0.0, 0.0),
+            middle: Complex::new(0.0, 0.0),
+        }
+    }
+
+    /// Change the loop bandwidth (in Hz)
+    pub fn set_bandwidth(&mut self, bandwidth: f64) {
+        self.control.set_bandwidth(2.0 * PI * bandwidth / (self.sample_rate / self.samples_per_symbol));
+    }
+
+    /// The current estimate of the symbol period, in samples
+    pub fn period(&self) -> f64 {
+        self.samples_per_symbol + self.period_offset
+    }
+
+    /// Feed the timing error of a symbol into the loop and work out the step to the next
+    /// interpolation point
+    fn advance(&mut self, error: f64) {
+        let (alpha, beta) = self.control.gains();
+        let limit = self.max_deviation * self.samples_per_symbol;
+
+        // The gains are per radian of timing error, and one symbol period is a full turn
+        let scale = self.samples_per_symbol / (2.0 * PI);
+        self.period_offset = (self.period_offset + beta * error * scale).clamp(-limit, limit);
+        let period = (self.samples_per_symbol + self.period_offset + alpha * error * scale).clamp(self.samples_per_symbol - limit, self.samples_per_symbol + limit);
+
+        self.step = if self.detector.needs_midpoint() { period / 2.0 } else { period };
+    }
+}
+
+impl DSPObject for SymbolSync {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::Complex, Type::F64]
+    }
+
+    fn input_sample_rate(&self) -> Option<f64> {
+        Some(self.sample_rate)
+    }
+
+    fn output_sample_rate(&self, input: Option<f64>) -> Option<f64> {
+        input.map(|rate| rate / self.samples_per_symbol)
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        let [output, timing_error] = outputs else {
+            return;
+        };
+        let (output, timing_error) = (output.as_complex_mut(), timing_error.as_f64_mut());
+        self.history.extend_from_slice(inputs[0].as_complex());
+
+        loop {
+            let n = self.position as usize;
+            if n + 2 >= self.history.len() {
+                break;
+            }
+
+            let sample = farrow(&self.history[n - 1..=n + 2], self.position - n as f64);
+
+            if self.at_midpoint {
+                self.middle = sample;
+            } else {
+                // Take the signal level out of the error, so the loop behaves the same at any
+                // amplitude
+                self.power += (sample.norm_sqr() - self.power) / 32.0;
+                let level = libm::pow(self.power, self.detector.degree() as f64 / 2.0);
+                let error = self.detector.error(self.previous, self.middle, sample) / level.max(f64::MIN_POSITIVE);
+                self.advance(error);
+                self.previous = sample;
+
+                output.push(sample);
+                timing_error.push(error);
+            }
+
+            self.position += self.step;
+            if self.detector.needs_midpoint() {
+                self.at_midpoint = !self.at_midpoint;
+            }
+        }
+
+        // Keep the sample before the next interpolation point and everything after it
+        let used = (self.position as usize - 1).min(self.history.len());
+        self.history.drain(..used);
+        self.position -= used as f64;
+    }
+}
@@ -0,0 +1,41 @@
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use spin::Mutex;
+
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Records every sample it receives.
+#[derive(Clone)]
+pub struct VectorSink {
+    pub data: Arc<Mutex<Samples>>,
+}
+
+impl VectorSink {
+    pub fn new(input_type: Type) -> VectorSink {
+        VectorSink {
+            data: Arc::new(Mutex::new(Samples::new(input_type))),
+        }
+    }
+
+    /// Get a copy of everything that has been recorded so far, by this sink or any clone of it
+    pub fn samples(&self) -> Samples {
+        self.data.lock().clone()
+    }
+}
+
+impl DSPObject for VectorSink {
+    fn input_types(&self) -> Vec<Type> {
+        vec![self.data.lock().get_type()]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![]
+    }
+
+    fn work(&mut self, inputs: &[Samples], _outputs: &mut [Samples]) {
+        let input = &inputs[0];
+        self.data.lock().extend_from(input, input.len());
+    }
+}
@@ -0,0 +1,67 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::objects::object::{DSPObject, Samples, Type};
+
+/// Plays back a fixed set of samples in blocks of `block_size`. Mostly useful for feeding
+/// recorded data or test vectors through a flowgraph.
+#[derive(Clone)]
+pub struct VectorSource {
+    pub data: Samples,
+    pub block_size: usize,
+    pub repeat: bool,
+
+    position: usize,
+}
+
+impl VectorSource {
+    /// Create a new vector source
+    /// - data: Samples - The samples to play back. Its type is the type of the output port.
+    /// - block_size: usize - The number of samples to emit per block
+    /// - repeat: bool - Start over from the beginning once the end of `data` is reached instead of
+    ///   finishing
+    pub fn new(data: Samples, block_size: usize, repeat: bool) -> VectorSource {
+        VectorSource {
+            data,
+            block_size,
+            repeat,
+
+            position: 0,
+        }
+    }
+}
+
+impl DSPObject for VectorSource {
+    fn input_types(&self) -> Vec<Type> {
+        vec![]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![self.data.get_type()]
+    }
+
+    fn is_done(&self) -> bool {
+        !self.repeat && self.position >= self.data.len()
+    }
+
+    fn work(&mut self, _inputs: &[Samples], outputs: &mut [Samples]) {
+        let output = &mut outputs[0];
+
+        while output.len() < self.block_size && !self.data.is_empty() {
+            if self.position >= self.data.len() {
+                if !self.repeat {
+                    break;
+                }
+                self.position = 0;
+            }
+
+            let end = (self.position + self.block_size - output.len()).min(self.data.len());
+            match (&self.data, &mut *output) {
+                (Samples::F64(data), Samples::F64(output)) => output.extend_from_slice(&data[self.position..end]),
+                (Samples::Complex(data), Samples::Complex(output)) => output.extend_from_slice(&data[self.position..end]),
+                _ => panic!("VectorSource output does not match the type of its data"),
+            }
+            self.position = end;
+        }
+    }
+}
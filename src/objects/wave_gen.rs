@@ -1,61 +1,54 @@
 use core::f64::consts::PI;
+use alloc::vec;
+use alloc::vec::Vec;
 
-use crate::objects::object::{Bus, DSPObject};
+use crate::objects::object::{DSPObject, Samples, Type};
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct WaveStepGen{
     pub frequency: f64,
     pub amplitude: f64,
     pub phase: f64,
     pub sample_rate: f64,
-    
-    pub bus: Bus<'static>,
+    pub block_size: usize,
 
     pub time: f64,
 }
 
 impl WaveStepGen {
-    pub fn new(frequency: f64, amplitude: f64, phase: f64, sample_rate: f64) -> WaveStepGen {
-
-        let bus = Bus::new_f64();
-
+    pub fn new(frequency: f64, amplitude: f64, phase: f64, sample_rate: f64, block_size: usize) -> WaveStepGen {
         WaveStepGen {
             frequency,
             amplitude,
             phase,
             sample_rate,
+            block_size,
 
-            bus,
             time: 0.0,
         }
     }
 }
 
 impl DSPObject for WaveStepGen {
-    fn return_type(&self) -> crate::objects::object::Type {
-        crate::objects::object::Type::F64
-    }
-    fn input_type(&self) -> crate::objects::object::Type {
-        crate::objects::object::Type::NONE
+    fn input_types(&self) -> Vec<Type> {
+        vec![]
     }
 
-    fn get_bus(&mut self) -> &mut Bus<'static> {
-        &mut self.bus
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::F64]
     }
 
-    fn set_bus(&mut self, bus: &mut Bus<'static>) {
-        panic!("WaveStepGen does not listen on a bus");
+    fn output_sample_rate(&self, _input: Option<f64>) -> Option<f64> {
+        Some(self.sample_rate)
     }
 
-    fn process(&mut self) {
-        self.bus.trigger_f64(self.amplitude * (2.0 * PI * self.frequency * self.time + self.phase).sin());
-        self.time += 1.0 / self.sample_rate;
-    }
+    fn work(&mut self, _inputs: &[Samples], outputs: &mut [Samples]) {
+        let output = outputs[0].as_f64_mut();
+        output.resize(self.block_size, 0.0);
 
-    fn start(&mut self) {
-        loop {
-            self.process();
+        for sample in output.iter_mut() {
+            *sample = self.amplitude * libm::sin(2.0 * PI * self.frequency * self.time + self.phase);
+            self.time += 1.0 / self.sample_rate;
         }
     }
 }
-
@@ -1,30 +1,30 @@
 use core::f64::consts::PI;
+use alloc::vec;
+use alloc::vec::Vec;
 
 use num::Complex;
 
-use crate::objects::object::{Bus, DSPObject, Type};
+use crate::objects::object::{DSPObject, Samples, Type};
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct WaveStepGenComplex {
     pub frequency: f64,
     pub amplitude: f64,
     pub phase: f64,
     pub sample_rate: f64,
-
-    pub bus: Bus<'static>,
+    pub block_size: usize,
 
     pub time: f64,
 }
 
 impl WaveStepGenComplex {
-    pub fn new(frequency: f64, amplitude: f64, phase: f64, sample_rate: f64) -> WaveStepGenComplex {
+    pub fn new(frequency: f64, amplitude: f64, phase: f64, sample_rate: f64, block_size: usize) -> WaveStepGenComplex {
         WaveStepGenComplex {
             frequency,
             amplitude,
             phase,
             sample_rate,
-
-            bus: Bus::new_complex(),
+            block_size,
 
             time: 0.0,
         }
@@ -33,32 +33,26 @@ impl WaveStepGenComplex {
 
 impl DSPObject for WaveStepGenComplex {
 
-    fn return_type(&self) -> Type {
-        Type::Complex
+    fn input_types(&self) -> Vec<Type> {
+        vec![]
     }
 
-    fn input_type(&self) -> Type {
-        Type::NONE
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
     }
 
-    fn get_bus(&mut self) -> &mut Bus<'static> {
-        &mut self.bus
+    fn output_sample_rate(&self, _input: Option<f64>) -> Option<f64> {
+        Some(self.sample_rate)
     }
 
-    fn set_bus(&mut self, bus: &mut Bus<'static>) {
-        panic!("WaveStepGenComplex does not listen on a bus");
-    }
-    fn process(&mut self) {
-        let phi = 2.0 * PI * self.frequency * self.time + self.phase;
-        let value = Complex::new(self.amplitude * phi.sin(), self.amplitude * phi.cos());
-        self.bus.trigger_complex(value);
-        
-        self.time += 1.0 / self.sample_rate;
-    }
-    fn start(&mut self) {
-        loop {
-            self.process();
+    fn work(&mut self, _inputs: &[Samples], outputs: &mut [Samples]) {
+        let output = outputs[0].as_complex_mut();
+        output.resize(self.block_size, Complex::new(0.0, 0.0));
+
+        for sample in output.iter_mut() {
+            let phi = 2.0 * PI * self.frequency * self.time + self.phase;
+            *sample = Complex::new(self.amplitude * libm::sin(phi), self.amplitude * libm::cos(phi));
+            self.time += 1.0 / self.sample_rate;
         }
     }
 }
-
@@ -1,30 +1,29 @@
 use core::f64::consts::PI;
 use std::thread::sleep;
+use std::vec;
+use std::vec::Vec;
 
-use crate::objects::object::{Bus, DSPObject, Type};
+use crate::objects::object::{DSPObject, Samples, Type};
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct WaveStepGenTime {
     pub frequency: f64,
     pub amplitude: f64,
     pub phase: f64,
     pub sample_rate: f64,
-
-    bus: Bus<'static>,
+    pub block_size: usize,
 
     pub time: f64,
 }
 
 impl WaveStepGenTime {
-    pub fn new(frequency: f64, amplitude: f64, phase: f64, sample_rate: f64) -> WaveStepGenTime {
+    pub fn new(frequency: f64, amplitude: f64, phase: f64, sample_rate: f64, block_size: usize) -> WaveStepGenTime {
         WaveStepGenTime {
             frequency,
             amplitude,
             phase,
             sample_rate,
-
-            bus: Bus::new_f64(),
-
+            block_size,
 
             time: 0.0,
         }
@@ -32,32 +31,27 @@ impl WaveStepGenTime {
 }
 
 impl DSPObject for WaveStepGenTime {
-    fn return_type(&self) -> Type {
-        Type::F64
-    }
-    fn input_type(&self) -> Type {
-        Type::NONE
+    fn input_types(&self) -> Vec<Type> {
+        vec![]
     }
 
-    fn get_bus(&mut self) -> &mut Bus<'static> {
-        &mut self.bus
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::F64]
     }
 
-    fn set_bus(&mut self, bus: &mut Bus<'static>) {
-        panic!("WaveStepGenTime does not listen on a bus");
+    fn output_sample_rate(&self, _input: Option<f64>) -> Option<f64> {
+        Some(self.sample_rate)
     }
 
-    fn process(&mut self) {
-        self.bus.trigger_f64(self.amplitude * (2.0 * PI * self.frequency * self.time + self.phase).sin());
-        self.time += 1.0 / self.sample_rate;
+    fn work(&mut self, _inputs: &[Samples], outputs: &mut [Samples]) {
+        let output = outputs[0].as_f64_mut();
+        output.resize(self.block_size, 0.0);
 
-        sleep(std::time::Duration::from_secs_f64(1.0 / self.sample_rate));
-    }
-
-    fn start(&mut self) {
-        loop {
-            self.process();
+        for sample in output.iter_mut() {
+            *sample = self.amplitude * (2.0 * PI * self.frequency * self.time + self.phase).sin();
+            self.time += 1.0 / self.sample_rate;
         }
+
+        sleep(std::time::Duration::from_secs_f64(self.block_size as f64 / self.sample_rate));
     }
 }
-
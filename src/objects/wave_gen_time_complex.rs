@@ -1,31 +1,31 @@
 use core::f64::consts::PI;
 use std::thread::sleep;
+use std::vec;
+use std::vec::Vec;
 
 use num::Complex;
 
-use crate::objects::object::{Bus, DSPObject, Type};
+use crate::objects::object::{DSPObject, Samples, Type};
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct WaveStepGenTimeComplex {
     pub frequency: f64,
     pub amplitude: f64,
     pub phase: f64,
     pub sample_rate: f64,
-    
-    bus: Bus<'static>,
+    pub block_size: usize,
 
     pub time: f64,
 }
 
 impl WaveStepGenTimeComplex {
-    pub fn new(frequency: f64, amplitude: f64, phase: f64, sample_rate: f64) -> WaveStepGenTimeComplex {
+    pub fn new(frequency: f64, amplitude: f64, phase: f64, sample_rate: f64, block_size: usize) -> WaveStepGenTimeComplex {
         WaveStepGenTimeComplex {
             frequency,
             amplitude,
             phase,
             sample_rate,
-            
-            bus: Bus::new_complex(),
+            block_size,
 
             time: 0.0,
         }
@@ -33,31 +33,28 @@ impl WaveStepGenTimeComplex {
 }
 
 impl DSPObject for WaveStepGenTimeComplex {
-    fn return_type(&self) -> Type {
-        Type::Complex
+    fn input_types(&self) -> Vec<Type> {
+        vec![]
     }
-    fn input_type(&self) -> Type {
-        Type::NONE
-    }
-    fn get_bus(&mut self) -> &mut Bus<'static> {
-        &mut self.bus
-    }
-    fn set_bus(&mut self, bus: &mut Bus<'static>) {
-        panic!("WaveStepGenTimeComplex does not listen on a bus");
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
     }
 
-    fn process(&mut self) {
-        let phi = 2.0 * PI * self.frequency * self.time + self.phase;
-        let value = Complex::new(self.amplitude * phi.sin(), self.amplitude * phi.cos());
-        self.bus.trigger_complex(value);
-        
-        self.time += 1.0 / self.sample_rate;
-        sleep(std::time::Duration::from_secs_f64(1.0 / self.sample_rate));
+    fn output_sample_rate(&self, _input: Option<f64>) -> Option<f64> {
+        Some(self.sample_rate)
     }
-    fn start(&mut self) {
-        loop {
-            self.process();
+
+    fn work(&mut self, _inputs: &[Samples], outputs: &mut [Samples]) {
+        let output = outputs[0].as_complex_mut();
+        output.resize(self.block_size, Complex::new(0.0, 0.0));
+
+        for sample in output.iter_mut() {
+            let phi = 2.0 * PI * self.frequency * self.time + self.phase;
+            *sample = Complex::new(self.amplitude * phi.sin(), self.amplitude * phi.cos());
+            self.time += 1.0 / self.sample_rate;
         }
+
+        sleep(std::time::Duration::from_secs_f64(self.block_size as f64 / self.sample_rate));
     }
 }
-
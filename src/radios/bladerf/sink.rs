@@ -2,6 +2,7 @@ use std::{mem, println, vec};
 use std::collections::VecDeque;
 use std::ffi::c_uint;
 use std::os::raw::c_void;
+use std::prelude::rust_2021::Vec;
 use std::ptr::null_mut;
 use std::sync::Arc;
 
@@ -9,7 +10,7 @@ use bladerf::{bladerf_channel, bladerf_init_devinfo, bladerf_open_with_devinfo};
 use num::Complex;
 use spin::Mutex;
 
-use crate::objects::object::{Bus, DSPObject, Type};
+use crate::objects::object::{DSPObject, Samples, Type};
 
 #[derive(Clone)]
 pub struct BladeRfSink {
@@ -22,8 +23,6 @@ pub struct BladeRfSink {
     pub buffer: Arc<Mutex<VecDeque<Complex<f64>>>>,
     pub counter: usize,
 
-    pub bus: Bus<'static>,
-
     pub dev: Arc<Mutex<*mut bladerf::bladerf>>,
 }
 
@@ -49,8 +48,6 @@ impl BladeRfSink {
             
             buffer: Arc::new(Mutex::new(VecDeque::from(vec![Complex::new(0.0, 0.0); num_samples]))),
             counter: 0,
-
-            bus: Bus::new_complex(),
             
             dev: Arc::new(Mutex::new(null_mut())),
         };
@@ -109,32 +106,26 @@ unsafe impl Sync for BladeRfSink {}
 
 
 impl DSPObject for BladeRfSink {
-    fn return_type(&self) -> Type {
-        Type::NONE
-    }
-
-    fn input_type(&self) -> Type {
-        Type::Complex
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
     }
 
-    fn get_bus(&mut self) -> &mut Bus<'static> {
-        panic!("BladeRfSink does not listen on a bus");
+    fn output_types(&self) -> Vec<Type> {
+        vec![]
     }
 
-    fn set_bus(&mut self, bus: &mut Bus<'static>) {
-        self.bus = *bus;
-        bus.subscribe(self);
+    fn input_sample_rate(&self) -> Option<f64> {
+        Some(self.sample_rate as f64)
     }
 
-    fn process(&mut self) {
-        let mut i16_buffer = vec![(self.bus.buffer_complex.unwrap().read().re * 2048.0) as i16, (self.bus.buffer_complex.unwrap().read().im * 2048.0) as i16];
-        let status = unsafe { bladerf::bladerf_sync_tx(*self.dev.lock(), i16_buffer.as_mut_ptr() as *mut c_void, self.num_samples as c_uint, null_mut(), 10000) };
+    fn work(&mut self, inputs: &[Samples], _outputs: &mut [Samples]) {
+        // Interleave the block into Q11 I/Q pairs
+        let mut i16_buffer: Vec<i16> = inputs[0].as_complex().iter().flat_map(|x| [(x.re * 2048.0) as i16, (x.im * 2048.0) as i16]).collect();
+        let status = unsafe { bladerf::bladerf_sync_tx(*self.dev.lock(), i16_buffer.as_mut_ptr() as *mut c_void, (i16_buffer.len() / 2) as c_uint, null_mut(), 10000) };
         
         if status != 0 {
             println!("Error sending samples to BladeRF");
             self.reconnect();
         }
     }
-
-    fn start(&mut self) {}
-}
\ No newline at end of file
+}
@@ -9,7 +9,7 @@ use bladerf::{bladerf_channel, bladerf_init_devinfo, bladerf_open_with_devinfo,
 use num::Complex;
 use spin::Mutex;
 
-use crate::objects::object::{Bus, DSPObject, Type};
+use crate::objects::object::{DSPObject, Samples, Type};
 
 #[derive(Clone)]
 pub struct BladeRfSrc {
@@ -20,9 +20,6 @@ pub struct BladeRfSrc {
     pub num_samples: usize,
 
     pub sample_buffer: Vec<Complex<i16>>,
-    pub counter: usize,
-    
-    pub bus: Bus<'static>,
 
     pub dev: Arc<Mutex<*mut bladerf::bladerf>>,
 
@@ -48,9 +45,6 @@ impl BladeRfSrc {
             num_samples,
 
             sample_buffer: vec![Complex::new(0, 0); num_samples],
-            counter: 0,
-            
-            bus: Bus::new_complex(),
             dev: Arc::new(Mutex::new(null_mut())),
         };
         
@@ -111,39 +105,27 @@ unsafe impl Sync for BladeRfSrc {}
 
 
 impl DSPObject for BladeRfSrc {
-    fn return_type(&self) -> Type {
-        Type::Complex
+    fn input_types(&self) -> Vec<Type> {
+        vec![]
     }
 
-    fn input_type(&self) -> Type {
-        Type::NONE
+    fn output_types(&self) -> Vec<Type> {
+        vec![Type::Complex]
     }
 
-    fn get_bus(&mut self) -> &mut Bus<'static> {
-        &mut self.bus
+    fn output_sample_rate(&self, _input: Option<f64>) -> Option<f64> {
+        Some(self.sample_rate as f64)
     }
 
-    fn set_bus(&mut self, bus: &mut Bus<'static>) {
-        panic!("BladeRfSrc does not listen on a bus");
-    }
+    fn work(&mut self, _inputs: &[Samples], outputs: &mut [Samples]) {
+        let status = unsafe { bladerf_sync_rx(*self.dev.lock(), self.sample_buffer.as_mut_ptr() as *mut c_void, self.num_samples as c_uint, null_mut(), 1000) };
 
-    fn process(&mut self) {
-        if self.counter == 0{
-            let status = unsafe { bladerf_sync_rx(*self.dev.lock(), self.sample_buffer.as_mut_ptr() as *mut c_void, self.num_samples as c_uint, null_mut(), 1000) };
-            
-            if status != 0 {
-                println!("Error reading samples from BladeRF");
-                self.reconnect();
-            }
+        if status != 0 {
+            println!("Error reading samples from BladeRF");
+            self.reconnect();
         }
 
-        self.bus.trigger_complex(Complex::new(self.sample_buffer[self.counter].re as f64 / 2048.0, self.sample_buffer[self.counter].re as f64 / 2048.0));
-        self.counter = (self.counter + 1) % self.num_samples;
+        // Convert the whole Q11 block at once
+        outputs[0].as_complex_mut().extend(self.sample_buffer.iter().map(|x| Complex::new(x.re as f64 / 2048.0, x.im as f64 / 2048.0)));
     }
-
-    fn start(&mut self) {
-        loop {
-            self.process();
-        }
-    }
-}
\ No newline at end of file
+}
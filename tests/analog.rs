@@ -0,0 +1,201 @@
+mod common;
+
+use core::f64::consts::PI;
+
+use num::Complex;
+
+use common::tone;
+use superdsp::math::filter::{hilbert, FilterError};
+use superdsp::math::window::Window;
+use superdsp::objects::am_demodulator::AmDemodulator;
+use superdsp::objects::am_modulator::{AmMode, AmModulator};
+use superdsp::objects::flowgraph::Flowgraph;
+use superdsp::objects::fm_demodulator::FmDemodulator;
+use superdsp::objects::fm_modulator::FmModulator;
+use superdsp::objects::frequency_shift::FrequencyShift;
+use superdsp::objects::object::{DSPObject, Samples, Type};
+use superdsp::objects::ssb_demodulator::SsbDemodulator;
+use superdsp::objects::ssb_modulator::{Sideband, SsbMethod, SsbModulator};
+use superdsp::objects::vector_sink::VectorSink;
+use superdsp::objects::vector_source::VectorSource;
+
+const AUDIO_RATE: f64 = 8000.0;
+const SAMPLE_RATE: f64 = 48000.0;
+
+/// Run blocks one after the other over the input, in blocks of 500 samples
+fn chain(stages: &mut [&mut dyn DSPObject], input: Samples) -> Samples {
+    let mut output = Samples::new(stages.last().unwrap().output_types()[0]);
+
+    for start in (0..input.len()).step_by(500) {
+        let mut signal = match &input {
+            Samples::F64(x) => Samples::F64(x[start..(start + 500).min(x.len())].to_vec()),
+            Samples::Complex(x) => Samples::Complex(x[start..(start + 500).min(x.len())].to_vec()),
+            Samples::NONE => Samples::NONE,
+        };
+
+        for stage in stages.iter_mut() {
+            let mut outputs: Vec<Samples> = stage.output_types().iter().map(|t| Samples::new(*t)).collect();
+            stage.work(&[signal], &mut outputs);
+            signal = outputs.swap_remove(0);
+        }
+
+        output.extend_from(&signal, signal.len());
+    }
+
+    output
+}
+
+/// Tones making up the test audio, as frequency and amplitude
+const VOICE: [(f64, f64); 3] = [(440.0, 0.3), (1250.0, 0.3), (2300.0, 0.2)];
+
+/// Fit the tones to the settled second half of `received`, and return the gain of each from
+/// `tones` and the ratio of their power to everything else, in dB. The delay through the blocks
+/// does not matter.
+fn fit(received: &[f64], tones: &[(f64, f64)], rate: f64) -> (Vec<f64>, f64) {
+    let settled = &received[received.len() / 2..];
+    let n = settled.len() as f64;
+    let mut residual = settled.to_vec();
+    let mut gains = Vec::new();
+
+    for (frequency, amplitude) in tones {
+        let phasor = |i: usize| Complex::from_polar(1.0, 2.0 * PI * frequency * i as f64 / rate);
+        let estimate: Complex<f64> = settled.iter().enumerate().map(|(i, y)| phasor(i).conj() * (2.0 * y / n)).sum();
+        residual.iter_mut().enumerate().for_each(|(i, y)| *y -= (estimate * phasor(i)).re);
+        gains.push(estimate.norm() / amplitude);
+    }
+
+    let signal: f64 = gains.iter().zip(tones.iter()).map(|(gain, (_, amplitude))| (gain * amplitude).powi(2) / 2.0).sum();
+    let noise = residual.iter().map(|y| y * y).sum::<f64>() / n;
+    (gains, 10.0 * libm::log10(signal / noise))
+}
+
+/// Check every tone came through at `gain` and nothing much else did
+fn assert_clean(received: &[f64], tones: &[(f64, f64)], rate: f64, gain: f64) {
+    let (gains, snr) = fit(received, tones, rate);
+    assert!(gains.iter().all(|g| (g - gain).abs() < 0.05), "gains {:?}", gains);
+    assert!(snr > 30.0, "signal to noise and distortion ratio {} dB", snr);
+}
+
+/// Amplitude of the component at `frequency` (in Hz), which may be negative
+fn amplitude(signal: &[Complex<f64>], frequency: f64, rate: f64) -> f64 {
+    let settled = &signal[signal.len() / 2..];
+    let sum: Complex<f64> = settled.iter().enumerate().map(|(i, x)| x * Complex::from_polar(1.0, -2.0 * PI * frequency * i as f64 / rate)).sum();
+    sum.norm() / settled.len() as f64
+}
+
+/// Something like speech: three tones across the voice band
+fn voice() -> Vec<f64> {
+    let tones: Vec<Vec<f64>> = VOICE.iter().map(|(frequency, amplitude)| tone(*frequency, *amplitude, AUDIO_RATE, 16000)).collect();
+    (0..16000).map(|i| tones.iter().map(|tone| tone[i]).sum()).collect()
+}
+
+#[test]
+fn test_hilbert() {
+    let taps = hilbert(63, Window::Blackman).unwrap();
+    assert!(taps.iter().skip(1).step_by(2).all(|tap| *tap == 0.0));
+    assert!((0..63).all(|k| (taps[k] + taps[62 - k]).abs() < 1e-15));
+
+    // A quarter of the sample rate is turned by -90° with unit gain
+    let response: Complex<f64> = taps.iter().enumerate().map(|(k, tap)| Complex::from_polar(*tap, -PI / 2.0 * (k as f64 - 31.0))).sum();
+    assert!((response - Complex::new(0.0, -1.0)).norm() < 1e-3);
+
+    assert_eq!(hilbert(64, Window::Blackman), Err(FilterError::InvalidTaps(64)));
+}
+
+#[test]
+fn test_am_full_carrier() {
+    let sink = VectorSink::new(Type::F64);
+
+    // The envelope does not care where the carrier is
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(VectorSource::new(Samples::F64(voice()), 400, false));
+    let modulator = flowgraph.add(AmModulator::new(AmMode::FullCarrier, 0.8, AUDIO_RATE, SAMPLE_RATE));
+    let offset = flowgraph.add(FrequencyShift::new(150.0, SAMPLE_RATE));
+    let demodulator = flowgraph.add(AmDemodulator::new(AmMode::FullCarrier, 0.8, AUDIO_RATE, SAMPLE_RATE));
+    let dst = flowgraph.add(sink.clone());
+    flowgraph.connect(src.output(0), modulator.input(0));
+    flowgraph.connect(modulator.output(0), offset.input(0));
+    flowgraph.connect(offset.output(0), demodulator.input(0));
+    flowgraph.connect(demodulator.output(0), dst.input(0));
+
+    assert_eq!(flowgraph.sample_rate(demodulator), Some(AUDIO_RATE));
+    flowgraph.run().unwrap();
+
+    assert_clean(sink.samples().as_f64(), &VOICE, AUDIO_RATE, 1.0);
+}
+
+#[test]
+fn test_am_suppressed_carrier() {
+    let mut modulator = AmModulator::new(AmMode::SuppressedCarrier, 1.0, AUDIO_RATE, SAMPLE_RATE);
+    let mut offset = FrequencyShift::new(3.0, SAMPLE_RATE);
+    let mut demodulator = AmDemodulator::new(AmMode::SuppressedCarrier, 1.0, AUDIO_RATE, SAMPLE_RATE);
+
+    let modulated = chain(&mut [&mut modulator], Samples::F64(voice()));
+    assert!(amplitude(modulated.as_complex(), 0.0, SAMPLE_RATE) < 1e-3);
+
+    let received = chain(&mut [&mut offset, &mut demodulator], modulated);
+    assert!((demodulator.frequency() - 3.0).abs() < 0.5);
+
+    assert_clean(received.as_f64(), &VOICE, AUDIO_RATE, 1.0);
+}
+
+#[test]
+fn test_fm() {
+    // A full scale tone swings by the deviation
+    let mut modulator = FmModulator::new(3000.0, None, AUDIO_RATE, SAMPLE_RATE);
+    let modulated = chain(&mut [&mut modulator], Samples::F64(vec![1.0; 4000]));
+    let modulated = modulated.as_complex();
+    let frequency = (modulated[20000] * modulated[19999].conj()).arg() * SAMPLE_RATE / (2.0 * PI);
+    assert!((frequency - 3000.0).abs() < 1.0, "frequency {}", frequency);
+    assert!(modulated.iter().all(|x| (x.norm() - 1.0).abs() < 1e-6));
+    assert_eq!(modulator.modulation_index(1000.0), 3.0);
+
+    // Narrowband voice, with pre-emphasis undone by de-emphasis
+    let audio: Vec<f64> = voice().iter().map(|a| a * 0.25).collect();
+    let mut modulator = FmModulator::nbfm(AUDIO_RATE, SAMPLE_RATE);
+    let mut demodulator = FmDemodulator::nbfm(AUDIO_RATE, SAMPLE_RATE);
+    let received = chain(&mut [&mut modulator, &mut demodulator], Samples::F64(audio));
+    let quiet: Vec<(f64, f64)> = VOICE.iter().map(|(frequency, amplitude)| (*frequency, amplitude * 0.25)).collect();
+    assert_clean(received.as_f64(), &quiet, AUDIO_RATE, 1.0);
+
+    // Broadcast FM at a wider sample rate
+    let audio = tone(1000.0, 0.5, 48000.0, 48000);
+    let mut modulator = FmModulator::wbfm(75e-6, 48000.0, 480000.0);
+    let mut demodulator = FmDemodulator::wbfm(75e-6, 48000.0, 480000.0);
+    let received = chain(&mut [&mut modulator, &mut demodulator], Samples::F64(audio));
+    assert_clean(received.as_f64(), &[(1000.0, 0.5)], 48000.0, 1.0);
+}
+
+#[test]
+fn test_ssb() {
+    let methods = [SsbMethod::Hilbert, SsbMethod::Weaver { low: 300.0, high: 3000.0 }];
+
+    for method in methods {
+        for sideband in [Sideband::Upper, Sideband::Lower] {
+            let sign = if sideband == Sideband::Upper { 1.0 } else { -1.0 };
+
+            // A tone ends up on one side of the carrier only
+            let mut modulator = SsbModulator::new(sideband, method, AUDIO_RATE, SAMPLE_RATE);
+            let modulated = chain(&mut [&mut modulator], Samples::F64(tone(1000.0, 1.0, AUDIO_RATE, 8000)));
+            let wanted = amplitude(modulated.as_complex(), sign * 1000.0, SAMPLE_RATE);
+            let unwanted = amplitude(modulated.as_complex(), -sign * 1000.0, SAMPLE_RATE);
+            assert!((wanted - 1.0).abs() < 0.02, "{:?} {:?} amplitude {}", method, sideband, wanted);
+            assert!(20.0 * libm::log10(wanted / unwanted) > 40.0, "{:?} {:?} only suppresses the other sideband by {} dB", method, sideband, 20.0 * libm::log10(wanted / unwanted));
+
+            // And comes back out of the right demodulator only
+            let audio = voice();
+            let mut modulator = SsbModulator::new(sideband, method, AUDIO_RATE, SAMPLE_RATE);
+            let modulated = chain(&mut [&mut modulator], Samples::F64(audio.clone()));
+
+            let mut demodulator = SsbDemodulator::new(sideband, method, AUDIO_RATE, SAMPLE_RATE);
+            let received = chain(&mut [&mut demodulator], modulated.clone());
+            assert_clean(received.as_f64(), &VOICE, AUDIO_RATE, 1.0);
+
+            let other = if sideband == Sideband::Upper { Sideband::Lower } else { Sideband::Upper };
+            let mut demodulator = SsbDemodulator::new(other, method, AUDIO_RATE, SAMPLE_RATE);
+            let received = chain(&mut [&mut demodulator], modulated);
+            let power = |x: &[f64]| x[x.len() / 2..].iter().map(|a| a * a).sum::<f64>();
+            assert!(power(received.as_f64()) < 1e-3 * power(&audio), "{:?} {:?} leaks into the other sideband", method, sideband);
+        }
+    }
+}
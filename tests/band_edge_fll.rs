@@ -0,0 +1,75 @@
+mod common;
+
+use core::f64::consts::{FRAC_1_SQRT_2, PI};
+
+use num::Complex;
+
+use common::Noise;
+use superdsp::math::filter::{root_raised_cosine, FilterError};
+use superdsp::objects::band_edge_fll::BandEdgeFll;
+use superdsp::objects::object::{DSPObject, Samples, Type};
+
+const SAMPLE_RATE: f64 = 100000.0;
+const SAMPLES_PER_SYMBOL: usize = 4;
+
+/// Unit power QPSK shaped with a root raised cosine and moved up by `offset` Hz
+fn shaped_qpsk(offset: f64, n: usize, noise: &mut Noise) -> Vec<Complex<f64>> {
+    let taps = root_raised_cosine(SAMPLES_PER_SYMBOL, 0.35, 10).unwrap();
+    let gain = libm::sqrt(SAMPLES_PER_SYMBOL as f64);
+
+    let mut upsampled = vec![Complex::new(0.0, 0.0); n + taps.len()];
+    for i in (0..upsampled.len()).step_by(SAMPLES_PER_SYMBOL) {
+        let (re, im) = (noise.below(2) as f64 * 2.0 - 1.0, noise.below(2) as f64 * 2.0 - 1.0);
+        upsampled[i] = Complex::new(re, im) * FRAC_1_SQRT_2 * gain;
+    }
+
+    (0..n).map(|i| {
+        let shaped: Complex<f64> = taps.iter().enumerate().map(|(k, tap)| upsampled[i + taps.len() - 1 - k] * tap).sum();
+        shaped * Complex::from_polar(1.0, 2.0 * PI * offset * i as f64 / SAMPLE_RATE) + noise.complex(0.05)
+    }).collect()
+}
+
+fn estimate(offset: f64) -> f64 {
+    let mut noise = Noise::new(7);
+    let input = shaped_qpsk(offset, 60000, &mut noise);
+
+    let mut fll = BandEdgeFll::new(SAMPLES_PER_SYMBOL as f64, 0.35, 4 * SAMPLES_PER_SYMBOL + 1, 500.0, SAMPLE_RATE);
+    let mut outputs = [Samples::new(Type::Complex), Samples::new(Type::F64)];
+    for block in input.chunks(1000) {
+        let mut block_outputs = [Samples::new(Type::Complex), Samples::new(Type::F64)];
+        fll.work(&[Samples::Complex(block.to_vec())], &mut block_outputs);
+        for (output, block) in outputs.iter_mut().zip(block_outputs.iter()) {
+            output.extend_from(block, block.len());
+        }
+    }
+
+    let frequency = &outputs[1].as_f64()[30000..];
+    frequency.iter().sum::<f64>() / frequency.len() as f64
+}
+
+#[test]
+fn test_band_edge_fll_pulls_in_offset() {
+    for offset in [8000.0, -5000.0, 0.0] {
+        let estimate = estimate(offset);
+        assert!((estimate - offset).abs() < 250.0, "estimated {} Hz for an offset of {} Hz", estimate, offset);
+    }
+}
+
+#[test]
+fn test_root_raised_cosine() {
+    let taps = root_raised_cosine(8, 0.35, 6).unwrap();
+    assert_eq!(taps.len(), 49);
+    assert!((taps.iter().map(|tap| tap * tap).sum::<f64>() - 1.0).abs() < 1e-12);
+
+    // Matched filtering gives a raised cosine, which is zero at every other symbol instant
+    let full: Vec<f64> = (0..2 * taps.len() - 1).map(|n| {
+        (0..taps.len()).filter(|k| n >= *k && n - k < taps.len()).map(|k| taps[k] * taps[n - k]).sum()
+    }).collect();
+    let middle = taps.len() - 1;
+    assert!((full[middle] - 1.0).abs() < 1e-12);
+    for symbol in 1..4 {
+        assert!(full[middle + 8 * symbol].abs() < 0.01, "{} at symbol {}", full[middle + 8 * symbol], symbol);
+    }
+
+    assert_eq!(root_raised_cosine(8, 0.0, 6), Err(FilterError::InvalidRolloff(0.0)));
+}
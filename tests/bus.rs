@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use num::Complex;
+use superdsp::objects::object::{connect, new_node, Bus, DSPObject, Samples, Type};
+use superdsp::objects::wave_gen::WaveStepGen;
+use superdsp::objects::wave_gen_complex::WaveStepGenComplex;
+
+#[test]
+fn test_bus_no_global_limit() {
+    let buses: Vec<Bus> = (0..1000).map(|_| Bus::new_f64()).collect();
+    let complex_buses: Vec<Bus> = (0..1000).map(|_| Bus::new_complex()).collect();
+
+    assert_eq!(buses.len(), 1000);
+    assert_eq!(complex_buses.len(), 1000);
+}
+
+#[test]
+fn test_bus_independent_storage() {
+    let a = Bus::new_f64();
+    let b = Bus::new_f64();
+
+    a.trigger(Samples::F64(vec![1.0]));
+    b.trigger(Samples::F64(vec![2.0, 3.0]));
+
+    assert_eq!(a.buffer.as_ref().unwrap().read().as_f64(), &[1.0]);
+    assert_eq!(b.buffer.as_ref().unwrap().read().as_f64(), &[2.0, 3.0]);
+
+    let c = Bus::new_complex();
+    c.trigger(Samples::Complex(vec![Complex::new(3.0, 4.0)]));
+    assert_eq!(c.buffer.as_ref().unwrap().read().as_complex(), &[Complex::new(3.0, 4.0)]);
+}
+
+#[test]
+fn test_bus_freed_on_last_drop() {
+    let a = Bus::new_complex();
+    let b = a.clone();
+
+    let buffer = a.buffer.clone().unwrap();
+    assert_eq!(Arc::strong_count(&buffer), 3);
+
+    b.trigger(Samples::Complex(vec![Complex::new(1.0, -1.0)]));
+    assert_eq!(a.buffer.as_ref().unwrap().read().as_complex(), &[Complex::new(1.0, -1.0)]);
+
+    drop(a);
+    drop(b);
+    assert_eq!(Arc::strong_count(&buffer), 1);
+}
+
+#[test]
+fn test_bus_take_reuses_block() {
+    let bus = Bus::new_f64();
+    bus.trigger(Samples::F64(vec![0.0; 256]));
+
+    let block = bus.take();
+    assert_eq!(block.len(), 256);
+    assert_eq!(bus.buffer.as_ref().unwrap().read().get_type(), Type::F64);
+    assert!(bus.buffer.as_ref().unwrap().read().is_empty());
+}
+
+#[test]
+fn test_source_block_size() {
+    let gen = new_node(WaveStepGen::new(1.0, 1.0, 0.0, 4.0, 8));
+    gen.lock().process();
+
+    let block = gen.lock().output_bus(0).buffer.clone().unwrap();
+    let expected = [0.0, 1.0, 0.0, -1.0, 0.0, 1.0, 0.0, -1.0];
+    for (x, e) in block.read().as_f64().iter().zip(expected.iter()) {
+        assert!((x - e).abs() < 1e-9);
+    }
+    assert_eq!(block.read().len(), 8);
+
+    let mut gen = WaveStepGenComplex::new(1.0, 1.0, 0.0, 4.0, 1024);
+    let mut outputs = [Samples::new(Type::Complex)];
+    gen.work(&[], &mut outputs);
+    assert_eq!(outputs[0].len(), 1024);
+    assert!((outputs[0].as_complex()[1] - Complex::new(1.0, 0.0)).norm_sqr() < 1e-18);
+}
+
+#[derive(Clone)]
+struct CountingSink {
+    count: Arc<spin::Mutex<usize>>,
+}
+
+impl DSPObject for CountingSink {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::F64]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![]
+    }
+
+    fn work(&mut self, inputs: &[Samples], _outputs: &mut [Samples]) {
+        *self.count.lock() += inputs[0].len();
+    }
+}
+
+#[test]
+fn test_subscriber_runs() {
+    let count = Arc::new(spin::Mutex::new(0));
+    let gen = new_node(WaveStepGen::new(1.0, 1.0, 0.0, 4.0, 16));
+    let sink = new_node(CountingSink { count: count.clone() });
+
+    connect(&gen, 0, &sink, 0);
+    gen.lock().process();
+    gen.lock().process();
+
+    assert_eq!(*count.lock(), 32);
+}
+
+#[test]
+fn test_dropped_subscriber_is_rejected() {
+    let count = Arc::new(spin::Mutex::new(0));
+    let gen = new_node(WaveStepGen::new(1.0, 1.0, 0.0, 4.0, 16));
+    let sink = new_node(CountingSink { count: count.clone() });
+    let bus = gen.lock().output_bus(0).clone();
+
+    connect(&gen, 0, &sink, 0);
+    assert_eq!(bus.subscriber_count(), 1);
+
+    // Moving the node somewhere else does not invalidate the subscription
+    let moved = Box::new(sink);
+    gen.lock().process();
+    assert_eq!(*count.lock(), 16);
+
+    // Dropping it ends the subscription instead of leaving a dangling pointer
+    drop(moved);
+    gen.lock().process();
+    assert_eq!(*count.lock(), 16);
+    assert_eq!(bus.subscriber_count(), 0);
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use core::f64::consts::PI;
+
+use num::Complex;
+
+use common::{work_all, Noise};
+use superdsp::math::control_loop::wrap;
+use superdsp::objects::costas_loop::{CostasLoop, Psk};
+use superdsp::objects::object::Samples;
+use superdsp::objects::pll::Pll;
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+#[test]
+fn test_pll_tracks_noisy_carrier() {
+    let sample_rate = 48000.0;
+    let offset = 300.0;
+    let mut noise = Noise::new(1);
+
+    let input: Vec<Complex<f64>> = (0..20000).map(|n| {
+        Complex::from_polar(1.0, 2.0 * PI * offset * n as f64 / sample_rate + 1.0) + noise.complex(0.1)
+    }).collect();
+
+    let mut pll = Pll::new(100.0, 0.707, sample_rate);
+    let outputs = work_all(&mut pll, Samples::Complex(input));
+    let (output, frequency, phase) = (outputs[0].as_complex(), outputs[1].as_f64(), outputs[2].as_f64());
+
+    let settled = 10000..20000;
+    assert!((mean(&frequency[settled.clone()]) - offset).abs() < 5.0, "frequency {}", mean(&frequency[settled.clone()]));
+    assert!((pll.frequency() - offset).abs() < 30.0);
+
+    // The carrier ends up at zero phase, and the phase estimate follows the carrier
+    let angles: Vec<f64> = output[settled.clone()].iter().map(|y| y.arg()).collect();
+    assert!(mean(&angles).abs() < 0.02, "mean phase error {}", mean(&angles));
+    assert!(angles.iter().all(|angle| angle.abs() < 0.6));
+
+    let expected = wrap(2.0 * PI * offset * 19999.0 / sample_rate + 1.0);
+    assert!(wrap(phase[19999] - expected).abs() < 0.3);
+}
+
+/// Send random symbols with a frequency offset through a Costas loop and check that the recovered
+/// symbols decide to the ones sent, up to the phase ambiguity of the constellation
+fn check_costas(psk: Psk, points: usize, rotation: f64, sigma: f64) {
+    let sample_rate = 10000.0;
+    let offset = 40.0;
+    let mut noise = Noise::new(points as u64);
+
+    let symbols: Vec<usize> = (0..20000).map(|_| noise.below(points)).collect();
+    let input: Vec<Complex<f64>> = symbols.iter().enumerate().map(|(n, symbol)| {
+        let angle = rotation + 2.0 * PI * *symbol as f64 / points as f64 + 2.0 * PI * offset * n as f64 / sample_rate + 0.5;
+        Complex::from_polar(1.0, angle) + noise.complex(sigma)
+    }).collect();
+
+    let mut costas = CostasLoop::new(psk, 100.0, 0.707, sample_rate);
+    let outputs = work_all(&mut costas, Samples::Complex(input));
+    let (output, frequency) = (outputs[0].as_complex(), outputs[1].as_f64());
+
+    assert!((mean(&frequency[10000..]) - offset).abs() < 2.0, "{:?} frequency {}", psk, mean(&frequency[10000..]));
+
+    let decide = |y: &Complex<f64>| {
+        let sector = libm::round((y.arg() - rotation) / (2.0 * PI / points as f64));
+        (sector as i64).rem_euclid(points as i64) as usize
+    };
+    let ambiguity = (decide(&output[10000]) + points - symbols[10000]) % points;
+    let errors = output.iter().zip(symbols.iter()).skip(10000).filter(|(y, symbol)| decide(y) != (*symbol + ambiguity) % points).count();
+    assert_eq!(errors, 0, "{:?} made {} symbol errors", psk, errors);
+}
+
+#[test]
+fn test_costas_loop_bpsk() {
+    check_costas(Psk::Bpsk, 2, 0.0, 0.2);
+}
+
+#[test]
+fn test_costas_loop_qpsk() {
+    check_costas(Psk::Qpsk, 4, PI / 4.0, 0.1);
+}
+
+#[test]
+fn test_costas_loop_8psk() {
+    check_costas(Psk::Psk8, 8, PI / 8.0, 0.05);
+}
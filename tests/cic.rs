@@ -0,0 +1,92 @@
+mod common;
+
+use core::f64::consts::PI;
+
+use num::Complex;
+
+use common::work_blocks;
+use superdsp::math::cic::{bit_growth, compensator, response};
+use superdsp::math::filter::{frequency_response, FilterError};
+use superdsp::objects::cic_decimator::CicDecimator;
+use superdsp::objects::cic_interpolator::CicInterpolator;
+use superdsp::objects::object::{Samples, Type};
+
+#[test]
+fn test_cic_decimator_dc_gain() {
+    let mut cic = CicDecimator::new(Type::Complex, 16, 4, 1, 16);
+    let input = Samples::Complex(vec![Complex::new(0.5, -0.25); 1600]);
+    let output = work_blocks(&mut cic, input, 100);
+
+    let output = output.as_complex();
+    assert_eq!(output.len(), 100);
+    for y in output.iter().skip(4) {
+        assert_eq!(*y, Complex::new(0.5, -0.25));
+    }
+}
+
+#[test]
+fn test_cic_decimator_large_bit_growth() {
+    // 12 bit samples growing by 50 bits only just fit, and the integrators wrap many times
+    assert_eq!(bit_growth(5, 1, 1000), 50);
+
+    let mut cic = CicDecimator::new(Type::F64, 1000, 5, 1, 12);
+    let input: Vec<f64> = (0..20000).map(|i| if (i / 6000) % 2 == 0 { 1.0 } else { -1.0 }).collect();
+    let output = work_blocks(&mut cic, Samples::F64(input), 4096);
+
+    let output = output.as_f64();
+    assert_eq!(output.len(), 20);
+    assert!((output[11] + 1.0).abs() < 1e-3, "{}", output[11]);
+    assert!((output[17] - 1.0).abs() < 1e-3, "{}", output[17]);
+}
+
+#[test]
+fn test_cic_decimator_matches_response() {
+    let (rate, order, delay) = (8, 3, 2);
+    let frequency = 0.02;
+    let input: Vec<f64> = (0..8000).map(|i| libm::cos(2.0 * PI * frequency * i as f64)).collect();
+
+    let whole = work_blocks(&mut CicDecimator::new(Type::F64, rate, order, delay, 24), Samples::F64(input.clone()), 8000);
+    let split = work_blocks(&mut CicDecimator::new(Type::F64, rate, order, delay, 24), Samples::F64(input), 37);
+    assert_eq!(whole, split);
+
+    let peak = whole.as_f64().iter().skip(10).fold(0.0, |max: f64, y| max.max(y.abs()));
+    let expected = response(order, delay, rate, frequency);
+    assert!((peak - expected).abs() < 2e-3, "peak {} expected {}", peak, expected);
+}
+
+#[test]
+fn test_cic_interpolator_dc_gain() {
+    let mut cic = CicInterpolator::new(Type::F64, 10, 3, 1, 16);
+    let output = work_blocks(&mut cic, Samples::F64(vec![0.75; 50]), 7);
+
+    let output = output.as_f64();
+    assert_eq!(output.len(), 500);
+    for y in output.iter().skip(30) {
+        assert!((y - 0.75).abs() < 1e-12, "{}", y);
+    }
+}
+
+#[test]
+fn test_cic_compensator_flattens_passband() {
+    let (rate, order, delay) = (16, 4, 1);
+    let sample_rate = 100000.0;
+    let taps = compensator(sample_rate, 20000.0, 5000.0, 60.0, order, delay, rate).unwrap();
+
+    for i in 0..=100 {
+        let frequency = 17500.0 * i as f64 / 100.0;
+        let cic = response(order, delay, rate, frequency / sample_rate / rate as f64);
+        let gain = cic * frequency_response(&taps, frequency, sample_rate).norm();
+        assert!((20.0 * libm::log10(gain)).abs() < 0.1, "{} dB at {} Hz", 20.0 * libm::log10(gain), frequency);
+    }
+
+    // Without compensation the passband edge droops by almost 2 dB
+    assert!(20.0 * libm::log10(response(order, delay, rate, 17500.0 / sample_rate / rate as f64)) < -1.5);
+
+    for i in 0..=100 {
+        let frequency = 22500.0 + 27500.0 * i as f64 / 100.0;
+        let gain = frequency_response(&taps, frequency, sample_rate).norm();
+        assert!(20.0 * libm::log10(gain) < -55.0, "{} dB at {} Hz", 20.0 * libm::log10(gain), frequency);
+    }
+
+    assert_eq!(compensator(sample_rate, 20000.0, 5000.0, 60.0, 0, 1, 16), Err(FilterError::InvalidCic { order: 0, delay: 1, rate: 16 }));
+}
@@ -0,0 +1,111 @@
+//! Helpers shared by the tests that need test signals, a noisy channel or push blocks through
+//! objects by hand
+
+#![allow(dead_code)]
+
+use core::f64::consts::PI;
+
+use num::Complex;
+
+use superdsp::objects::object::{DSPObject, Samples};
+
+/// Deterministic Gaussian noise from a xorshift generator, so noisy tests give the same result
+/// every run
+pub struct Noise {
+    state: u64,
+}
+
+impl Noise {
+    pub fn new(seed: u64) -> Noise {
+        Noise { state: seed.max(1) }
+    }
+
+    /// Uniform in (0, 1]
+    pub fn uniform(&mut self) -> f64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+
+        ((self.state >> 11) + 1) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Normally distributed with the given standard deviation
+    pub fn gaussian(&mut self, sigma: f64) -> f64 {
+        let (u, v) = (self.uniform(), self.uniform());
+        sigma * libm::sqrt(-2.0 * libm::log(u)) * libm::cos(2.0 * PI * v)
+    }
+
+    /// Circular complex noise with the given standard deviation on each component
+    pub fn complex(&mut self, sigma: f64) -> Complex<f64> {
+        Complex::new(self.gaussian(sigma), self.gaussian(sigma))
+    }
+
+    /// A uniformly distributed integer below `n`
+    pub fn below(&mut self, n: usize) -> usize {
+        ((self.uniform() * n as f64) as usize).min(n - 1)
+    }
+}
+
+/// Bits as 0.0 or 1.0, each equally likely
+pub fn random_bits(noise: &mut Noise, n: usize) -> Vec<f64> {
+    (0..n).map(|_| noise.below(2) as f64).collect()
+}
+
+/// A sine wave starting at zero phase
+pub fn tone(frequency: f64, amplitude: f64, sample_rate: f64, n: usize) -> Vec<f64> {
+    (0..n).map(|i| amplitude * libm::sin(2.0 * PI * frequency * i as f64 / sample_rate)).collect()
+}
+
+/// A complex exponential starting at zero phase
+pub fn complex_tone(frequency: f64, amplitude: f64, sample_rate: f64, n: usize) -> Vec<Complex<f64>> {
+    (0..n).map(|i| Complex::from_polar(amplitude, 2.0 * PI * frequency * i as f64 / sample_rate)).collect()
+}
+
+/// Run one block through an object and return what comes out of every output
+pub fn work_all(object: &mut dyn DSPObject, input: Samples) -> Vec<Samples> {
+    let mut outputs: Vec<Samples> = object.output_types().iter().map(|t| Samples::new(*t)).collect();
+    object.work(&[input], &mut outputs);
+
+    outputs
+}
+
+/// Run one block through an object and return what comes out of its first output
+pub fn work(object: &mut dyn DSPObject, input: Samples) -> Samples {
+    work_all(object, input).swap_remove(0)
+}
+
+/// Run an input through an object in blocks of `block_size` and join up what comes out of its
+/// first output
+pub fn work_blocks(object: &mut dyn DSPObject, input: Samples, block_size: usize) -> Samples {
+    let mut output = Samples::new(object.output_types()[0]);
+    let mut start = 0;
+
+    while start < input.len() {
+        let n = block_size.min(input.len() - start);
+        let mut block = Samples::new(input.get_type());
+        block.extend_from(&input, start + n);
+        block.remove_front(start);
+
+        let result = work(object, block);
+        output.extend_from(&result, result.len());
+        start += n;
+    }
+
+    output
+}
+
+/// Hard decisions from log likelihood ratios or soft bits, positive for a one
+pub fn hard_decisions(soft: &[f64]) -> Vec<f64> {
+    soft.iter().map(|bit| (*bit > 0.0) as u8 as f64).collect()
+}
+
+/// Find the delay the receiver introduced and count the bits that differ after `skip`. A fast
+/// transmitter clock can leave the receiver with a few more bits than were sent.
+pub fn count_errors(sent: &[f64], received: &[f64], skip: usize) -> usize {
+    let received = &received[..received.len().min(sent.len())];
+    let delay = (0..received.len().min(400))
+        .find(|delay| (skip..skip + 64).all(|k| received[k] == sent[k - delay]))
+        .expect("no alignment found");
+
+    (skip..received.len()).filter(|k| received[*k] != sent[k - delay]).count()
+}
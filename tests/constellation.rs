@@ -0,0 +1,205 @@
+mod common;
+
+use core::f64::consts::{FRAC_1_SQRT_2, PI};
+
+use num::Complex;
+
+use common::{count_errors, hard_decisions, random_bits, work, Noise};
+use superdsp::math::constellation::{gray, gray_inverse, Constellation, ConstellationError};
+use superdsp::objects::constellation_mapper::ConstellationMapper;
+use superdsp::objects::costas_loop::{CostasLoop, Psk};
+use superdsp::objects::flowgraph::Flowgraph;
+use superdsp::objects::frequency_shift::FrequencyShift;
+use superdsp::objects::object::{DSPObject, Samples, Type};
+use superdsp::objects::psk_demodulator::matched_filter;
+use superdsp::objects::psk_modulator::{pulse_shaper, BitFormat};
+use superdsp::objects::soft_demapper::SoftDemapper;
+use superdsp::objects::symbol_sync::{SymbolSync, TimingDetector};
+use superdsp::objects::vector_sink::VectorSink;
+use superdsp::objects::vector_source::VectorSource;
+
+const SAMPLE_RATE: f64 = 48000.0;
+const SAMPLES_PER_SYMBOL: usize = 4;
+
+fn power(constellation: &Constellation) -> f64 {
+    constellation.points().iter().map(|point| point.norm_sqr()).sum::<f64>() / constellation.order() as f64
+}
+
+/// Whether every point differs from its nearest neighbours in a single bit
+fn is_gray(constellation: &Constellation) -> bool {
+    let points = constellation.points();
+    let distance = |a: usize, b: usize| (points[a] - points[b]).norm();
+    let closest = (0..points.len()).flat_map(|a| (0..a).map(move |b| (a, b))).map(|(a, b)| distance(a, b)).fold(f64::INFINITY, f64::min);
+
+    (0..points.len()).all(|a| (0..a).all(|b| distance(a, b) > closest + 1e-9 || (a ^ b).count_ones() == 1))
+}
+
+/// Whether every point differs from its own nearest neighbours in a single bit, for
+/// constellations whose points are not all the same distance apart
+fn is_gray_locally(constellation: &Constellation) -> bool {
+    let points = constellation.points();
+    let distance = |a: usize, b: usize| (points[a] - points[b]).norm();
+
+    (0..points.len()).all(|a| {
+        let closest = (0..points.len()).filter(|b| *b != a).map(|b| distance(a, b)).fold(f64::INFINITY, f64::min);
+        (0..points.len()).filter(|b| *b != a).all(|b| distance(a, b) > closest + 1e-9 || (a ^ b).count_ones() == 1)
+    })
+}
+
+#[test]
+fn test_gray_code() {
+    for value in 0..256 {
+        assert_eq!(gray_inverse(gray(value)), value);
+        assert_eq!((gray(value) ^ gray(value + 1)).count_ones(), 1);
+    }
+}
+
+#[test]
+fn test_qam() {
+    for order in [4, 16, 64, 256] {
+        let constellation = Constellation::qam(order).unwrap();
+        assert_eq!(constellation.order(), order);
+        assert!((power(&constellation) - 1.0).abs() < 1e-12);
+        assert!(is_gray(&constellation), "{}-QAM is not Gray mapped", order);
+    }
+
+    // 16-QAM has corners at 3/√10 and inner points at 1/√10
+    let qam16 = Constellation::qam(16).unwrap();
+    let corner = 3.0 / libm::sqrt(10.0);
+    assert!((qam16.point(0) - Complex::new(-corner, -corner)).norm() < 1e-12);
+
+    assert_eq!(Constellation::qam(32), Err(ConstellationError::InvalidOrder(32)));
+    assert_eq!(Constellation::qam(12), Err(ConstellationError::InvalidOrder(12)));
+}
+
+#[test]
+fn test_apsk_and_custom() {
+    let apsk = Constellation::apsk16(2.7).unwrap();
+    assert_eq!(apsk.order(), 16);
+    assert!((power(&apsk) - 1.0).abs() < 1e-12);
+    assert!((apsk.point(0).norm() / apsk.point(12).norm() - 2.7).abs() < 1e-12);
+
+    // DVB-S2 puts 0000 on the outer ring and 1100 on the inner ring at 45°, and 0100 at 15°
+    assert!((apsk.point(0).arg() - PI / 4.0).abs() < 1e-12);
+    assert!((apsk.point(12).arg() - PI / 4.0).abs() < 1e-12);
+    assert!((apsk.point(4).arg() - PI / 12.0).abs() < 1e-12);
+
+    for ratio in [2.57, 2.7, 3.15] {
+        assert!(is_gray_locally(&Constellation::apsk16(ratio).unwrap()), "16APSK with ratio {}", ratio);
+    }
+    for (middle, outer) in [(2.53, 4.3), (2.64, 4.64), (2.84, 5.27)] {
+        let apsk = Constellation::apsk32(middle, outer).unwrap();
+        assert_eq!(apsk.order(), 32);
+        assert!(is_gray_locally(&apsk), "32APSK with radii {} and {}", middle, outer);
+    }
+    assert_eq!(Constellation::apsk(&[(4, 1.0, 0.0), (0, 2.0, 0.0)]), Err(ConstellationError::InvalidRing { points: 0, radius: 2.0 }));
+
+    // The PSK constellations use the same mapping as the PSK modulator
+    let qpsk = Psk::Qpsk.constellation();
+    assert!(is_gray(&qpsk));
+    assert!((qpsk.point(3) - Psk::Qpsk.point(2)).norm() < 1e-12);
+
+    let points = vec![Complex::new(2.0, 0.0), Complex::new(-2.0, 0.0)];
+    let bpsk = Constellation::new(points.clone(), vec![1, 0]).unwrap();
+    assert_eq!(bpsk.point(1), Complex::new(1.0, 0.0));
+    assert_eq!(Constellation::new(points.clone(), vec![1, 1]), Err(ConstellationError::InvalidLabel(1)));
+    assert_eq!(Constellation::new(points.clone(), vec![0]), Err(ConstellationError::LabelCount { points: 2, labels: 1 }));
+    assert_eq!(Constellation::new(vec![Complex::new(0.0, 0.0); 2], vec![0, 1]), Err(ConstellationError::ZeroPower));
+}
+
+#[test]
+fn test_llrs() {
+    let qam16 = Constellation::qam(16).unwrap();
+
+    // On a point every ratio has the sign of its bit
+    for label in 0..16 {
+        let mut llrs = Vec::new();
+        qam16.llrs(qam16.point(label), 0.1, &mut llrs);
+        assert_eq!(qam16.nearest(qam16.point(label)), label);
+        for (k, llr) in llrs.iter().enumerate() {
+            assert_eq!(*llr > 0.0, (label >> (3 - k)) & 1 == 1);
+        }
+    }
+
+    // Half way between two points the bit they disagree on is a coin toss, and the ratios scale
+    // with one over the noise variance
+    let middle = (qam16.point(0) + qam16.point(1)) / 2.0;
+    let (mut llrs, mut noisier) = (Vec::new(), Vec::new());
+    qam16.llrs(middle, 0.1, &mut llrs);
+    qam16.llrs(middle, 0.2, &mut noisier);
+    assert!(llrs[3].abs() < 1e-12);
+    assert!(llrs.iter().zip(noisier.iter()).all(|(a, b)| (a - 2.0 * b).abs() < 1e-9));
+}
+
+fn timing() -> SymbolSync {
+    SymbolSync::new(TimingDetector::Gardner, SAMPLES_PER_SYMBOL as f64, 30.0, FRAC_1_SQRT_2, SAMPLE_RATE)
+}
+
+fn carrier() -> CostasLoop {
+    CostasLoop::new(Psk::Qpsk, 20.0, FRAC_1_SQRT_2, SAMPLE_RATE / SAMPLES_PER_SYMBOL as f64)
+}
+
+#[test]
+fn test_qam16_through_psk_chain() {
+    let mut noise = Noise::new(9);
+    let bits = random_bits(&mut noise, 40000);
+    let qam16 = Constellation::qam(16).unwrap();
+    let sink = VectorSink::new(Type::F64);
+
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(VectorSource::new(Samples::F64(bits.clone()), 400, false));
+    let mapper = flowgraph.add(ConstellationMapper::new(qam16.clone(), BitFormat::Bits));
+    let shaper = flowgraph.add(pulse_shaper(SAMPLES_PER_SYMBOL, 0.35));
+    let offset = flowgraph.add(FrequencyShift::new(10.0, SAMPLE_RATE));
+    let matched = flowgraph.add(matched_filter(SAMPLES_PER_SYMBOL, 0.35));
+    let sync = flowgraph.add(timing());
+    let costas = flowgraph.add(carrier());
+    let demapper = flowgraph.add(SoftDemapper::new(qam16, 0.01));
+    let dst = flowgraph.add(sink.clone());
+    flowgraph.connect(src.output(0), mapper.input(0));
+    flowgraph.connect(mapper.output(0), shaper.input(0));
+    flowgraph.connect(shaper.output(0), offset.input(0));
+    flowgraph.connect(offset.output(0), matched.input(0));
+    flowgraph.connect(matched.output(0), sync.input(0));
+    flowgraph.connect(sync.output(0), costas.input(0));
+    flowgraph.connect(costas.output(0), demapper.input(0));
+    flowgraph.connect(demapper.output(0), dst.input(0));
+    flowgraph.run().unwrap();
+
+    assert_eq!(count_errors(&bits, &hard_decisions(sink.samples().as_f64()), 8000), 0);
+}
+
+#[test]
+fn test_qam64_with_noise() {
+    let mut noise = Noise::new(10);
+    let bits = random_bits(&mut noise, 60000);
+    let qam64 = Constellation::qam(64).unwrap();
+
+    let (mut mapper, mut shaper, mut matched, mut sync, mut costas) = (ConstellationMapper::new(qam64.clone(), BitFormat::Bits), pulse_shaper(SAMPLES_PER_SYMBOL, 0.35), matched_filter(SAMPLES_PER_SYMBOL, 0.35), timing(), carrier());
+    let mut demapper = SoftDemapper::new(qam64.clone(), 0.001);
+    let rotation = Complex::from_polar(1.0, PI / 20.0);
+    let mut llrs = Vec::new();
+
+    for (n, block) in bits.chunks(600).enumerate() {
+        // Pull in with the QPSK detector, then track on decisions
+        if n == 10 {
+            costas.decisions = Some(qam64.clone());
+        }
+
+        let mut stages: [&mut dyn DSPObject; 2] = [&mut mapper, &mut shaper];
+        let mut signal = Samples::F64(block.to_vec());
+        for stage in stages.iter_mut() {
+            signal = work(*stage, signal);
+        }
+
+        let noisy = signal.as_complex().iter().map(|x| x * rotation + noise.complex(0.02)).collect();
+        let mut signal = Samples::Complex(noisy);
+        let mut stages: [&mut dyn DSPObject; 4] = [&mut matched, &mut sync, &mut costas, &mut demapper];
+        for stage in stages.iter_mut() {
+            signal = work(*stage, signal);
+        }
+        llrs.extend_from_slice(signal.as_f64());
+    }
+
+    assert_eq!(count_errors(&bits, &hard_decisions(&llrs), 12000), 0);
+}
@@ -0,0 +1,124 @@
+use num::Complex;
+
+use superdsp::math::filter::{bandpass, bandstop, highpass, lowpass, magnitude_db, FilterError};
+use superdsp::objects::fir_filter::FirFilter;
+use superdsp::objects::flowgraph::Flowgraph;
+use superdsp::objects::object::{DSPObject, Samples, Type};
+use superdsp::objects::vector_sink::VectorSink;
+use superdsp::objects::vector_source::VectorSource;
+
+const SAMPLE_RATE: f64 = 48000.0;
+
+/// Check the response of `taps` on a fine grid. Passbands must stay within the ripple a windowed
+/// design has for this attenuation and stopbands must be at least `attenuation` dB down.
+fn check_response(taps: &[f64], passbands: &[(f64, f64)], stopbands: &[(f64, f64)], attenuation: f64) {
+    let ripple = libm::pow(10.0, -attenuation / 20.0);
+
+    for i in 0..=2400 {
+        let frequency = SAMPLE_RATE / 2.0 * i as f64 / 2400.0;
+        let response = magnitude_db(taps, frequency, SAMPLE_RATE);
+
+        if passbands.iter().any(|(low, high)| frequency >= *low && frequency <= *high) {
+            assert!((libm::pow(10.0, response / 20.0) - 1.0).abs() <= ripple, "{} dB at {} Hz in the passband", response, frequency);
+        }
+
+        if stopbands.iter().any(|(low, high)| frequency >= *low && frequency <= *high) {
+            assert!(response <= -attenuation, "{} dB at {} Hz in the stopband", response, frequency);
+        }
+    }
+}
+
+#[test]
+fn test_lowpass_response() {
+    let taps = lowpass(SAMPLE_RATE, 6000.0, 1000.0, 60.0).unwrap();
+    check_response(&taps, &[(0.0, 5500.0)], &[(6500.0, 24000.0)], 60.0);
+
+    // -6 dB at the cutoff
+    assert!((magnitude_db(&taps, 6000.0, SAMPLE_RATE) + 6.02).abs() < 0.1);
+}
+
+#[test]
+fn test_highpass_response() {
+    let taps = highpass(SAMPLE_RATE, 3000.0, 800.0, 50.0).unwrap();
+    assert_eq!(taps.len() % 2, 1);
+    check_response(&taps, &[(3400.0, 24000.0)], &[(0.0, 2600.0)], 50.0);
+}
+
+#[test]
+fn test_bandpass_response() {
+    let taps = bandpass(SAMPLE_RATE, 4000.0, 8000.0, 1000.0, 70.0).unwrap();
+    check_response(&taps, &[(4500.0, 7500.0)], &[(0.0, 3500.0), (8500.0, 24000.0)], 70.0);
+}
+
+#[test]
+fn test_bandstop_response() {
+    let taps = bandstop(SAMPLE_RATE, 4000.0, 8000.0, 1500.0, 40.0).unwrap();
+    check_response(&taps, &[(0.0, 3250.0), (8750.0, 24000.0)], &[(4750.0, 7250.0)], 40.0);
+}
+
+#[test]
+fn test_filter_design_errors() {
+    assert_eq!(lowpass(0.0, 1.0, 1.0, 60.0), Err(FilterError::InvalidSampleRate(0.0)));
+    assert_eq!(lowpass(SAMPLE_RATE, 30000.0, 1.0, 60.0), Err(FilterError::InvalidFrequency { frequency: 30000.0, nyquist: 24000.0 }));
+    assert_eq!(highpass(SAMPLE_RATE, 1000.0, -1.0, 60.0), Err(FilterError::InvalidTransitionWidth(-1.0)));
+    assert_eq!(bandpass(SAMPLE_RATE, 1000.0, 500.0, 100.0, 60.0), Err(FilterError::InvalidBand { low: 1000.0, high: 500.0 }));
+    assert_eq!(bandstop(SAMPLE_RATE, 500.0, 1000.0, 100.0, 0.0), Err(FilterError::InvalidAttenuation(0.0)));
+
+    // The transition band has to fit between 0 and the nyquist frequency too
+    assert_eq!(lowpass(SAMPLE_RATE, 23000.0, 4000.0, 60.0), Err(FilterError::InvalidFrequency { frequency: 25000.0, nyquist: 24000.0 }));
+    assert_eq!(bandpass(SAMPLE_RATE, 500.0, 8000.0, 2000.0, 60.0), Err(FilterError::InvalidFrequency { frequency: -500.0, nyquist: 24000.0 }));
+    assert!(lowpass(SAMPLE_RATE, 23000.0, 2000.0, 60.0).is_ok());
+
+    // The transition bands around both edges can not overlap
+    assert_eq!(bandpass(1000.0, 100.0, 110.0, 50.0, 60.0), Err(FilterError::InvalidBand { low: 100.0, high: 110.0 }));
+    assert_eq!(bandstop(1000.0, 100.0, 110.0, 50.0, 60.0), Err(FilterError::InvalidBand { low: 100.0, high: 110.0 }));
+
+    // Beyond what double precision can reach
+    assert!(matches!(lowpass(SAMPLE_RATE, 6000.0, 2000.0, 400.0), Err(FilterError::DidNotConverge { .. })));
+}
+
+#[test]
+fn test_fir_filter_impulse_response() {
+    let taps = vec![0.5, 0.25, -1.0, 2.0];
+    let mut filter = FirFilter::new(Type::F64, taps.clone());
+    assert_eq!(filter.taps(), taps);
+
+    let mut impulse = vec![0.0; 8];
+    impulse[0] = 1.0;
+    let mut outputs = [Samples::new(Type::F64)];
+    filter.work(&[Samples::F64(impulse)], &mut outputs);
+    assert_eq!(outputs[0].as_f64(), &[0.5, 0.25, -1.0, 2.0, 0.0, 0.0, 0.0, 0.0]);
+
+    // Resetting forgets the history, so the tail of the impulse does not show up again
+    filter.work(&[Samples::F64(vec![1.0])], &mut outputs);
+    filter.reset();
+    let mut outputs = [Samples::new(Type::F64)];
+    filter.work(&[Samples::F64(vec![0.0; 3])], &mut outputs);
+    assert_eq!(outputs[0].as_f64(), &[0.0; 3]);
+}
+
+#[test]
+fn test_fir_filter_blocks_match_convolution() {
+    let taps = lowpass(SAMPLE_RATE, 2000.0, 2000.0, 40.0).unwrap();
+    let data: Vec<Complex<f64>> = (0..1000).map(|i| Complex::new(libm::sin(i as f64), libm::cos(0.1 * i as f64))).collect();
+
+    let expected: Vec<Complex<f64>> = (0..data.len()).map(|i| {
+        (0..taps.len()).filter(|k| *k <= i).map(|k| data[i - k] * taps[k]).sum()
+    }).collect();
+
+    // Odd block sizes so blocks are shorter than the filter and split at awkward places
+    let sink = VectorSink::new(Type::Complex);
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(VectorSource::new(Samples::Complex(data), 13, false));
+    let filter = flowgraph.add(FirFilter::new(Type::Complex, taps));
+    let dst = flowgraph.add(sink.clone());
+    flowgraph.connect(src.output(0), filter.input(0));
+    flowgraph.connect(filter.output(0), dst.input(0));
+    flowgraph.run().unwrap();
+
+    let output = sink.samples();
+    assert_eq!(output.len(), expected.len());
+    for (x, y) in output.as_complex().iter().zip(expected.iter()) {
+        assert!((x - y).norm_sqr() < 1e-24);
+    }
+}
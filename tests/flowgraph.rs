@@ -0,0 +1,261 @@
+use num::Complex;
+
+use superdsp::objects::add::Add;
+use superdsp::objects::complex_to_mag_phase::ComplexToMagPhase;
+use superdsp::objects::complex_to_real_imag::ComplexToRealImag;
+use superdsp::objects::deinterleave::Deinterleave;
+use superdsp::objects::flowgraph::{Flowgraph, FlowgraphError};
+use superdsp::objects::interleave::Interleave;
+use superdsp::objects::mag_phase_to_complex::MagPhaseToComplex;
+use superdsp::objects::multiply::Multiply;
+use superdsp::objects::multiply_conjugate::MultiplyConjugate;
+use superdsp::objects::object::{Samples, Type};
+use superdsp::objects::real_imag_to_complex::RealImagToComplex;
+use superdsp::objects::vector_sink::VectorSink;
+use superdsp::objects::vector_source::VectorSource;
+use superdsp::objects::wave_gen::WaveStepGen;
+use superdsp::objects::wave_gen_complex::WaveStepGenComplex;
+
+#[test]
+fn test_flowgraph_run() {
+    let data: Vec<f64> = (0..1000).map(|x| x as f64).collect();
+    let sink = VectorSink::new(Type::F64);
+    let other_sink = VectorSink::new(Type::F64);
+
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(VectorSource::new(Samples::F64(data.clone()), 64, false));
+    let a = flowgraph.add(sink.clone());
+    let b = flowgraph.add(other_sink.clone());
+
+    flowgraph.connect(src.output(0), a.input(0));
+    flowgraph.connect(src.output(0), b.input(0));
+    flowgraph.run().unwrap();
+
+    assert_eq!(sink.samples(), Samples::F64(data.clone()));
+    assert_eq!(other_sink.samples(), Samples::F64(data));
+}
+
+#[test]
+fn test_flowgraph_rebuild() {
+    let data: Vec<f64> = (0..1000).map(|x| x as f64).collect();
+    let sink = VectorSink::new(Type::F64);
+    let other_sink = VectorSink::new(Type::F64);
+
+    // Connections wired by the first build must not be wired again by the second
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(VectorSource::new(Samples::F64(data.clone()), 64, false));
+    let a = flowgraph.add(sink.clone());
+    flowgraph.connect(src.output(0), a.input(0));
+    flowgraph.build().unwrap();
+
+    let b = flowgraph.add(other_sink.clone());
+    flowgraph.connect(src.output(0), b.input(0));
+    flowgraph.run().unwrap();
+
+    assert_eq!(sink.samples().len(), data.len());
+    assert_eq!(sink.samples(), Samples::F64(data.clone()));
+    assert_eq!(other_sink.samples(), Samples::F64(data));
+}
+
+#[test]
+fn test_flowgraph_type_mismatch() {
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(WaveStepGen::new(1.0, 1.0, 0.0, 8.0, 16));
+    let sink = flowgraph.add(VectorSink::new(Type::Complex));
+    flowgraph.connect(src.output(0), sink.input(0));
+
+    let error = flowgraph.build().unwrap_err();
+    assert_eq!(
+        error,
+        FlowgraphError::TypeMismatch {
+            from_block: "WaveStepGen",
+            from: src.output(0),
+            output_type: Type::F64,
+            to_block: "VectorSink",
+            to: sink.input(0),
+            input_type: Type::Complex,
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "can not connect output 0 of WaveStepGen (block 0) which produces F64 to input 0 of VectorSink (block 1) which expects Complex"
+    );
+}
+
+#[test]
+fn test_flowgraph_bad_ports() {
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(WaveStepGenComplex::new(1.0, 1.0, 0.0, 8.0, 16));
+    let sink = flowgraph.add(VectorSink::new(Type::Complex));
+    flowgraph.connect(src.output(1), sink.input(0));
+    assert!(matches!(flowgraph.build(), Err(FlowgraphError::NoSuchOutput { outputs: 1, .. })));
+
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(WaveStepGenComplex::new(1.0, 1.0, 0.0, 8.0, 16));
+    let sink = flowgraph.add(VectorSink::new(Type::Complex));
+    flowgraph.connect(src.output(0), sink.input(2));
+    assert!(matches!(flowgraph.build(), Err(FlowgraphError::NoSuchInput { inputs: 1, .. })));
+
+    let mut flowgraph = Flowgraph::new();
+    flowgraph.add(WaveStepGenComplex::new(1.0, 1.0, 0.0, 8.0, 16));
+    let sink = flowgraph.add(VectorSink::new(Type::Complex));
+    assert_eq!(flowgraph.build(), Err(FlowgraphError::InputNotConnected { block: "VectorSink", port: sink.input(0) }));
+
+    let mut flowgraph = Flowgraph::new();
+    let a = flowgraph.add(WaveStepGenComplex::new(1.0, 1.0, 0.0, 8.0, 16));
+    let b = flowgraph.add(VectorSource::new(Samples::Complex(vec![Complex::new(0.0, 0.0)]), 1, false));
+    let sink = flowgraph.add(VectorSink::new(Type::Complex));
+    flowgraph.connect(a.output(0), sink.input(0));
+    flowgraph.connect(b.output(0), sink.input(0));
+    assert!(matches!(flowgraph.build(), Err(FlowgraphError::InputAlreadyConnected { .. })));
+}
+
+#[test]
+fn test_flowgraph_multiple_inputs_are_aligned() {
+    let a: Vec<f64> = (0..100).map(|x| x as f64).collect();
+    let b: Vec<f64> = (0..100).map(|x| 2.0 * x as f64).collect();
+    let sum = VectorSink::new(Type::F64);
+    let product = VectorSink::new(Type::F64);
+
+    // Different block sizes on each input still line up sample by sample
+    let mut flowgraph = Flowgraph::new();
+    let src_a = flowgraph.add(VectorSource::new(Samples::F64(a.clone()), 7, false));
+    let src_b = flowgraph.add(VectorSource::new(Samples::F64(b.clone()), 5, false));
+    let add = flowgraph.add(Add::new(Type::F64, 2));
+    let multiply = flowgraph.add(Multiply::new(Type::F64, 2));
+    let sum_block = flowgraph.add(sum.clone());
+    let product_block = flowgraph.add(product.clone());
+
+    flowgraph.connect(src_a.output(0), add.input(0));
+    flowgraph.connect(src_b.output(0), add.input(1));
+    flowgraph.connect(src_a.output(0), multiply.input(0));
+    flowgraph.connect(src_b.output(0), multiply.input(1));
+    flowgraph.connect(add.output(0), sum_block.input(0));
+    flowgraph.connect(multiply.output(0), product_block.input(0));
+    flowgraph.run().unwrap();
+
+    let expected: Vec<f64> = a.iter().zip(b.iter()).map(|(a, b)| a + b).collect();
+    assert_eq!(sum.samples(), Samples::F64(expected));
+
+    let expected: Vec<f64> = a.iter().zip(b.iter()).map(|(a, b)| a * b).collect();
+    assert_eq!(product.samples(), Samples::F64(expected));
+}
+
+#[test]
+fn test_flowgraph_interleave_round_trip() {
+    let data: Vec<Complex<f64>> = (0..99).map(|x| Complex::new(x as f64, -(x as f64))).collect();
+    let sink = VectorSink::new(Type::Complex);
+
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(VectorSource::new(Samples::Complex(data.clone()), 10, false));
+    let deinterleave = flowgraph.add(Deinterleave::new(Type::Complex, 3));
+    let interleave = flowgraph.add(Interleave::new(Type::Complex, 3));
+    let dst = flowgraph.add(sink.clone());
+
+    flowgraph.connect(src.output(0), deinterleave.input(0));
+    for port in 0..3 {
+        flowgraph.connect(deinterleave.output(port), interleave.input(port));
+    }
+    flowgraph.connect(interleave.output(0), dst.input(0));
+    flowgraph.run().unwrap();
+
+    assert_eq!(sink.samples(), Samples::Complex(data));
+}
+
+#[test]
+fn test_flowgraph_complex_conversions() {
+    let data: Vec<Complex<f64>> = (1..50).map(|x| Complex::new(libm::cos(x as f64), 2.0 * libm::sin(x as f64))).collect();
+    let real_imag = VectorSink::new(Type::Complex);
+    let mag_phase = VectorSink::new(Type::Complex);
+    let conjugate = VectorSink::new(Type::Complex);
+
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(VectorSource::new(Samples::Complex(data.clone()), 16, false));
+    let to_real_imag = flowgraph.add(ComplexToRealImag::new());
+    let from_real_imag = flowgraph.add(RealImagToComplex::new());
+    let to_mag_phase = flowgraph.add(ComplexToMagPhase::new());
+    let from_mag_phase = flowgraph.add(MagPhaseToComplex::new());
+    let multiply_conjugate = flowgraph.add(MultiplyConjugate::new());
+    let real_imag_block = flowgraph.add(real_imag.clone());
+    let mag_phase_block = flowgraph.add(mag_phase.clone());
+    let conjugate_block = flowgraph.add(conjugate.clone());
+
+    flowgraph.connect(src.output(0), to_real_imag.input(0));
+    flowgraph.connect(to_real_imag.output(0), from_real_imag.input(0));
+    flowgraph.connect(to_real_imag.output(1), from_real_imag.input(1));
+    flowgraph.connect(from_real_imag.output(0), real_imag_block.input(0));
+
+    flowgraph.connect(src.output(0), to_mag_phase.input(0));
+    flowgraph.connect(to_mag_phase.output(0), from_mag_phase.input(0));
+    flowgraph.connect(to_mag_phase.output(1), from_mag_phase.input(1));
+    flowgraph.connect(from_mag_phase.output(0), mag_phase_block.input(0));
+
+    flowgraph.connect(src.output(0), multiply_conjugate.input(0));
+    flowgraph.connect(src.output(0), multiply_conjugate.input(1));
+    flowgraph.connect(multiply_conjugate.output(0), conjugate_block.input(0));
+    flowgraph.run().unwrap();
+
+    assert_eq!(real_imag.samples(), Samples::Complex(data.clone()));
+
+    for (x, y) in mag_phase.samples().as_complex().iter().zip(data.iter()) {
+        assert!((x - y).norm_sqr() < 1e-20);
+    }
+
+    for (x, y) in conjugate.samples().as_complex().iter().zip(data.iter()) {
+        assert!((x.re - y.norm_sqr()).abs() < 1e-12);
+        assert!(x.im.abs() < 1e-12);
+    }
+}
+
+#[test]
+fn test_flowgraph_rejects_cycles() {
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(WaveStepGen::new(1.0, 1.0, 0.0, 8.0, 16));
+    let add = flowgraph.add(Add::new(Type::F64, 2));
+    flowgraph.connect(src.output(0), add.input(0));
+    flowgraph.connect(add.output(0), add.input(1));
+
+    assert_eq!(flowgraph.build(), Err(FlowgraphError::Cycle { block: "Add", id: add }));
+}
+
+#[test]
+fn test_flowgraph_sample_rates() {
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(WaveStepGenComplex::new(1.0, 1.0, 0.0, 48000.0, 16));
+    let split = flowgraph.add(Deinterleave::new(Type::Complex, 2));
+    let join = flowgraph.add(Interleave::new(Type::Complex, 2));
+    let sink = flowgraph.add(VectorSink::new(Type::Complex));
+    flowgraph.connect(src.output(0), split.input(0));
+    flowgraph.connect(split.output(0), join.input(0));
+    flowgraph.connect(split.output(1), join.input(1));
+    flowgraph.connect(join.output(0), sink.input(0));
+
+    assert_eq!(flowgraph.sample_rate(src), Some(48000.0));
+    assert_eq!(flowgraph.sample_rate(split), Some(24000.0));
+    assert_eq!(flowgraph.sample_rate(join), Some(48000.0));
+
+    // Nothing is known about the rate of a vector source
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(VectorSource::new(Samples::F64(vec![1.0]), 1, false));
+    let sink = flowgraph.add(VectorSink::new(Type::F64));
+    flowgraph.connect(src.output(0), sink.input(0));
+    assert_eq!(flowgraph.sample_rate(src), None);
+    assert_eq!(flowgraph.build(), Ok(()));
+}
+
+#[test]
+fn test_flowgraph_sample_rate_mismatch() {
+    let mut flowgraph = Flowgraph::new();
+    let a = flowgraph.add(WaveStepGen::new(1.0, 1.0, 0.0, 48000.0, 16));
+    let b = flowgraph.add(WaveStepGen::new(1.0, 1.0, 0.0, 44100.0, 16));
+    let add = flowgraph.add(Add::new(Type::F64, 2));
+    let sink = flowgraph.add(VectorSink::new(Type::F64));
+    flowgraph.connect(a.output(0), add.input(0));
+    flowgraph.connect(b.output(0), add.input(1));
+    flowgraph.connect(add.output(0), sink.input(0));
+
+    let error = flowgraph.build().unwrap_err();
+    assert_eq!(error, FlowgraphError::SampleRateMismatch { block: "Add", port: add.input(1), expected: 48000.0, found: 44100.0 });
+    assert_eq!(error.to_string(), "input 1 of Add (block 2) receives 44100 samples/s but expects 48000 samples/s");
+    assert_eq!(flowgraph.sample_rate(add), None);
+}
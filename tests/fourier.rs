@@ -1,7 +1,7 @@
 use core::f64::consts::PI;
 use num::Complex;
 
-use superdsp::math::fourier::{fft_shift, fft_shift_inverse, make_basis, make_inverse_basis};
+use superdsp::math::fourier::{fft, fft_shift, fft_shift_inverse, fftshift, ifft, ifftshift, make_basis, make_inverse_basis, FftPlan, RealFftPlan};
 
 #[test]
 fn test_basis(){
@@ -71,3 +71,101 @@ fn test_fft_shift(){
     let out = fft_shift_inverse(5).dot(&out);
     assert_eq!(out.as_slice().unwrap(), &[Complex::new(1.0,0.0),Complex::new(2.0,0.0),Complex::new(3.0,0.0),Complex::new(4.0,0.0),Complex::new(5.0,0.0)]);
 }
+
+
+fn test_signal(n: usize) -> Vec<Complex<f64>> {
+    (0..n).map(|i| Complex::new(libm::sin(0.37 * i as f64) + 0.1 * i as f64, libm::cos(1.3 * i as f64))).collect()
+}
+
+#[test]
+fn test_fft_matches_basis() {
+    // Powers of two use radix-2, everything else goes through Bluestein
+    for n in (1..=40).chain([64, 100, 127, 128]) {
+        let wave = test_signal(n);
+        let expected = make_basis(n).dot(&ndarray::Array1::from(wave.clone()));
+        let expected_inverse = make_inverse_basis(n).dot(&ndarray::Array1::from(wave.clone()));
+
+        let plan = FftPlan::new(n);
+        assert_eq!(plan.len(), n);
+
+        let mut forward = wave.clone();
+        plan.fft(&mut forward);
+        let mut inverse = wave.clone();
+        plan.ifft(&mut inverse);
+
+        for i in 0..n {
+            assert!((forward[i] - expected[i]).norm_sqr() < 1e-18, "forward n = {} bin {}", n, i);
+            assert!((inverse[i] - expected_inverse[i]).norm_sqr() < 1e-18, "inverse n = {} bin {}", n, i);
+        }
+    }
+}
+
+#[test]
+fn test_fft_round_trip() {
+    for n in [1000, 1024, 4099] {
+        let wave = test_signal(n);
+        let mut data = wave.clone();
+
+        fft(&mut data);
+
+        // Scaled by 1/sqrt(n), so energy is preserved
+        let energy: f64 = wave.iter().map(|x| x.norm_sqr()).sum();
+        let spectrum_energy: f64 = data.iter().map(|x| x.norm_sqr()).sum();
+        assert!((energy - spectrum_energy).abs() < 1e-9 * energy);
+
+        ifft(&mut data);
+        for (x, y) in data.iter().zip(wave.iter()) {
+            assert!((x - y).norm_sqr() < 1e-18);
+        }
+    }
+}
+
+#[test]
+fn test_fft_tone() {
+    let n = 1000;
+    let mut data: Vec<Complex<f64>> = (0..n).map(|i| {
+        let theta = 2.0 * PI * 37.0 * i as f64 / n as f64;
+        Complex::new(libm::cos(theta), libm::sin(theta))
+    }).collect();
+
+    FftPlan::new(n).fft(&mut data);
+
+    for (bin, x) in data.iter().enumerate() {
+        let expected = if bin == 37 { libm::sqrt(n as f64) } else { 0.0 };
+        assert!((x.re - expected).abs() < 1e-9 && x.im.abs() < 1e-9, "bin {}", bin);
+    }
+}
+
+#[test]
+fn test_fftshift_in_place() {
+    let mut even = [0, 1, 2, 3, -4, -3, -2, -1];
+    fftshift(&mut even);
+    assert_eq!(even, [-4, -3, -2, -1, 0, 1, 2, 3]);
+    ifftshift(&mut even);
+    assert_eq!(even, [0, 1, 2, 3, -4, -3, -2, -1]);
+
+    let mut odd = [0, 1, 2, -2, -1];
+    fftshift(&mut odd);
+    assert_eq!(odd, [-2, -1, 0, 1, 2]);
+    ifftshift(&mut odd);
+    assert_eq!(odd, [0, 1, 2, -2, -1]);
+}
+
+#[test]
+fn test_real_fft_matches_complex() {
+    for n in (1..=33).chain([256, 1000, 1001]) {
+        let wave: Vec<f64> = (0..n).map(|i| libm::sin(0.37 * i as f64) + 0.01 * (i * i) as f64).collect();
+
+        let mut expected: Vec<Complex<f64>> = wave.iter().map(|x| Complex::new(*x, 0.0)).collect();
+        fft(&mut expected);
+
+        let plan = RealFftPlan::new(n);
+        let mut half = vec![Complex::new(0.0, 0.0); plan.output_len()];
+        plan.fft(&wave, &mut half);
+
+        assert_eq!(half.len(), n / 2 + 1);
+        for (bin, x) in half.iter().enumerate() {
+            assert!((x - expected[bin]).norm_sqr() < 1e-18 * n as f64, "n = {} bin {}", n, bin);
+        }
+    }
+}
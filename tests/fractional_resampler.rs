@@ -0,0 +1,100 @@
+use core::f64::consts::PI;
+
+use num::Complex;
+
+use superdsp::objects::fractional_resampler::{FractionalResampler, Interpolation};
+use superdsp::objects::object::{DSPObject, Samples, Type};
+use superdsp::objects::wave_gen_complex::WaveStepGenComplex;
+
+const SAMPLE_RATE: f64 = 48000.0;
+const FREQUENCY: f64 = 1000.0;
+
+/// Feed `blocks` blocks of a tone through `resampler` and return everything it produced
+fn resample_tone(resampler: &mut FractionalResampler, blocks: usize) -> Vec<Complex<f64>> {
+    let mut source = WaveStepGenComplex::new(FREQUENCY, 1.0, 0.0, SAMPLE_RATE, 256);
+    let mut output = Vec::new();
+
+    for _ in 0..blocks {
+        let mut block = [Samples::new(Type::Complex)];
+        source.work(&[], &mut block);
+
+        let mut resampled = [Samples::new(Type::Complex)];
+        resampler.work(&block, &mut resampled);
+        output.extend_from_slice(resampled[0].as_complex());
+    }
+
+    output
+}
+
+/// The tone `WaveStepGenComplex` produces, at a time given in input samples
+fn expected(t: f64) -> Complex<f64> {
+    let phi = 2.0 * PI * FREQUENCY * t / SAMPLE_RATE;
+    Complex::new(libm::sin(phi), libm::cos(phi))
+}
+
+/// SNR in dB of the output against the ideal tone at the new rate, skipping the first outputs
+fn snr(output: &[Complex<f64>], ratio: f64) -> f64 {
+    let (mut signal, mut noise) = (0.0, 0.0);
+
+    for (k, y) in output.iter().enumerate().skip(64) {
+        let x = expected(k as f64 / ratio);
+        signal += x.norm_sqr();
+        noise += (y - x).norm_sqr();
+    }
+
+    10.0 * libm::log10(signal / noise)
+}
+
+#[test]
+fn test_fractional_resampler_farrow_snr() {
+    let ratio = core::f64::consts::SQRT_2;
+    let mut resampler = FractionalResampler::new(Type::Complex, ratio, Interpolation::Farrow);
+    let output = resample_tone(&mut resampler, 40);
+
+    assert!((output.len() as f64 - 40.0 * 256.0 * ratio).abs() < 4.0);
+    let snr = snr(&output, ratio);
+    assert!(snr > 90.0, "SNR is {} dB", snr);
+}
+
+#[test]
+fn test_fractional_resampler_polyphase_snr() {
+    let ratio = 0.7318;
+    let mut resampler = FractionalResampler::new(Type::Complex, ratio, Interpolation::Polyphase { phases: 64, taps: 16 });
+    let output = resample_tone(&mut resampler, 40);
+
+    assert!((output.len() as f64 - 40.0 * 256.0 * ratio).abs() < 16.0);
+    let snr = snr(&output, ratio);
+    assert!(snr > 65.0, "SNR is {} dB", snr);
+}
+
+#[test]
+fn test_fractional_resampler_ratio_change() {
+    let mut resampler = FractionalResampler::new(Type::Complex, 1.5, Interpolation::Farrow);
+    let handle = resampler.clone();
+
+    let first = resample_tone(&mut resampler, 8);
+    handle.set_ratio(0.5);
+    assert_eq!(resampler.ratio(), 0.5);
+    let second = resample_tone(&mut resampler, 8);
+
+    assert!((first.len() as f64 - 8.0 * 256.0 * 1.5).abs() < 4.0);
+    assert!((second.len() as f64 - 8.0 * 256.0 * 0.5).abs() < 4.0);
+}
+
+#[test]
+fn test_fractional_resampler_unity_ratio() {
+    let input: Vec<f64> = (0..100).map(|i| libm::sin(i as f64 * 0.3)).collect();
+
+    for interpolation in [Interpolation::Farrow, Interpolation::Polyphase { phases: 8, taps: 8 }] {
+        let mut resampler = FractionalResampler::new(Type::F64, 1.0, interpolation);
+        let mut output = [Samples::new(Type::F64)];
+        resampler.work(&[Samples::F64(input.clone())], &mut output);
+
+        // Outputs land exactly on the inputs, apart from the last few that are still waiting
+        let output = output[0].as_f64();
+        assert!(output.len() + 8 >= input.len());
+        for (y, x) in output.iter().zip(input.iter()).skip(4) {
+            assert!((y - x).abs() < 1e-12);
+        }
+    }
+}
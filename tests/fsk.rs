@@ -0,0 +1,158 @@
+mod common;
+
+use core::f64::consts::PI;
+
+use num::Complex;
+
+use common::{count_errors, random_bits, work, work_all, Noise};
+use superdsp::math::filter::{gaussian, FilterError};
+use superdsp::objects::flowgraph::Flowgraph;
+use superdsp::objects::fractional_resampler::{FractionalResampler, Interpolation};
+use superdsp::objects::fsk_demodulator::FskDemodulator;
+use superdsp::objects::fsk_modulator::FskModulator;
+use superdsp::objects::mfsk_demodulator::MfskDemodulator;
+use superdsp::objects::object::{DSPObject, Samples, Type};
+use superdsp::objects::vector_sink::VectorSink;
+use superdsp::objects::vector_source::VectorSource;
+
+const SAMPLE_RATE: f64 = 48000.0;
+
+/// Modulate, add a clock offset, a phase offset and noise and demodulate, returning the bits sent, the hard
+/// bits and the soft bits
+fn loopback<T: DSPObject>(mut modulator: FskModulator, mut demodulator: T, sigma: f64) -> (Vec<f64>, Vec<f64>, Vec<f64>) {
+    let bits = random_bits(&mut Noise::new(3), 16000);
+    let mut noise = Noise::new(4);
+    let mut clock = FractionalResampler::new(Type::Complex, 1.0005, Interpolation::Farrow);
+    let (mut hard, mut soft) = (Vec::new(), Vec::new());
+    let phase = Complex::from_polar(1.0, 1.0);
+
+    for block in bits.chunks(200) {
+        let signal = work(&mut modulator, Samples::F64(block.to_vec()));
+        let signal = work(&mut clock, signal);
+        let noisy: Vec<Complex<f64>> = signal.as_complex().iter().map(|x| x * phase + noise.complex(sigma)).collect();
+
+        let outputs = work_all(&mut demodulator, Samples::Complex(noisy));
+        hard.extend_from_slice(outputs[0].as_f64());
+        soft.extend_from_slice(outputs[1].as_f64());
+    }
+
+    (bits, hard, soft)
+}
+
+#[test]
+fn test_gaussian() {
+    let taps = gaussian(8, 0.3, 4).unwrap();
+    assert_eq!(taps.len(), 33);
+    assert!((taps.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+    assert!((taps[0] - taps[32]).abs() < 1e-15);
+    assert!(taps.iter().all(|tap| *tap <= taps[16]));
+
+    // A narrower bandwidth spreads the pulse out
+    assert!(gaussian(8, 0.5, 4).unwrap()[16] > taps[16]);
+
+    assert_eq!(gaussian(8, 0.0, 4), Err(FilterError::InvalidBandwidthTime(0.0)));
+    assert_eq!(gaussian(0, 0.3, 4), Err(FilterError::InvalidTaps(1)));
+}
+
+#[test]
+fn test_msk_phase_steps() {
+    // MSK turns by a quarter turn every symbol, counterclockwise for a one
+    let mut modulator = FskModulator::msk(4800.0, SAMPLE_RATE);
+    assert_eq!(modulator.samples_per_symbol(), 10);
+    assert_eq!(modulator.modulation_index(), 0.5);
+
+    let bits = vec![1.0, 1.0, 0.0, 1.0, 0.0, 0.0, 0.0];
+    let output = work(&mut modulator, Samples::F64(bits.clone()));
+    let output = output.as_complex();
+    assert_eq!(output.len(), 70);
+    assert!(output.iter().all(|x| (x.norm() - 1.0).abs() < 1e-6));
+
+    for (k, bit) in bits.iter().enumerate().take(bits.len() - 1) {
+        let turn = (output[10 * (k + 1)] * output[10 * k].conj()).arg();
+        let expected = if *bit == 1.0 { PI / 2.0 } else { -PI / 2.0 };
+        assert!((turn - expected).abs() < 1e-5, "symbol {} turned {}", k, turn);
+    }
+}
+
+#[test]
+fn test_fsk_tones() {
+    // Dibits are Gray mapped onto the four tones from lowest to highest
+    let mut modulator = FskModulator::new(4, 1000.0, 2000.0, None, SAMPLE_RATE);
+    let output = work(&mut modulator, Samples::F64(vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0]));
+    let output = output.as_complex();
+
+    for (k, tone) in [-3000.0, -1000.0, 1000.0, 3000.0].iter().enumerate() {
+        let frequency = (output[24 * k + 12] * output[24 * k + 11].conj()).arg() * SAMPLE_RATE / (2.0 * PI);
+        assert!((frequency - tone).abs() < 1e-3, "tone {} at {} Hz", k, frequency);
+    }
+}
+
+#[test]
+fn test_gmsk_loopback_flowgraph() {
+    let bits = random_bits(&mut Noise::new(1), 10000);
+    let sink = VectorSink::new(Type::F64);
+
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(VectorSource::new(Samples::F64(bits.clone()), 500, false));
+    let modulator = flowgraph.add(FskModulator::gmsk(9600.0, 0.3, SAMPLE_RATE));
+    let demodulator = flowgraph.add(FskDemodulator::msk(9600.0, SAMPLE_RATE));
+    let dst = flowgraph.add(sink.clone());
+    flowgraph.connect(src.output(0), modulator.input(0));
+    flowgraph.connect(modulator.output(0), demodulator.input(0));
+    flowgraph.connect(demodulator.output(0), dst.input(0));
+
+    // The modulator sets the rate from its own settings, whatever comes in
+    assert_eq!(flowgraph.sample_rate(modulator), Some(SAMPLE_RATE));
+    assert_eq!(flowgraph.sample_rate(demodulator), Some(9600.0));
+    flowgraph.run().unwrap();
+
+    let received = sink.samples();
+    assert_eq!(count_errors(&bits, received.as_f64(), 1000), 0);
+}
+
+#[test]
+fn test_fsk_discriminator_with_noise() {
+    let (bits, hard, soft) = loopback(FskModulator::new(2, 2400.0, 4800.0, Some(0.5), SAMPLE_RATE), FskDemodulator::new(2, 2400.0, 4800.0, SAMPLE_RATE), 0.1);
+    assert_eq!(count_errors(&bits, &hard, 2000), 0);
+
+    // Binary soft bits are the frequency in units of the deviation
+    let settled = &soft[2000..];
+    let mean = settled.iter().map(|x| x.abs()).sum::<f64>() / settled.len() as f64;
+    assert!((mean - 1.0).abs() < 0.25, "mean soft bit magnitude {}", mean);
+
+    let (bits, hard, _) = loopback(FskModulator::new(4, 1200.0, 4800.0, None, SAMPLE_RATE), FskDemodulator::new(4, 1200.0, 4800.0, SAMPLE_RATE), 0.05);
+    assert_eq!(count_errors(&bits, &hard, 2000), 0);
+}
+
+#[test]
+fn test_mfsk_matched_filter_bank() {
+    let (bits, hard, soft) = loopback(FskModulator::new(4, 1200.0, 2400.0, None, SAMPLE_RATE), MfskDemodulator::new(4, 1200.0, 2400.0, SAMPLE_RATE), 0.3);
+    assert_eq!(count_errors(&bits, &hard, 2000), 0);
+    assert!(soft.iter().all(|x| x.abs() <= 1.0));
+    assert!(soft[2000..].iter().all(|x| x.abs() > 0.3));
+
+    let (bits, hard, _) = loopback(FskModulator::new(8, 1200.0, 2400.0, None, SAMPLE_RATE), MfskDemodulator::new(8, 1200.0, 2400.0, SAMPLE_RATE), 0.3);
+    assert_eq!(count_errors(&bits, &hard, 3000), 0);
+}
+
+#[test]
+fn test_mfsk_burst_then_silence() {
+    let mut modulator = FskModulator::new(4, 1200.0, 2400.0, None, SAMPLE_RATE);
+    let mut demodulator = MfskDemodulator::new(4, 1200.0, 2400.0, SAMPLE_RATE);
+
+    // One tone for a burst, then nothing at all
+    let burst = work(&mut modulator, Samples::F64(vec![1.0; 40]));
+    let mut signal = burst.as_complex()[..400].to_vec();
+    signal.extend(vec![Complex::new(0.0, 0.0); 400]);
+
+    let mut hard = Vec::new();
+    for block in signal.chunks(100) {
+        hard.extend_from_slice(work_all(&mut demodulator, Samples::Complex(block.to_vec()))[0].as_f64());
+    }
+
+    // The timing loop stays within its limits and the demodulator keeps up with the input
+    let period = demodulator.period();
+    assert!((period - 20.0).abs() <= 1.0, "period {}", period);
+    assert!(hard.len() >= 2 * 35, "only {} bits", hard.len());
+    assert!(hard[..2 * 15].iter().all(|bit| *bit == 1.0));
+}
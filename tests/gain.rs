@@ -0,0 +1,67 @@
+mod common;
+
+use num::Complex;
+
+use common::{complex_tone, work};
+use superdsp::objects::agc::Agc;
+use superdsp::objects::fast_attack_agc::FastAttackAgc;
+use superdsp::objects::manual_gain::ManualGain;
+use superdsp::objects::object::{Samples, Type};
+
+const SAMPLE_RATE: f64 = 48000.0;
+
+#[test]
+fn test_manual_gain() {
+    let mut gain = ManualGain::new(Type::F64, 2.0);
+    let handle = gain.clone();
+    assert_eq!(work(&mut gain, Samples::F64(vec![1.0, -0.5])), Samples::F64(vec![2.0, -1.0]));
+
+    handle.set_gain_db(-20.0);
+    assert!((gain.gain() - 0.1).abs() < 1e-12);
+    let output = work(&mut gain, Samples::F64(vec![1.0, 2.0]));
+    assert!((output.as_f64()[0] - 0.1).abs() < 1e-12 && (output.as_f64()[1] - 0.2).abs() < 1e-12);
+
+    let output = work(&mut ManualGain::new(Type::Complex, 0.1), Samples::Complex(vec![Complex::new(1.0, 2.0)]));
+    assert!((output.as_complex()[0] - Complex::new(0.1, 0.2)).norm() < 1e-12);
+}
+
+#[test]
+fn test_agc_settles_on_reference() {
+    let mut agc = Agc::new(Type::Complex, 1.0, 1e-2, 1000.0);
+    let output = work(&mut agc, Samples::Complex(complex_tone(1000.0, 0.05, SAMPLE_RATE, 20000)));
+    for y in &output.as_complex()[15000..] {
+        assert!((y.norm() - 1.0).abs() < 1e-3, "{}", y.norm());
+    }
+    assert!((agc.gain() - 20.0).abs() < 0.1);
+
+    // A real square wave has a constant magnitude too
+    let mut agc = Agc::new(Type::F64, 0.5, 1e-2, 1000.0);
+    let input: Vec<f64> = (0..20000).map(|i| if (i / 10) % 2 == 0 { 3.0 } else { -3.0 }).collect();
+    let output = work(&mut agc, Samples::F64(input));
+    assert!(output.as_f64()[15000..].iter().all(|y| (y.abs() - 0.5).abs() < 1e-3));
+}
+
+#[test]
+fn test_agc_max_gain() {
+    let mut agc = Agc::new(Type::Complex, 1.0, 1e-2, 10.0);
+    work(&mut agc, Samples::Complex(complex_tone(1000.0, 1e-4, SAMPLE_RATE, 10000)));
+    assert_eq!(agc.gain(), 10.0);
+}
+
+#[test]
+fn test_fast_attack_agc() {
+    let mut agc = FastAttackAgc::new(Type::Complex, 1.0, 0.1, 1e-2, 1000.0);
+
+    // Settle on a weak signal, then hit it with one 40 dB stronger
+    work(&mut agc, Samples::Complex(complex_tone(1000.0, 0.01, SAMPLE_RATE, 200000)));
+    assert!((agc.gain() - 100.0).abs() < 1.0, "{}", agc.gain());
+
+    let output = work(&mut agc, Samples::Complex(complex_tone(1000.0, 1.0, SAMPLE_RATE, 2000)));
+    let output = output.as_complex();
+    assert!(output[100..].iter().all(|y| (y.norm() - 1.0).abs() < 0.05));
+
+    // Dropping back down recovers slowly
+    let output = work(&mut agc, Samples::Complex(complex_tone(1000.0, 0.1, SAMPLE_RATE, 2000)));
+    assert!(output.as_complex()[50].norm() < 0.2);
+    assert!(agc.gain() < 10.0);
+}
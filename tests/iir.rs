@@ -0,0 +1,162 @@
+use num::Complex;
+
+use superdsp::math::filter::FilterError;
+use superdsp::math::iir::{butterworth, chebyshev1, chebyshev2, elliptic, frequency_response, magnitude_db, BandType, Biquad};
+use superdsp::objects::flowgraph::Flowgraph;
+use superdsp::objects::iir_filter::IirFilter;
+use superdsp::objects::object::{DSPObject, Samples, Type};
+use superdsp::objects::vector_sink::VectorSink;
+use superdsp::objects::vector_source::VectorSource;
+
+const SAMPLE_RATE: f64 = 48000.0;
+
+/// Highest and lowest response in dB over a range of frequencies
+fn response_range(sections: &[Biquad], low: f64, high: f64) -> (f64, f64) {
+    (0..=500).map(|i| magnitude_db(sections, low + (high - low) * i as f64 / 500.0, SAMPLE_RATE))
+        .fold((f64::NEG_INFINITY, f64::INFINITY), |(max, min), x| (max.max(x), min.min(x)))
+}
+
+fn assert_stable(sections: &[Biquad]) {
+    for section in sections {
+        // Both poles of 1 + a1 z⁻¹ + a2 z⁻² are inside the unit circle
+        assert!(section.a2.abs() < 1.0 && section.a1.abs() < 1.0 + section.a2, "{:?}", section);
+    }
+}
+
+#[test]
+fn test_butterworth_matches_reference() {
+    // Second order, cutoff at a quarter of nyquist
+    let sections = butterworth(2, BandType::LowPass(1000.0), 8000.0).unwrap();
+    assert_eq!(sections.len(), 1);
+
+    let expected = Biquad::new(0.09763107, 0.19526215, 0.09763107, -0.94280904, 0.33333333);
+    let section = sections[0];
+    for (x, y) in [(section.b0, expected.b0), (section.b1, expected.b1), (section.b2, expected.b2), (section.a1, expected.a1), (section.a2, expected.a2)] {
+        assert!((x - y).abs() < 1e-7);
+    }
+}
+
+#[test]
+fn test_butterworth_response() {
+    let lowpass = butterworth(5, BandType::LowPass(3000.0), SAMPLE_RATE).unwrap();
+    assert_eq!(lowpass.len(), 3);
+    assert_stable(&lowpass);
+    assert!(magnitude_db(&lowpass, 0.0, SAMPLE_RATE).abs() < 1e-9);
+    assert!((magnitude_db(&lowpass, 3000.0, SAMPLE_RATE) + 3.0103).abs() < 1e-3);
+
+    let highpass = butterworth(4, BandType::HighPass(3000.0), SAMPLE_RATE).unwrap();
+    assert_stable(&highpass);
+    assert!(magnitude_db(&highpass, 24000.0, SAMPLE_RATE).abs() < 1e-9);
+    assert!((magnitude_db(&highpass, 3000.0, SAMPLE_RATE) + 3.0103).abs() < 1e-3);
+    assert!(magnitude_db(&highpass, 300.0, SAMPLE_RATE) < -75.0);
+
+    let bandpass = butterworth(3, BandType::BandPass(2000.0, 4000.0), SAMPLE_RATE).unwrap();
+    assert_eq!(bandpass.len(), 3);
+    assert_stable(&bandpass);
+    assert!((magnitude_db(&bandpass, 2000.0, SAMPLE_RATE) + 3.0103).abs() < 1e-3);
+    assert!((magnitude_db(&bandpass, 4000.0, SAMPLE_RATE) + 3.0103).abs() < 1e-3);
+    assert!(response_range(&bandpass, 2500.0, 3500.0).1 > -0.5);
+
+    let bandstop = butterworth(3, BandType::BandStop(2000.0, 4000.0), SAMPLE_RATE).unwrap();
+    assert_stable(&bandstop);
+    assert!((magnitude_db(&bandstop, 2000.0, SAMPLE_RATE) + 3.0103).abs() < 1e-3);
+    assert!(magnitude_db(&bandstop, 0.0, SAMPLE_RATE).abs() < 1e-9);
+    assert!(response_range(&bandstop, 2800.0, 2900.0).0 < -40.0);
+}
+
+#[test]
+fn test_chebyshev_response() {
+    let type1 = chebyshev1(5, 1.0, BandType::LowPass(4000.0), SAMPLE_RATE).unwrap();
+    assert_stable(&type1);
+    let (max, min) = response_range(&type1, 0.0, 4000.0);
+    assert!(max < 1e-9 && min > -1.0 - 1e-6);
+    assert!((magnitude_db(&type1, 4000.0, SAMPLE_RATE) + 1.0).abs() < 1e-6);
+
+    let type2 = chebyshev2(6, 50.0, BandType::LowPass(6000.0), SAMPLE_RATE).unwrap();
+    assert_stable(&type2);
+    assert!(response_range(&type2, 6000.0, 24000.0).0 < -50.0 + 1e-6);
+    assert!((magnitude_db(&type2, 6000.0, SAMPLE_RATE) + 50.0).abs() < 1e-6);
+    assert!(magnitude_db(&type2, 0.0, SAMPLE_RATE).abs() < 1e-9);
+
+    let highpass = chebyshev1(4, 0.5, BandType::HighPass(8000.0), SAMPLE_RATE).unwrap();
+    let (max, min) = response_range(&highpass, 8000.0, 24000.0);
+    assert!(max < 1e-9 && min > -0.5 - 1e-6);
+}
+
+#[test]
+fn test_elliptic_response() {
+    let lowpass = elliptic(5, 0.5, 60.0, BandType::LowPass(4000.0), SAMPLE_RATE).unwrap();
+    assert_stable(&lowpass);
+    let (max, min) = response_range(&lowpass, 0.0, 4000.0);
+    assert!(max < 1e-6 && min > -0.5 - 1e-6);
+    assert!((magnitude_db(&lowpass, 4000.0, SAMPLE_RATE) + 0.5).abs() < 1e-6);
+    assert!(magnitude_db(&lowpass, 0.0, SAMPLE_RATE).abs() < 1e-9);
+
+    // The stopband is equiripple, touching -60 dB but never going above it
+    let (max, _) = response_range(&lowpass, 7000.0, 24000.0);
+    assert!(max < -60.0 + 1e-6 && max > -60.5);
+
+    let even = elliptic(4, 1.0, 40.0, BandType::LowPass(4000.0), SAMPLE_RATE).unwrap();
+    assert!((magnitude_db(&even, 0.0, SAMPLE_RATE) + 1.0).abs() < 1e-6);
+    let (max, _) = response_range(&even, 6500.0, 24000.0);
+    assert!(max < -40.0 + 1e-6 && max > -40.5);
+
+    let bandpass = elliptic(4, 1.0, 50.0, BandType::BandPass(5000.0, 7000.0), SAMPLE_RATE).unwrap();
+    assert_stable(&bandpass);
+    let (max, min) = response_range(&bandpass, 5000.0, 7000.0);
+    assert!(max < 1e-6 && min > -1.0 - 1e-6);
+    assert!(response_range(&bandpass, 0.0, 3500.0).0 < -50.0 + 1e-6);
+}
+
+#[test]
+fn test_iir_design_errors() {
+    assert_eq!(butterworth(0, BandType::LowPass(1000.0), SAMPLE_RATE), Err(FilterError::InvalidOrder(0)));
+    assert_eq!(butterworth(2, BandType::LowPass(25000.0), SAMPLE_RATE), Err(FilterError::InvalidFrequency { frequency: 25000.0, nyquist: 24000.0 }));
+    assert_eq!(butterworth(2, BandType::BandPass(2000.0, 1000.0), SAMPLE_RATE), Err(FilterError::InvalidBand { low: 2000.0, high: 1000.0 }));
+    assert_eq!(chebyshev1(2, 0.0, BandType::LowPass(1000.0), SAMPLE_RATE), Err(FilterError::InvalidRipple(0.0)));
+    assert_eq!(elliptic(2, 3.0, 2.0, BandType::LowPass(1000.0), SAMPLE_RATE), Err(FilterError::InvalidAttenuation(2.0)));
+}
+
+#[test]
+fn test_iir_filter_block() {
+    let sections = chebyshev1(4, 1.0, BandType::LowPass(2000.0), SAMPLE_RATE).unwrap();
+    let data: Vec<Complex<f64>> = (0..2000).map(|i| Complex::new(libm::sin(0.3 * i as f64), libm::cos(2.0 * i as f64))).collect();
+
+    // Filtering in one go is the same as filtering block by block
+    let mut filter = IirFilter::new(Type::Complex, sections.clone());
+    let mut expected = [Samples::new(Type::Complex)];
+    filter.work(&[Samples::Complex(data.clone())], &mut expected);
+
+    let sink = VectorSink::new(Type::Complex);
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(VectorSource::new(Samples::Complex(data.clone()), 17, false));
+    let iir = flowgraph.add(IirFilter::new(Type::Complex, sections.clone()));
+    let dst = flowgraph.add(sink.clone());
+    flowgraph.connect(src.output(0), iir.input(0));
+    flowgraph.connect(iir.output(0), dst.input(0));
+    flowgraph.run().unwrap();
+    assert_eq!(sink.samples(), expected[0]);
+
+    // Real sections filter the real and imaginary parts separately
+    let real: Vec<f64> = data.iter().map(|x| x.re).collect();
+    let mut real_filter = IirFilter::new(Type::F64, sections.clone());
+    let mut outputs = [Samples::new(Type::F64)];
+    real_filter.work(&[Samples::F64(real)], &mut outputs);
+    for (x, y) in outputs[0].as_f64().iter().zip(expected[0].as_complex()) {
+        assert!((x - y.re).abs() < 1e-12);
+    }
+
+    // A steady tone comes out scaled by the frequency response
+    let tone: Vec<f64> = (0..20000).map(|i| libm::cos(2.0 * core::f64::consts::PI * 1000.0 * i as f64 / SAMPLE_RATE)).collect();
+    real_filter.reset();
+    let mut outputs = [Samples::new(Type::F64)];
+    real_filter.work(&[Samples::F64(tone)], &mut outputs);
+    let peak = outputs[0].as_f64()[10000..].iter().fold(0.0, |max: f64, x| max.max(x.abs()));
+    assert!((peak - frequency_response(&sections, 1000.0, SAMPLE_RATE).norm()).abs() < 1e-3);
+
+    // After a reset the filter starts from silence again
+    real_filter.reset();
+    let mut outputs = [Samples::new(Type::F64)];
+    real_filter.work(&[Samples::F64(vec![0.0; 10])], &mut outputs);
+    assert_eq!(outputs[0].as_f64(), &[0.0; 10]);
+}
@@ -0,0 +1,102 @@
+use core::f64::consts::PI;
+
+use num::Complex;
+
+use superdsp::math::nco::Nco;
+use superdsp::objects::flowgraph::{Flowgraph, FlowgraphError};
+use superdsp::objects::frequency_shift::FrequencyShift;
+use superdsp::objects::nco_source::NcoSource;
+use superdsp::objects::object::{DSPObject, Samples, Type};
+use superdsp::objects::vector_sink::VectorSink;
+
+#[test]
+fn test_nco_stays_accurate() {
+    let cycles = 0.123456789;
+    let mut nco = Nco::new(cycles * 48000.0, 48000.0);
+    assert!((nco.frequency() - cycles * 48000.0).abs() < 1e-9);
+
+    for n in 0..1_000_000u64 {
+        let sample = nco.next_sample();
+
+        if n % 99991 == 0 {
+            let turns = (n as f64 * cycles).fract();
+            let expected = Complex::new(libm::cos(2.0 * PI * turns), libm::sin(2.0 * PI * turns));
+            assert!((sample - expected).norm() < 1e-6, "sample {} is {}, expected {}", n, sample, expected);
+        }
+    }
+}
+
+#[test]
+fn test_nco_phase_and_negative_frequency() {
+    let mut nco = Nco::new(-1000.0, 8000.0);
+    assert!((nco.frequency() + 1000.0).abs() < 1e-9);
+    assert!((nco.step() + PI / 4.0).abs() < 1e-12);
+
+    // Eight steps of -π/4 come back to the start
+    let first = nco.next_sample();
+    for _ in 0..7 {
+        nco.next_sample();
+    }
+    assert!((nco.current() - first).norm() < 1e-6);
+
+    nco.set_phase(PI / 2.0);
+    assert!((nco.current() - Complex::new(0.0, 1.0)).norm() < 1e-6);
+    nco.adjust_phase(PI);
+    assert!((nco.phase() + PI / 2.0).abs() < 1e-12);
+
+    // Frequencies above half the sample rate alias
+    nco.set_frequency(7000.0);
+    assert!((nco.frequency() + 1000.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_nco_source_retunes_without_phase_jump() {
+    let mut source = NcoSource::new(1000.0, 1.0, 48000.0, 100);
+    let handle = source.clone();
+
+    let mut first = [Samples::new(Type::Complex)];
+    source.work(&[], &mut first);
+    handle.set_frequency(-3000.0);
+    let mut second = [Samples::new(Type::Complex)];
+    source.work(&[], &mut second);
+
+    let first = first[0].as_complex();
+    let second = second[0].as_complex();
+    assert_eq!(source.frequency(), -3000.0);
+
+    // The first sample after retuning continues from where the old tone left off
+    let expected = first[99] * Complex::from_polar(1.0, 2.0 * PI * 1000.0 / 48000.0);
+    assert!((second[0] - expected).norm() < 1e-6);
+    let expected = second[0] * Complex::from_polar(1.0, -2.0 * PI * 3000.0 / 48000.0);
+    assert!((second[1] - expected).norm() < 1e-6);
+}
+
+#[test]
+fn test_frequency_shift_moves_tone_to_dc() {
+    let mut source = NcoSource::new(12500.0, 0.5, 100000.0, 1000);
+    let mut shift = FrequencyShift::new(-12500.0, 100000.0);
+
+    let mut tone = [Samples::new(Type::Complex)];
+    source.work(&[], &mut tone);
+    let mut output = [Samples::new(Type::Complex)];
+    shift.work(&tone, &mut output);
+
+    for y in output[0].as_complex() {
+        assert!((y - Complex::new(0.5, 0.0)).norm() < 1e-6, "{}", y);
+    }
+}
+
+#[test]
+fn test_frequency_shift_checks_sample_rate() {
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(NcoSource::new(1000.0, 1.0, 44100.0, 64));
+    let shift = flowgraph.add(FrequencyShift::new(100.0, 48000.0));
+    let sink = flowgraph.add(VectorSink::new(Type::Complex));
+    flowgraph.connect(src.output(0), shift.input(0));
+    flowgraph.connect(shift.output(0), sink.input(0));
+
+    assert_eq!(
+        flowgraph.build(),
+        Err(FlowgraphError::SampleRateMismatch { block: "FrequencyShift", port: shift.input(0), expected: 48000.0, found: 44100.0 })
+    );
+}
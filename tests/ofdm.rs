@@ -0,0 +1,140 @@
+mod common;
+
+use core::f64::consts::PI;
+
+use num::Complex;
+
+use common::{random_bits, Noise};
+use superdsp::math::constellation::Constellation;
+use superdsp::math::fourier::FftPlan;
+use superdsp::math::ofdm::{OfdmConfig, OfdmError};
+use superdsp::objects::flowgraph::Flowgraph;
+use superdsp::objects::object::{DSPObject, Samples, Type};
+use superdsp::objects::ofdm_receiver::OfdmReceiver;
+use superdsp::objects::ofdm_transmitter::OfdmTransmitter;
+use superdsp::objects::vector_sink::VectorSink;
+use superdsp::objects::vector_source::VectorSource;
+
+fn transmit(config: &OfdmConfig, bits: &[f64]) -> Vec<Complex<f64>> {
+    let mut transmitter = OfdmTransmitter::new(config.clone());
+    let mut output = [Samples::new(Type::Complex)];
+    transmitter.work(&[Samples::F64(bits.to_vec())], &mut output);
+
+    let [output] = output;
+    output.as_complex().to_vec()
+}
+
+/// Feed the receiver in uneven blocks and collect the hard bits
+fn receive(receiver: &mut OfdmReceiver, signal: &[Complex<f64>]) -> Vec<f64> {
+    let mut bits = Vec::new();
+    for block in signal.chunks(317) {
+        let mut outputs = [Samples::new(Type::F64), Samples::new(Type::F64)];
+        receiver.work(&[Samples::Complex(block.to_vec())], &mut outputs);
+        bits.extend_from_slice(outputs[0].as_f64());
+    }
+
+    bits
+}
+
+#[test]
+fn test_config() {
+    let qpsk = Constellation::qam(4).unwrap();
+
+    let config = OfdmConfig::standard(64, qpsk.clone(), 4).unwrap();
+    assert_eq!(config.data_carriers.len(), 48);
+    assert_eq!(config.pilot_carriers, vec![-21, -7, 7, 21]);
+    assert!(!config.data_carriers.contains(&0));
+    assert_eq!(config.cyclic_prefix, 16);
+    assert_eq!(config.bits_per_symbol(), 96);
+    assert_eq!(config.bits_per_frame(), 384);
+    assert_eq!(config.frame_length(), 6 * 80 + 64);
+
+    assert_eq!(OfdmConfig::standard(48, qpsk.clone(), 4), Err(OfdmError::InvalidFftSize(48)));
+    assert_eq!(OfdmConfig::new(7, 0, vec![1, 2, 3], vec![-1], qpsk.clone(), 1), Err(OfdmError::InvalidFftSize(7)));
+    assert_eq!(OfdmConfig::new(16, 17, vec![1, 2, 3], vec![-1], qpsk.clone(), 1), Err(OfdmError::InvalidCyclicPrefix { cyclic_prefix: 17, fft_size: 16 }));
+    assert_eq!(OfdmConfig::new(16, 4, vec![0, 1, 2], vec![-1], qpsk.clone(), 1), Err(OfdmError::InvalidCarrier(0)));
+    assert_eq!(OfdmConfig::new(16, 4, vec![1, 2, 8], vec![-1], qpsk.clone(), 1), Err(OfdmError::InvalidCarrier(8)));
+    assert_eq!(OfdmConfig::new(16, 4, vec![1, 2, 3], vec![2], qpsk.clone(), 1), Err(OfdmError::InvalidCarrier(2)));
+    assert_eq!(OfdmConfig::new(16, 4, vec![], vec![-2, -1, 1, 2], qpsk.clone(), 1), Err(OfdmError::TooFewCarriers(4)));
+    assert_eq!(OfdmConfig::new(16, 4, vec![1, 2, 3], vec![-1], qpsk, 0), Err(OfdmError::NoSymbols));
+}
+
+#[test]
+fn test_preamble() {
+    let config = OfdmConfig::standard(64, Constellation::qam(4).unwrap(), 1).unwrap();
+    let signal = transmit(&config, &vec![0.0; config.bits_per_frame()]);
+    assert_eq!(signal.len(), config.frame_length());
+
+    // The first symbol repeats after half of it, prefix included, so its metric is flat
+    let half = config.fft_size / 2;
+    for n in 0..config.cyclic_prefix + half {
+        assert!((signal[n] - signal[n + half]).norm() < 1e-12);
+    }
+
+    // Unit power on the symbols, and the second preamble symbol is what the config says
+    let symbols = &signal[..(2 + config.symbols_per_frame) * config.symbol_length()];
+    let power = symbols.iter().map(|x| x.norm_sqr()).sum::<f64>() / symbols.len() as f64;
+    assert!((power - 1.0).abs() < 0.05, "power {}", power);
+
+    let start = config.symbol_length() + config.cyclic_prefix;
+    let mut spectrum = signal[start..start + config.fft_size].to_vec();
+    FftPlan::new(config.fft_size).fft(&mut spectrum);
+    let scale = spectrum[config.bin(1)] / config.preamble()[1][config.bin(1)];
+    for (x, expected) in spectrum.iter().zip(config.preamble()[1].iter()) {
+        assert!((x - expected * scale).norm() < 1e-9);
+    }
+}
+
+#[test]
+fn test_loopback() {
+    let mut noise = Noise::new(11);
+
+    for (order, fft_size) in [(4, 64), (16, 64), (16, 128)] {
+        let config = OfdmConfig::standard(fft_size, Constellation::qam(order).unwrap(), 6).unwrap();
+        let bits = random_bits(&mut noise, 3 * config.bits_per_frame());
+        let sent = transmit(&config, &bits);
+
+        // Noise before the first frame, an offset of 2.3 subcarriers, a channel that spreads over
+        // a few samples and 30 dB of SNR
+        let offset = 2.3 * 2.0 * PI / fft_size as f64;
+        let taps = [Complex::new(0.8, 0.0), Complex::new(0.0, 0.45), Complex::new(-0.2, 0.1), Complex::new(0.1, 0.0)];
+        let sigma = libm::sqrt(libm::pow(10.0, -3.0) / 2.0);
+
+        let mut signal: Vec<Complex<f64>> = (0..377).map(|_| noise.complex(sigma)).collect();
+        for n in 0..sent.len() + taps.len() {
+            let x: Complex<f64> = taps.iter().enumerate().filter(|(k, _)| *k <= n && n - k < sent.len()).map(|(k, tap)| tap * sent[n - k]).sum();
+            signal.push(x * Complex::from_polar(1.0, offset * n as f64) + noise.complex(sigma));
+        }
+
+        let mut receiver = OfdmReceiver::new(config.clone());
+        let received = receive(&mut receiver, &signal);
+
+        assert_eq!(receiver.frames(), 3);
+        assert_eq!(received, bits, "{}-QAM with {} subcarriers", order, fft_size);
+        assert!((receiver.cfo() - 2.3).abs() < 0.02, "offset {}", receiver.cfo());
+
+        // A unitary FFT leaves the noise power on each subcarrier as it was on each sample
+        let variance = 2.0 * sigma * sigma;
+        assert!((receiver.noise_variance() / variance - 1.0).abs() < 0.5, "noise {} for {}", receiver.noise_variance(), variance);
+    }
+}
+
+#[test]
+fn test_flowgraph() {
+    let mut noise = Noise::new(5);
+    let config = OfdmConfig::standard(64, Constellation::qam(16).unwrap(), 4).unwrap();
+    let bits = random_bits(&mut noise, 5 * config.bits_per_frame());
+    let sink = VectorSink::new(Type::F64);
+
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(VectorSource::new(Samples::F64(bits.clone()), 500, false));
+    let transmitter = flowgraph.add(OfdmTransmitter::new(config.clone()));
+    let receiver = flowgraph.add(OfdmReceiver::new(config));
+    let dst = flowgraph.add(sink.clone());
+    flowgraph.connect(src.output(0), transmitter.input(0));
+    flowgraph.connect(transmitter.output(0), receiver.input(0));
+    flowgraph.connect(receiver.output(0), dst.input(0));
+    flowgraph.run().unwrap();
+
+    assert_eq!(sink.samples().as_f64(), &bits[..]);
+}
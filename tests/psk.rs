@@ -0,0 +1,109 @@
+mod common;
+
+use num::Complex;
+
+use common::{count_errors, random_bits, work, Noise};
+use superdsp::objects::costas_loop::Psk;
+use superdsp::objects::flowgraph::Flowgraph;
+use superdsp::objects::frequency_shift::FrequencyShift;
+use superdsp::objects::object::{Samples, Type};
+use superdsp::objects::psk_demodulator::PskDemodulator;
+use superdsp::objects::psk_modulator::{BitFormat, PskModulator};
+use superdsp::objects::vector_sink::VectorSink;
+use superdsp::objects::vector_source::VectorSource;
+
+/// The sample rate `WaveStepGenComplex` is usually run at in the examples
+const SAMPLE_RATE: f64 = 48000.0;
+
+#[test]
+fn test_psk_modulator_gray_mapping() {
+    // With one sample per symbol the pulse is an impulse, so the symbols come out as they are,
+    // delayed by half the pulse span. Each dibit lands on its own QPSK point, and neighbouring
+    // points differ in a single bit.
+    let mut modulator = PskModulator::new(Psk::Qpsk, BitFormat::Bits, 1, 0.35, false);
+    let bits = vec![0.0, 0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+    let output = work(&mut modulator, Samples::F64(bits));
+
+    let points: Vec<usize> = output.as_complex()[5..9].iter().map(|x| Psk::Qpsk.nearest(*x)).collect();
+    assert_eq!(points, vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn test_psk_modulator_unit_power() {
+    let mut noise = Noise::new(5);
+    let bits = random_bits(&mut noise, 3000);
+    let output = work(&mut PskModulator::new(Psk::Psk8, BitFormat::Bits, 8, 0.35, false), Samples::F64(bits));
+
+    let output = &output.as_complex()[200..];
+    let power = output.iter().map(|x| x.norm_sqr()).sum::<f64>() / output.len() as f64;
+    assert!((power - 1.0).abs() < 0.05, "power {}", power);
+}
+
+#[test]
+fn test_psk_loopback_flowgraph() {
+    let mut noise = Noise::new(11);
+    let bits = random_bits(&mut noise, 20000);
+    let sink = VectorSink::new(Type::F64);
+
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(VectorSource::new(Samples::F64(bits.clone()), 500, false));
+    let modulator = flowgraph.add(PskModulator::new(Psk::Qpsk, BitFormat::Bits, 4, 0.35, true));
+    let offset = flowgraph.add(FrequencyShift::new(150.0, SAMPLE_RATE));
+    let demodulator = flowgraph.add(PskDemodulator::new(Psk::Qpsk, BitFormat::Bits, 4, 0.35, true, SAMPLE_RATE));
+    let dst = flowgraph.add(sink.clone());
+    flowgraph.connect(src.output(0), modulator.input(0));
+    flowgraph.connect(modulator.output(0), offset.input(0));
+    flowgraph.connect(offset.output(0), demodulator.input(0));
+    flowgraph.connect(demodulator.output(0), dst.input(0));
+
+    assert_eq!(flowgraph.sample_rate(demodulator), Some(SAMPLE_RATE / 2.0));
+    flowgraph.run().unwrap();
+
+    let received = sink.samples();
+    let received = received.as_f64();
+    assert!(received.len() > 19900, "only {} bits came out", received.len());
+    assert_eq!(count_errors(&bits, received, 4000), 0);
+}
+
+/// Modulate, add a frequency offset and noise, and demodulate again,
+/// and return the bits sent and received
+fn loopback(psk: Psk, format: BitFormat, differential: bool, sigma: f64) -> (Vec<f64>, Vec<f64>) {
+    let mut noise = Noise::new(psk.order() as u64);
+    let values: Vec<f64> = (0..12000 / format.bits_per_sample()).map(|_| noise.below(1 << format.bits_per_sample()) as f64).collect();
+
+    let mut modulator = PskModulator::new(psk, format, 4, 0.35, differential);
+    let mut shift = FrequencyShift::new(if differential { 40.0 } else { 0.0 }, SAMPLE_RATE);
+    let mut demodulator = PskDemodulator::new(psk, format, 4, 0.35, differential, SAMPLE_RATE);
+    let mut received = Vec::new();
+
+    for block in values.chunks(100) {
+        let signal = work(&mut modulator, Samples::F64(block.to_vec()));
+        let signal = work(&mut shift, signal);
+        let noisy: Vec<Complex<f64>> = signal.as_complex().iter().map(|x| x + noise.complex(sigma)).collect();
+        received.extend_from_slice(work(&mut demodulator, Samples::Complex(noisy)).as_f64());
+    }
+
+    // The stream has no framing, so bytes only line up with the ones sent up to a bit offset
+    let unpack = |values: Vec<f64>| -> Vec<f64> {
+        let width = format.bits_per_sample();
+        values.iter().flat_map(|value| (0..width).rev().map(move |bit| ((*value as usize >> bit) & 1) as f64)).collect()
+    };
+
+    (unpack(values), unpack(received))
+}
+
+#[test]
+fn test_psk_loopback_with_noise() {
+    let (sent, received) = loopback(Psk::Bpsk, BitFormat::Bits, true, 0.2);
+    assert_eq!(count_errors(&sent, &received, 4000), 0);
+
+    let (sent, received) = loopback(Psk::Qpsk, BitFormat::Bytes, true, 0.1);
+    assert_eq!(count_errors(&sent, &received, 4000), 0);
+
+    let (sent, received) = loopback(Psk::Psk8, BitFormat::Bits, true, 0.05);
+    assert_eq!(count_errors(&sent, &received, 4000), 0);
+
+    // Without differential encoding the carrier loop has to lock at the right phase by itself
+    let (sent, received) = loopback(Psk::Qpsk, BitFormat::Bits, false, 0.1);
+    assert_eq!(count_errors(&sent, &received, 4000), 0);
+}
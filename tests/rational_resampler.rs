@@ -0,0 +1,84 @@
+mod common;
+
+use core::f64::consts::PI;
+
+use num::Complex;
+
+use common::tone;
+use superdsp::objects::flowgraph::Flowgraph;
+use superdsp::objects::object::{Samples, Type};
+use superdsp::objects::rational_resampler::RationalResampler;
+use superdsp::objects::vector_sink::VectorSink;
+use superdsp::objects::vector_source::VectorSource;
+use superdsp::objects::wave_gen_complex::WaveStepGenComplex;
+
+const SAMPLE_RATE: f64 = 48000.0;
+
+/// Run `input` through a resampler in blocks of `block_size`
+fn resample(resampler: RationalResampler, input: Samples, block_size: usize) -> Samples {
+    let sink = VectorSink::new(input.get_type());
+
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(VectorSource::new(input, block_size, false));
+    let resampler = flowgraph.add(resampler);
+    let dst = flowgraph.add(sink.clone());
+    flowgraph.connect(src.output(0), resampler.input(0));
+    flowgraph.connect(resampler.output(0), dst.input(0));
+    flowgraph.run().unwrap();
+
+    sink.samples()
+}
+
+#[test]
+fn test_rational_resampler_reduces_ratio() {
+    let resampler = RationalResampler::new(Type::F64, 12, 8);
+    assert_eq!((resampler.interpolation(), resampler.decimation()), (3, 2));
+
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(WaveStepGenComplex::new(1000.0, 1.0, 0.0, 1e6, 1000));
+    let resampler = flowgraph.add(RationalResampler::new(Type::Complex, 6, 125));
+    let sink = flowgraph.add(VectorSink::new(Type::Complex));
+    flowgraph.connect(src.output(0), resampler.input(0));
+    flowgraph.connect(resampler.output(0), sink.input(0));
+
+    assert_eq!(flowgraph.sample_rate(resampler), Some(48000.0));
+}
+
+#[test]
+fn test_rational_resampler_keeps_tone() {
+    let resampler = RationalResampler::new(Type::F64, 3, 2);
+    let delay = (resampler.taps().len() - 1) as f64 / 2.0;
+
+    let output = resample(resampler, Samples::F64(tone(1000.0, 1.0, SAMPLE_RATE, 4800)), 480);
+    let output = output.as_f64();
+    assert_eq!(output.len(), 7200);
+
+    // Compare against the tone at the new rate once the filter has filled up
+    for (n, y) in output.iter().enumerate().skip(200) {
+        let t = (2.0 * n as f64 - delay) / (3.0 * SAMPLE_RATE);
+        let expected = libm::sin(2.0 * PI * 1000.0 * t);
+        assert!((y - expected).abs() < 2e-3, "sample {} is {}, expected {}", n, y, expected);
+    }
+}
+
+#[test]
+fn test_rational_resampler_rejects_aliases() {
+    // 20 kHz is above the 8 kHz Nyquist frequency of the output and would alias to 4 kHz
+    let output = resample(RationalResampler::new(Type::F64, 1, 3), Samples::F64(tone(20000.0, 1.0, SAMPLE_RATE, 9600)), 960);
+    let output = output.as_f64();
+    assert_eq!(output.len(), 3200);
+
+    let peak = output.iter().skip(100).fold(0.0, |max: f64, y| max.max(y.abs()));
+    assert!(peak < 1e-3, "alias peak is {}", peak);
+}
+
+#[test]
+fn test_rational_resampler_block_size_independent() {
+    let input: Vec<Complex<f64>> = (0..1000).map(|i| Complex::new(libm::sin(i as f64 * 0.1), libm::cos(i as f64 * 0.37))).collect();
+
+    let whole = resample(RationalResampler::new(Type::Complex, 5, 7), Samples::Complex(input.clone()), 1000);
+    let split = resample(RationalResampler::new(Type::Complex, 5, 7), Samples::Complex(input), 13);
+
+    assert_eq!(whole.len(), 715);
+    assert_eq!(whole, split);
+}
@@ -0,0 +1,82 @@
+use superdsp::math::filter::{magnitude_db, FilterError};
+use superdsp::math::remez::{remez, remez_with_limit, Band};
+
+const SAMPLE_RATE: f64 = 48000.0;
+
+/// Largest deviation from `gain` across a band, on a fine grid
+fn worst(taps: &[f64], low: f64, high: f64, gain: f64) -> f64 {
+    (0..=1000).map(|i| {
+        let frequency = low + (high - low) * i as f64 / 1000.0;
+        (libm::pow(10.0, magnitude_db(taps, frequency, SAMPLE_RATE) / 20.0) - gain).abs()
+    }).fold(0.0, f64::max)
+}
+
+#[test]
+fn test_remez_lowpass_is_equiripple() {
+    let bands = [Band::new(0.0, 6000.0, 1.0, 1.0), Band::new(8000.0, 24000.0, 0.0, 10.0)];
+    let design = remez(61, &bands, SAMPLE_RATE).unwrap();
+    let taps = &design.taps;
+
+    assert_eq!(taps.len(), 61);
+    for k in 0..61 {
+        assert!((taps[k] - taps[60 - k]).abs() < 1e-12);
+    }
+
+    // The weighted error is the same in both bands, so the passband error is 10 times larger. The
+    // design only sees a grid, so peaks between grid points can be a little higher.
+    let passband = worst(taps, 0.0, 6000.0, 1.0);
+    let stopband = worst(taps, 8000.0, 24000.0, 0.0);
+    assert!((passband - design.deviation).abs() < 0.03 * design.deviation);
+    assert!((stopband * 10.0 - design.deviation).abs() < 0.03 * design.deviation);
+
+    assert!(design.stopband_attenuation > 55.0);
+    assert!((design.stopband_attenuation + 20.0 * libm::log10(stopband)).abs() < 0.1);
+    assert!((design.passband_ripple - 20.0 * libm::log10(1.0 + passband)).abs() < 0.01);
+}
+
+#[test]
+fn test_remez_even_length() {
+    let bands = [Band::new(0.0, 4000.0, 1.0, 1.0), Band::new(6000.0, 24000.0, 0.0, 1.0)];
+    let design = remez(40, &bands, SAMPLE_RATE).unwrap();
+
+    assert_eq!(design.taps.len(), 40);
+    assert!((worst(&design.taps, 0.0, 4000.0, 1.0) - design.deviation).abs() < 0.03 * design.deviation);
+    assert!((worst(&design.taps, 6000.0, 24000.0, 0.0) - design.deviation).abs() < 0.03 * design.deviation);
+}
+
+#[test]
+fn test_remez_bandpass_and_highpass() {
+    let bands = [
+        Band::new(0.0, 3000.0, 0.0, 1.0),
+        Band::new(5000.0, 9000.0, 1.0, 1.0),
+        Band::new(11000.0, 24000.0, 0.0, 1.0),
+    ];
+    let design = remez(81, &bands, SAMPLE_RATE).unwrap();
+    assert!(design.stopband_attenuation > 50.0);
+    assert!(design.passband_ripple < 0.05);
+
+    let bands = [Band::new(0.0, 8000.0, 0.0, 1.0), Band::new(10000.0, 24000.0, 1.0, 1.0)];
+    let design = remez(51, &bands, SAMPLE_RATE).unwrap();
+    assert!(design.stopband_attenuation > 40.0);
+    assert!(magnitude_db(&design.taps, 24000.0, SAMPLE_RATE).abs() < 0.1);
+}
+
+#[test]
+fn test_remez_errors() {
+    let lowpass = [Band::new(0.0, 6000.0, 1.0, 1.0), Band::new(8000.0, 24000.0, 0.0, 1.0)];
+    assert_eq!(remez_with_limit(61, &lowpass, SAMPLE_RATE, 1), Err(FilterError::DidNotConverge { iterations: 1 }));
+
+    assert_eq!(remez(2, &lowpass, SAMPLE_RATE), Err(FilterError::InvalidTaps(2)));
+    let highpass = [Band::new(0.0, 8000.0, 0.0, 1.0), Band::new(10000.0, 24000.0, 1.0, 1.0)];
+    assert_eq!(remez(50, &highpass, SAMPLE_RATE), Err(FilterError::InvalidTaps(50)));
+    assert_eq!(remez(31, &[], SAMPLE_RATE), Err(FilterError::NoBands));
+
+    let overlapping = [Band::new(0.0, 6000.0, 1.0, 1.0), Band::new(5000.0, 24000.0, 0.0, 1.0)];
+    assert_eq!(remez(31, &overlapping, SAMPLE_RATE), Err(FilterError::InvalidBand { low: 5000.0, high: 24000.0 }));
+
+    let too_high = [Band::new(0.0, 30000.0, 1.0, 1.0)];
+    assert_eq!(remez(31, &too_high, SAMPLE_RATE), Err(FilterError::InvalidFrequency { frequency: 30000.0, nyquist: 24000.0 }));
+
+    let unweighted = [Band::new(0.0, 6000.0, 1.0, 0.0)];
+    assert_eq!(remez(31, &unweighted, SAMPLE_RATE), Err(FilterError::InvalidWeight(0.0)));
+}
@@ -0,0 +1,49 @@
+use std::thread;
+
+use superdsp::objects::ring_buffer::ring_buffer;
+
+#[test]
+fn test_ring_buffer_across_threads() {
+    let (mut producer, mut consumer) = ring_buffer(4);
+
+    let writer = thread::spawn(move || {
+        for i in 0..10_000 {
+            let mut item = i;
+            while let Err(rejected) = producer.push(item) {
+                item = rejected;
+                thread::yield_now();
+            }
+        }
+    });
+
+    let mut expected = 0;
+    while expected < 10_000 {
+        match consumer.pop() {
+            Some(item) => {
+                assert_eq!(item, expected);
+                expected += 1;
+            }
+            None => thread::yield_now(),
+        }
+    }
+
+    writer.join().unwrap();
+    assert!(consumer.is_empty());
+}
+
+#[test]
+fn test_ring_buffer_full() {
+    let (mut producer, mut consumer) = ring_buffer(2);
+
+    assert_eq!(producer.push(1), Ok(()));
+    assert_eq!(producer.push(2), Ok(()));
+    assert_eq!(producer.push(3), Err(3));
+    assert_eq!(producer.free(), 0);
+
+    assert_eq!(consumer.pop(), Some(1));
+    assert_eq!(producer.free(), 1);
+    assert_eq!(producer.push(3), Ok(()));
+    assert_eq!(consumer.pop(), Some(2));
+    assert_eq!(consumer.pop(), Some(3));
+    assert_eq!(consumer.pop(), None);
+}
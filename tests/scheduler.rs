@@ -0,0 +1,137 @@
+#![cfg(feature = "multithreading-std")]
+
+use std::thread;
+
+use num::Complex;
+
+use superdsp::objects::add::Add;
+use superdsp::objects::deinterleave::Deinterleave;
+use superdsp::objects::flowgraph::{Flowgraph, FlowgraphError};
+use superdsp::objects::interleave::Interleave;
+use superdsp::objects::object::{DSPObject, Samples, Type};
+use superdsp::objects::scheduler::Scheduler;
+use superdsp::objects::vector_sink::VectorSink;
+use superdsp::objects::vector_source::VectorSource;
+use superdsp::objects::wave_gen::WaveStepGen;
+
+#[test]
+fn test_scheduler_matches_single_threaded() {
+    let a: Vec<f64> = (0..5000).map(|x| x as f64).collect();
+    let b: Vec<f64> = (0..5000).map(|x| -3.0 * x as f64).collect();
+    let expected: Vec<f64> = a.iter().zip(b.iter()).map(|(a, b)| a + b).collect();
+
+    for threads in [1, 2, 3, 8] {
+        let sum = VectorSink::new(Type::F64);
+        let copy = VectorSink::new(Type::F64);
+
+        let mut flowgraph = Flowgraph::new();
+        let src_a = flowgraph.add(VectorSource::new(Samples::F64(a.clone()), 64, false));
+        let src_b = flowgraph.add(VectorSource::new(Samples::F64(b.clone()), 37, false));
+        let add = flowgraph.add(Add::new(Type::F64, 2));
+        let sum_block = flowgraph.add(sum.clone());
+        let copy_block = flowgraph.add(copy.clone());
+
+        flowgraph.connect(src_a.output(0), add.input(0));
+        flowgraph.connect(src_b.output(0), add.input(1));
+        flowgraph.connect(add.output(0), sum_block.input(0));
+        flowgraph.connect(src_a.output(0), copy_block.input(0));
+        Scheduler::new(threads, 2).run(&flowgraph).unwrap();
+
+        assert_eq!(sum.samples(), Samples::F64(expected.clone()));
+        assert_eq!(copy.samples(), Samples::F64(a.clone()));
+    }
+}
+
+#[test]
+fn test_scheduler_interleave_round_trip() {
+    let data: Vec<Complex<f64>> = (0..999).map(|x| Complex::new(x as f64, 1.0)).collect();
+    let sink = VectorSink::new(Type::Complex);
+
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(VectorSource::new(Samples::Complex(data.clone()), 30, false));
+    let deinterleave = flowgraph.add(Deinterleave::new(Type::Complex, 3));
+    let interleave = flowgraph.add(Interleave::new(Type::Complex, 3));
+    let dst = flowgraph.add(sink.clone());
+
+    flowgraph.connect(src.output(0), deinterleave.input(0));
+    for port in 0..3 {
+        flowgraph.connect(deinterleave.output(port), interleave.input(port));
+    }
+    flowgraph.connect(interleave.output(0), dst.input(0));
+    Scheduler::new(4, 1).run(&flowgraph).unwrap();
+
+    assert_eq!(sink.samples(), Samples::Complex(data));
+}
+
+/// A sink that only gets through one block at a time
+#[derive(Clone)]
+struct SlowSink {
+    sink: VectorSink,
+}
+
+impl DSPObject for SlowSink {
+    fn input_types(&self) -> Vec<Type> {
+        vec![Type::F64]
+    }
+
+    fn output_types(&self) -> Vec<Type> {
+        vec![]
+    }
+
+    fn work(&mut self, inputs: &[Samples], outputs: &mut [Samples]) {
+        thread::sleep(std::time::Duration::from_millis(1));
+        self.sink.work(inputs, outputs);
+    }
+}
+
+#[test]
+fn test_scheduler_slow_sink_does_not_lose_samples() {
+    let data: Vec<f64> = (0..2000).map(|x| x as f64).collect();
+    let slow = VectorSink::new(Type::F64);
+    let fast = VectorSink::new(Type::F64);
+
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(VectorSource::new(Samples::F64(data.clone()), 100, false));
+    let slow_block = flowgraph.add(SlowSink { sink: slow.clone() });
+    let fast_block = flowgraph.add(fast.clone());
+
+    flowgraph.connect(src.output(0), slow_block.input(0));
+    flowgraph.connect(src.output(0), fast_block.input(0));
+    Scheduler::new(3, 2).run(&flowgraph).unwrap();
+
+    assert_eq!(slow.samples(), Samples::F64(data.clone()));
+    assert_eq!(fast.samples(), Samples::F64(data));
+}
+
+#[test]
+fn test_scheduler_starved_input_stays_bounded() {
+    let b: Vec<f64> = (0..1000).map(|x| x as f64).collect();
+    let sum = VectorSink::new(Type::F64);
+
+    // The repeating source never runs out, so the adder must stop taking from it while the other
+    // input has nothing, and everything must stop once the finite source is done
+    let mut flowgraph = Flowgraph::new();
+    let src_a = flowgraph.add(VectorSource::new(Samples::F64(vec![1.0; 64]), 64, true));
+    let src_b = flowgraph.add(VectorSource::new(Samples::F64(b.clone()), 37, false));
+    let add = flowgraph.add(Add::new(Type::F64, 2));
+    let dst = flowgraph.add(sum.clone());
+
+    flowgraph.connect(src_a.output(0), add.input(0));
+    flowgraph.connect(src_b.output(0), add.input(1));
+    flowgraph.connect(add.output(0), dst.input(0));
+    Scheduler::new(2, 4).run(&flowgraph).unwrap();
+
+    let expected: Vec<f64> = b.iter().map(|x| x + 1.0).collect();
+    assert_eq!(sum.samples(), Samples::F64(expected));
+    assert!(flowgraph.node(add).unwrap().lock().pending(0) <= 2 * 64);
+}
+
+#[test]
+fn test_scheduler_validates_graph() {
+    let mut flowgraph = Flowgraph::new();
+    let src = flowgraph.add(WaveStepGen::new(1.0, 1.0, 0.0, 8.0, 16));
+    let sink = flowgraph.add(VectorSink::new(Type::Complex));
+    flowgraph.connect(src.output(0), sink.input(0));
+
+    assert!(matches!(Scheduler::new(2, 4).run(&flowgraph), Err(FlowgraphError::TypeMismatch { .. })));
+}
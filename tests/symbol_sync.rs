@@ -0,0 +1,97 @@
+mod common;
+
+use core::f64::consts::FRAC_1_SQRT_2;
+
+use num::Complex;
+
+use common::{work, work_all, Noise};
+use superdsp::math::filter::root_raised_cosine;
+use superdsp::objects::fir_filter::FirFilter;
+use superdsp::objects::fractional_resampler::{FractionalResampler, Interpolation};
+use superdsp::objects::object::{Samples, Type};
+use superdsp::objects::symbol_sync::{SymbolSync, TimingDetector};
+
+const SAMPLES_PER_SYMBOL: usize = 4;
+const SAMPLE_RATE: f64 = 40000.0;
+
+/// QPSK symbols shaped with a root raised cosine, sent through a channel whose clock runs
+/// `ratio` times faster, which stretches every symbol, and then matched filtered
+fn channel(symbols: &[Complex<f64>], ratio: f64, noise: &mut Noise) -> Vec<Complex<f64>> {
+    let taps = root_raised_cosine(SAMPLES_PER_SYMBOL, 0.35, 8).unwrap();
+    let gain = libm::sqrt(SAMPLES_PER_SYMBOL as f64);
+
+    let mut upsampled = vec![Complex::new(0.0, 0.0); symbols.len() * SAMPLES_PER_SYMBOL];
+    for (i, symbol) in symbols.iter().enumerate() {
+        upsampled[i * SAMPLES_PER_SYMBOL] = symbol * gain;
+    }
+
+    let shaped = work(&mut FirFilter::new(Type::Complex, taps.clone()), Samples::Complex(upsampled));
+    let resampled = work(&mut FractionalResampler::new(Type::Complex, ratio, Interpolation::Farrow), shaped);
+    let noisy: Vec<Complex<f64>> = resampled.as_complex().iter().map(|x| x + noise.complex(0.05)).collect();
+
+    let matched = work(&mut FirFilter::new(Type::Complex, taps.iter().map(|tap| tap / gain).collect()), Samples::Complex(noisy));
+    matched.as_complex().to_vec()
+}
+
+fn check_detector(detector: TimingDetector, amplitude: f64) {
+    let mut noise = Noise::new(3);
+    let symbols: Vec<Complex<f64>> = (0..6000).map(|_| {
+        Complex::new(noise.below(2) as f64 * 2.0 - 1.0, noise.below(2) as f64 * 2.0 - 1.0) * FRAC_1_SQRT_2
+    }).collect();
+    let received: Vec<Complex<f64>> = channel(&symbols, 1.0005, &mut noise).iter().map(|x| x * amplitude).collect();
+
+    let mut sync = SymbolSync::new(detector, SAMPLES_PER_SYMBOL as f64, 50.0, FRAC_1_SQRT_2, SAMPLE_RATE);
+    let mut recovered = Vec::new();
+    let mut errors = Vec::new();
+    let mut periods = Vec::new();
+    for block in received.chunks(997) {
+        let outputs = work_all(&mut sync, Samples::Complex(block.to_vec()));
+        recovered.extend_from_slice(outputs[0].as_complex());
+        errors.extend_from_slice(outputs[1].as_f64());
+        periods.push(sync.period());
+    }
+
+    // One output per symbol, with the period following the slower clock
+    assert_eq!(recovered.len(), errors.len());
+    assert!((recovered.len() as f64 - 6000.0).abs() < 2.0, "{:?} produced {} symbols", detector, recovered.len());
+    let period = periods[periods.len() / 2..].iter().sum::<f64>() / (periods.len() - periods.len() / 2) as f64;
+    assert!((period - SAMPLES_PER_SYMBOL as f64 * 1.0005).abs() < 5e-4, "{:?} period {}", detector, period);
+
+    // Every symbol after the loop has settled decides to what was sent, a fixed delay later
+    let decide = |x: &Complex<f64>| (x.re > 0.0, x.im > 0.0);
+    let delay = (0..20).find(|delay| (2000..2100).all(|k| decide(&recovered[k]) == decide(&symbols[k - delay]))).expect("no alignment found");
+    let wrong = (2000..recovered.len() - 10).filter(|k| decide(&recovered[*k]) != decide(&symbols[k - delay])).count();
+    assert_eq!(wrong, 0, "{:?} got {} symbols wrong", detector, wrong);
+
+    // The recovered symbols sit close to the constellation points
+    let spread = recovered[2000..].iter().map(|x| x / amplitude).map(|x| (x.re.abs() - FRAC_1_SQRT_2).abs().max((x.im.abs() - FRAC_1_SQRT_2).abs())).fold(0.0, f64::max);
+    assert!(spread < 0.2, "{:?} spread {}", detector, spread);
+
+    let mean_error = errors[2000..].iter().sum::<f64>() / (errors.len() - 2000) as f64;
+    assert!(mean_error.abs() < 0.01, "{:?} mean timing error {}", detector, mean_error);
+}
+
+#[test]
+fn test_symbol_sync_gardner() {
+    check_detector(TimingDetector::Gardner, 1.0);
+}
+
+#[test]
+fn test_symbol_sync_mueller_muller() {
+    check_detector(TimingDetector::MuellerMuller, 1.0);
+}
+
+#[test]
+fn test_symbol_sync_zero_crossing() {
+    check_detector(TimingDetector::ZeroCrossing, 1.0);
+}
+
+#[test]
+fn test_symbol_sync_signal_level() {
+    // The loop behaves the same well above and below unit amplitude
+    for amplitude in [0.1, 10.0] {
+        check_detector(TimingDetector::Gardner, amplitude);
+        check_detector(TimingDetector::MuellerMuller, amplitude);
+        check_detector(TimingDetector::ZeroCrossing, amplitude);
+    }
+}
@@ -0,0 +1,101 @@
+use core::f64::consts::PI;
+use num::Complex;
+
+use superdsp::math::fourier::fft;
+use superdsp::math::window::{bessel_i0, coherent_gain, enbw, Window};
+
+fn assert_close(a: &[f64], b: &[f64]) {
+    assert_eq!(a.len(), b.len());
+    for (x, y) in a.iter().zip(b.iter()) {
+        assert!((x - y).abs() < 1e-9, "{:?} != {:?}", a, b);
+    }
+}
+
+#[test]
+fn test_window_values() {
+    assert_close(&Window::Hann.symmetric(5), &[0.0, 0.5, 1.0, 0.5, 0.0]);
+    assert_close(&Window::Hann.periodic(4), &[0.0, 0.5, 1.0, 0.5]);
+    assert_close(&Window::Hamming.symmetric(3), &[0.08, 1.0, 0.08]);
+    assert_close(&Window::Blackman.symmetric(3), &[0.0, 1.0, 0.0]);
+    assert_close(&Window::Rectangular.symmetric(4), &[1.0; 4]);
+    assert_close(&Window::Hann.symmetric(1), &[1.0]);
+
+    // Special cases of the parametric windows
+    assert_close(&Window::Kaiser(0.0).symmetric(8), &[1.0; 8]);
+    assert_close(&Window::Tukey(0.0).symmetric(8), &[1.0; 8]);
+    assert_close(&Window::Tukey(1.0).symmetric(9), &Window::Hann.symmetric(9));
+
+    let kaiser = Window::Kaiser(8.6).symmetric(11);
+    assert!((kaiser[0] - 1.0 / bessel_i0(8.6)).abs() < 1e-12);
+    assert!((kaiser[5] - 1.0).abs() < 1e-12);
+
+    let gaussian = Window::Gaussian(0.4).symmetric(11);
+    assert!((gaussian[0] - libm::exp(-0.5 / 0.16)).abs() < 1e-12);
+
+    // Symmetric windows mirror around the middle
+    for window in [Window::BlackmanHarris, Window::FlatTop, Window::Kaiser(5.0), Window::Gaussian(0.3), Window::Tukey(0.5)] {
+        let w = window.symmetric(33);
+        for k in 0..33 {
+            assert!((w[k] - w[32 - k]).abs() < 1e-12, "{:?}", window);
+        }
+    }
+}
+
+#[test]
+fn test_bessel_i0() {
+    assert_eq!(bessel_i0(0.0), 1.0);
+    assert!((bessel_i0(1.0) - 1.2660658777520082).abs() < 1e-12);
+    assert!((bessel_i0(10.0) - 2815.716628466254).abs() < 1e-8);
+}
+
+#[test]
+fn test_window_gain_and_enbw() {
+    let n = 4096;
+    let expected = [
+        (Window::Rectangular, 1.0, 1.0),
+        (Window::Hann, 0.5, 1.5),
+        (Window::Hamming, 0.54, 1.3628),
+        (Window::Blackman, 0.42, 1.7268),
+        (Window::BlackmanHarris, 0.35875, 2.0044),
+        (Window::FlatTop, 0.21557895, 3.7702),
+    ];
+
+    for (window, gain, bandwidth) in expected {
+        assert!((window.coherent_gain(n) - gain).abs() < 1e-6, "{:?}", window);
+        assert!((window.enbw(n) - bandwidth).abs() < 1e-3, "{:?} {}", window, window.enbw(n));
+    }
+
+    let w = Window::Kaiser(6.0).periodic(n);
+    assert_eq!(coherent_gain(&w), Window::Kaiser(6.0).coherent_gain(n));
+    assert_eq!(enbw(&w), Window::Kaiser(6.0).enbw(n));
+}
+
+#[test]
+fn test_window_reduces_leakage() {
+    let n = 256;
+
+    // A tone half way between two bins leaks the most
+    let leakage = |window: Window| {
+        let w = window.periodic(n);
+        let mut data: Vec<Complex<f64>> = (0..n).map(|i| {
+            let theta = 2.0 * PI * 20.5 * i as f64 / n as f64;
+            Complex::new(libm::cos(theta), libm::sin(theta)) * w[i]
+        }).collect();
+        fft(&mut data);
+
+        // Worst bin outside the main lobe of every window here
+        let peak = data.iter().map(|x| x.norm_sqr()).fold(0.0, f64::max);
+        let worst = data.iter().enumerate()
+            .filter(|(bin, _)| (*bin as f64 - 20.5).abs() > 6.0 && (*bin as f64 - 20.5 - n as f64).abs() > 6.0)
+            .map(|(_, x)| x.norm_sqr())
+            .fold(0.0, f64::max);
+        worst / peak
+    };
+
+    let rectangular = leakage(Window::Rectangular);
+    let hann = leakage(Window::Hann);
+    let blackman_harris = leakage(Window::BlackmanHarris);
+
+    assert!(hann < rectangular * 1e-3);
+    assert!(blackman_harris < hann * 1e-3);
+}